@@ -1,22 +1,40 @@
 use std::{thread, time};
+use std::convert::TryInto;
 use std::fs::File;
 use std::io::Write;
 use std::mem::size_of;
 use std::str::FromStr;
 
-use anyhow::Result;
-use chrono::NaiveDateTime;
+use anyhow::{anyhow, Result};
+use chrono::{NaiveDateTime, Utc};
 use clap::Clap;
 use client::utils::{Cluster, create_account_instr, create_and_init_mint_instr,
                     create_signer_key_and_nonce, create_token_account_instr, get_account,
                     mnemonic_to_keypair, read_keypair_file, send_instructions};
-use omega::instruction::{init_omega_contract, resolve};
-use omega::state::{DETAILS_BUFFER_LEN, OmegaContract, Loadable};
+use client::allow_list::{allow_list_root, read_allow_list_csv};
+use client::attestation::{sign_attestation, verify_attestation};
+use client::registry::list_contracts;
+use client::reputation::get_oracle_stats;
+use client::resolution::get_resolution;
+use client::stats::get_market_stats;
+use omega::instruction::{apply_config, cast_vote, close_contract, commit_resolution, commitment_hash,
+                         create_series_contract, finalize_vote, find_program_config_address,
+                         find_series_contract_address, forfeit_creator_bond, init_bucket_group, init_market_series, init_omega_contract, init_oracle_bond, init_oracle_fee,
+                         init_oracle_liveness, init_oracle_reputation, init_price_resolution, init_program_config,
+                         init_registry, init_vote_resolution, oracle_heartbeat, overturn_resolution, propose_config,
+                         record_oracle_reputation, release_creator_bond, release_oracle_bond, resolve,
+                         resolve_from_price, resolve_late, resolve_value, resolve_with_attestation, reveal_resolution,
+                         set_allow_list, set_issuance_caps, slash_oracle_bond, with_creator_bond, with_oracle_fee,
+                         with_oracle_reputation, withdraw_oracle_fee, withdraw_vote};
+use omega::price::PriceSourceKind;
+use omega::state::{BondStatus, Comparison, DETAILS_BUFFER_LEN, MarketSeries, MAX_OUTCOMES, OmegaContract, Loadable,
+                   ProgramConfig, Registry, TieBreak};
 use serde_json::{json, Value};
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::hash::{hash, Hash};
 use solana_sdk::pubkey::Pubkey;
-use solana_sdk::signature::{Keypair, Signer, write_keypair_file};
+use solana_sdk::signature::{Keypair, Signature, Signer, write_keypair_file};
 use spl_token::state::Mint;
 
 #[derive(Clap, Debug)]
@@ -53,8 +71,91 @@ pub enum Command {
         #[clap(long)]
         auto_exp_time: String,
         #[clap(long)]
+        icon_urls: Option<Vec<String>>,
+        /// Registry of the payer the contract is listed in
+        #[clap(long)]
+        registry: String,
+        /// Quote tokens the payer funds as a reward for the oracle
+        #[clap(long)]
+        oracle_fee: Option<u64>,
+        /// Pay the reward as basis points of open interest, capped at --oracle-fee.
+        /// 0 keeps the program config's default rate
+        #[clap(long, default_value = "0")]
+        oracle_fee_bps: u64,
+        /// The reward shrinks to 0 at this time
+        #[clap(long)]
+        oracle_fee_deadline: Option<String>,
+        /// Quote token account the reward is funded from
+        #[clap(long)]
+        funder_quote_acc: Option<String>,
+        /// Quote token account paying the creator bond, if the program config sets one
+        #[clap(long)]
+        creator_quote_acc: Option<String>,
+    },
+    InitBucketGroup {
+        #[clap(long, short)]
+        payer: String,
+        #[clap(long)]
+        omega_program_id: String,
+        #[clap(long, short)]
+        oracle_keypair: String,
+        #[clap(long, short)]
+        quote_mint: String,
+        #[clap(long, short)]
+        contract_keys_paths: Vec<String>,
+        #[clap(long)]
+        contract_name: String,
+        #[clap(long, short)]
+        breakpoints: Vec<i64>,
+        #[clap(long)]
+        details: String,
+        #[clap(long)]
+        exp_time: String,
+        #[clap(long)]
+        auto_exp_time: String,
+        #[clap(long)]
+        icon_urls: Option<Vec<String>>,
+        /// Registry of the payer the contract is listed in
+        #[clap(long)]
+        registry: String,
+        /// Quote token account paying the creator bond, if the program config sets one
+        #[clap(long)]
+        creator_quote_acc: Option<String>,
+    },
+    InitMarketSeries {
+        #[clap(long, short)]
+        payer: String,
+        #[clap(long)]
+        omega_program_id: String,
+        #[clap(long)]
+        oracle: String,
+        #[clap(long, short)]
+        quote_mint: String,
+        #[clap(long, short)]
+        series_keys_path: String,
+        #[clap(long)]
+        series_name: String,
+        #[clap(long)]
+        outcome_names: Vec<String>,
+        #[clap(long)]
+        details: String,
+        #[clap(long)]
+        first_exp_time: String,
+        #[clap(long)]
+        period: u64,
+        #[clap(long)]
+        resolve_window: u64,
+        #[clap(long)]
         icon_urls: Option<Vec<String>>
     },
+    CreateSeriesContract {
+        #[clap(long, short)]
+        payer: String,
+        #[clap(long, short)]
+        series_keys_path: String,
+        #[clap(long, short)]
+        contract_keys_paths: Vec<String>,
+    },
     IssueSet {
         #[clap(long, short, default_value="~/.config/solana/id.json")]
         payer: String,
@@ -72,6 +173,273 @@ pub enum Command {
         oracle_keypair: String,
         #[clap(long, short)]
         winner: String,
+        #[clap(long, default_value = "")]
+        evidence_uri: String,
+        /// Local copy of the evidence; its hash is stored on the contract
+        #[clap(long)]
+        evidence_file: Option<String>,
+        /// Quote token account that receives the oracle fee, if the contract has one
+        #[clap(long)]
+        oracle_quote_acc: Option<String>,
+    },
+    WithdrawOracleFee {
+        #[clap(long, short)]
+        payer: String,
+        #[clap(long, short)]
+        contract_keys_path: String,
+        /// Quote token account of the funder
+        #[clap(long)]
+        funder_quote_acc: String,
+    },
+    ShowResolution {
+        #[clap(long, short)]
+        contract_keys_path: String,
+    },
+    /// Print the open interest and activity of a contract
+    ShowStats {
+        #[clap(long, short)]
+        contract_keys_path: String,
+    },
+    PostOracleBond {
+        #[clap(long, short)]
+        payer: String,
+        #[clap(long, short)]
+        contract_keys_path: String,
+        #[clap(long, short)]
+        oracle_keypair: String,
+        /// Quote token account the bond is paid from
+        #[clap(long)]
+        oracle_quote_acc: String,
+        #[clap(long)]
+        amount: u64,
+        /// Seconds after resolution during which the winner can be overturned
+        #[clap(long)]
+        dispute_window: u64,
+    },
+    ReleaseOracleBond {
+        #[clap(long, short)]
+        payer: String,
+        #[clap(long, short)]
+        contract_keys_path: String,
+        /// Quote token account of the oracle that receives the bond
+        #[clap(long)]
+        oracle_quote_acc: String,
+    },
+    SlashOracleBond {
+        #[clap(long, short)]
+        payer: String,
+        #[clap(long, short)]
+        contract_keys_path: String,
+    },
+    OverturnResolution {
+        #[clap(long, short)]
+        payer: String,
+        #[clap(long, short)]
+        contract_keys_path: String,
+        #[clap(long, short)]
+        admin_keypair: String,
+        #[clap(long, short)]
+        winner: String,
+    },
+    ResolveLate {
+        #[clap(long, short)]
+        payer: String,
+        #[clap(long, short)]
+        contract_keys_path: String,
+        #[clap(long, short)]
+        admin_keypair: String,
+        #[clap(long, short)]
+        winner: String,
+    },
+    InitOracleLiveness {
+        #[clap(long, short)]
+        payer: String,
+        #[clap(long, short)]
+        contract_keys_path: String,
+        #[clap(long, short)]
+        oracle_keypair: String,
+        #[clap(long)]
+        backup_oracle: String,
+        /// Max seconds between two heartbeats before the backup oracle takes over
+        #[clap(long)]
+        heartbeat_period: u64,
+    },
+    /// Send a heartbeat for every unresolved contract. Meant to be run from cron
+    OracleHeartbeat {
+        #[clap(long, short)]
+        payer: String,
+        #[clap(long, short)]
+        oracle_keypair: String,
+        #[clap(long, short)]
+        contract_keys_paths: Vec<String>,
+    },
+    InitOracleReputation {
+        #[clap(long, short)]
+        payer: String,
+        #[clap(long)]
+        omega_program_id: String,
+        #[clap(long)]
+        oracle: String,
+    },
+    /// Count what happened on a contract in the reputation of its oracle
+    RecordOracleReputation {
+        #[clap(long, short)]
+        payer: String,
+        #[clap(long, short)]
+        contract_keys_path: String,
+    },
+    OracleStats {
+        #[clap(long)]
+        omega_program_id: String,
+        #[clap(long)]
+        oracle: String,
+    },
+    /// Return the creator bond once the contract resolved and the dispute window passed
+    ReleaseCreatorBond {
+        #[clap(long, short)]
+        payer: String,
+        #[clap(long, short)]
+        contract_keys_path: String,
+        /// Quote token account of the creator that receives the bond
+        #[clap(long)]
+        creator_quote_acc: String,
+    },
+    /// Pay the creator bond to the treasury. Needs the admin unless the contract was resolved invalid
+    ForfeitCreatorBond {
+        #[clap(long, short)]
+        payer: String,
+        #[clap(long, short)]
+        contract_keys_path: String,
+        #[clap(long, short)]
+        admin_keypair: Option<String>,
+        /// Quote token account of the treasury
+        #[clap(long)]
+        treasury_quote_acc: String,
+    },
+    /// Only let the pubkeys listed in a CSV issue sets of a contract. Without a CSV the contract is opened up again
+    SetAllowList {
+        #[clap(long, short)]
+        payer: String,
+        #[clap(long, short)]
+        contract_keys_path: String,
+        #[clap(long, short)]
+        admin_keypair: String,
+        /// Pubkeys in the first column, one per line
+        #[clap(long)]
+        csv: Option<String>,
+    },
+    /// Cap the sets outstanding on a contract and the sets each user can issue. 0 removes a cap
+    SetIssuanceCaps {
+        #[clap(long, short)]
+        payer: String,
+        #[clap(long, short)]
+        contract_keys_path: String,
+        #[clap(long, short)]
+        admin_keypair: String,
+        #[clap(long, default_value = "0")]
+        max_supply: u64,
+        #[clap(long, default_value = "0")]
+        wallet_cap: u64,
+    },
+    /// Hand resolution to the holders of a governance mint, who vote after exp_time
+    InitVoteResolution {
+        #[clap(long, short)]
+        payer: String,
+        #[clap(long, short)]
+        contract_keys_path: String,
+        #[clap(long, short)]
+        oracle_keypair: String,
+        #[clap(long)]
+        gov_mint: String,
+        /// Seconds after exp_time during which holders can vote
+        #[clap(long)]
+        voting_period: u64,
+        /// Min governance tokens voted in total
+        #[clap(long)]
+        quorum: u64,
+        #[clap(long, possible_values = &["none", "lowest", "designated"])]
+        tie_break: String,
+        /// Outcome name winning ties with `--tie-break designated`
+        #[clap(long)]
+        tie_break_outcome: Option<String>,
+    },
+    CastVote {
+        #[clap(long, short)]
+        contract_keys_path: String,
+        #[clap(long, short)]
+        voter_keypair: String,
+        /// Governance token account the votes are paid from
+        #[clap(long)]
+        voter_gov_acc: String,
+        #[clap(long)]
+        outcome: String,
+        #[clap(long)]
+        amount: u64,
+    },
+    FinalizeVote {
+        #[clap(long, short)]
+        payer: String,
+        #[clap(long, short)]
+        contract_keys_path: String,
+    },
+    WithdrawVote {
+        #[clap(long, short)]
+        contract_keys_path: String,
+        #[clap(long, short)]
+        voter_keypair: String,
+        /// Governance token account that receives the votes back
+        #[clap(long)]
+        voter_gov_acc: String,
+    },
+    CommitResolution {
+        #[clap(long, short)]
+        payer: String,
+        #[clap(long, short)]
+        contract_keys_path: String,
+        #[clap(long, short)]
+        oracle_keypair: String,
+        #[clap(long, short)]
+        winner: String,
+        #[clap(long)]
+        reveal_window: u64,
+        #[clap(long, short)]
+        salt_path: String,
+    },
+    RevealResolution {
+        #[clap(long, short)]
+        payer: String,
+        #[clap(long, short)]
+        contract_keys_path: String,
+        #[clap(long, short)]
+        salt_path: String,
+    },
+    Attest {
+        #[clap(long, short)]
+        contract_keys_path: String,
+        #[clap(long, short)]
+        oracle_keypair: String,
+        #[clap(long, short)]
+        winner: String,
+        #[clap(long, short)]
+        attestation_path: String,
+    },
+    SubmitAttestation {
+        #[clap(long, short)]
+        payer: String,
+        #[clap(long, short)]
+        contract_keys_path: String,
+        #[clap(long, short)]
+        attestation_path: String,
+    },
+    ResolveValue {
+        #[clap(long, short)]
+        payer: String,
+        #[clap(long, short)]
+        contract_keys_path: String,
+        #[clap(long, short)]
+        oracle_keypair: String,
+        #[clap(long, short, allow_hyphen_values = true)]
+        value: i64,
     },
 
     SolletToLocal {
@@ -97,37 +465,144 @@ pub enum Command {
         #[clap(long, short)]
         filepath: Option<String>,
     },
-
-}
-
-impl Opts {
-    fn client(&self) -> RpcClient {
-        RpcClient::new_with_commitment(self.cluster.url().to_string(), CommitmentConfig::confirmed())
-    }
-}
-
-#[allow(unused_variables)]
-pub fn start(opts: Opts) -> Result<()> {
-    let client = opts.client();
-
-    match opts.command {
-        Command::InitOmegaContract {
-            payer,
-            omega_program_id,
-            oracle,
-            quote_mint,
-            num_outcomes,
-            contract_keys_paths,
-            contract_name,
-            outcome_names,
-            details,
-            exp_time,
-            auto_exp_time,
-            icon_urls
+    InitProgramConfig {
+        #[clap(long, short)]
+        payer: String,
+        #[clap(long)]
+        omega_program_id: String,
+        #[clap(long)]
+        timelock: u64,
+        /// Default oracle fee rate of new contracts, in basis points of open interest
+        #[clap(long, default_value = "0")]
+        fee_bps: u64,
+        /// Quote tokens each contract creator posts
+        #[clap(long, default_value = "0")]
+        creator_bond: u64,
+        /// Owner of the token accounts receiving forfeited creator bonds
+        #[clap(long)]
+        treasury: Option<String>,
+        #[clap(long)]
+        quote_mints: Vec<String>,
+        #[clap(long)]
+        creators: Option<Vec<String>>,
+    },
+    ProposeConfig {
+        #[clap(long, short)]
+        admin: String,
+        #[clap(long)]
+        omega_program_id: String,
+        #[clap(long)]
+        new_admin: Option<String>,
+        #[clap(long)]
+        timelock: u64,
+        /// Default oracle fee rate of new contracts, in basis points of open interest
+        #[clap(long, default_value = "0")]
+        fee_bps: u64,
+        /// Quote tokens each contract creator posts
+        #[clap(long, default_value = "0")]
+        creator_bond: u64,
+        /// Owner of the token accounts receiving forfeited creator bonds
+        #[clap(long)]
+        treasury: Option<String>,
+        #[clap(long)]
+        quote_mints: Vec<String>,
+        #[clap(long)]
+        creators: Option<Vec<String>>,
+    },
+    ApplyConfig {
+        #[clap(long, short)]
+        payer: String,
+        #[clap(long)]
+        omega_program_id: String,
+    },
+    InitPriceResolution {
+        #[clap(long, short)]
+        payer: String,
+        #[clap(long, short)]
+        contract_keys_path: String,
+        #[clap(long, short)]
+        oracle_keypair: String,
+        #[clap(long)]
+        price_account: String,
+        #[clap(long, possible_values = &["simple", "pyth"], default_value = "pyth")]
+        source: String,
+        /// Program that owns the price account. Defaults to its current owner
+        #[clap(long)]
+        price_program: Option<String>,
+        #[clap(long, possible_values = &["gt", "ge", "lt", "le"])]
+        comparison: String,
+        #[clap(long, allow_hyphen_values = true)]
+        threshold: i64,
+        #[clap(long)]
+        max_staleness: u64,
+    },
+    ResolveFromPrice {
+        #[clap(long, short)]
+        payer: String,
+        #[clap(long, short)]
+        contract_keys_path: String,
+    },
+    InitRegistry {
+        #[clap(long, short)]
+        payer: String,
+        #[clap(long)]
+        omega_program_id: String,
+    },
+    ListContracts {
+        #[clap(long, short)]
+        registry: String,
+        #[clap(long)]
+        category: Option<u64>,
+        #[clap(long, default_value = "0")]
+        page: usize,
+        #[clap(long, default_value = "20")]
+        page_size: usize,
+    },
+    /// Close a resolved or auto expired contract listed in the registry of the payer, once its vault is empty
+    CloseContract {
+        #[clap(long, short)]
+        payer: String,
+        #[clap(long, short)]
+        contract_keys_path: String,
+        #[clap(long)]
+        registry: String,
+    },
+
+}
+
+impl Opts {
+    fn client(&self) -> RpcClient {
+        RpcClient::new_with_commitment(self.cluster.url().to_string(), CommitmentConfig::confirmed())
+    }
+}
+
+#[allow(unused_variables)]
+pub fn start(opts: Opts) -> Result<()> {
+    let client = opts.client();
+
+    match opts.command {
+        Command::InitOmegaContract {
+            payer,
+            omega_program_id,
+            oracle,
+            quote_mint,
+            num_outcomes,
+            contract_keys_paths,
+            contract_name,
+            outcome_names,
+            details,
+            exp_time,
+            auto_exp_time,
+            icon_urls,
+            registry,
+            oracle_fee,
+            oracle_fee_bps,
+            oracle_fee_deadline,
+            funder_quote_acc,
+            creator_quote_acc
         } => {
             println!("InitOmegaContract");
             assert_eq!(num_outcomes, outcome_names.len());
-            assert!(details.len() <= DETAILS_BUFFER_LEN);
 
             let icon_urls: Vec<String> = match icon_urls {
                 None => { vec![
@@ -136,27 +611,208 @@ pub fn start(opts: Opts) -> Result<()> {
                 ] }
                 Some(v) => v
             };
-            assert_eq!(icon_urls.len(), num_outcomes);
             let payer = read_keypair_file(payer.as_str())?;
             let omega_program_id = Pubkey::from_str(omega_program_id.as_str())?;
             let oracle_pk = Pubkey::from_str(oracle.as_str())?;
             let quote_mint_pk = Pubkey::from_str(quote_mint.as_str())?;
-            let mut instructions = vec![];
-            let mut signers = vec![];
+            let registry_pk = Pubkey::from_str(registry.as_str())?;
+            let creator_quote_pk = match creator_quote_acc {
+                None => None,
+                Some(creator_quote_acc) => Some(Pubkey::from_str(creator_quote_acc.as_str())?)
+            };
 
-            let omega_contract_kp = Keypair::new();
-            let omega_contract_pk = omega_contract_kp.pubkey();
-            instructions.push(create_account_instr(
-                &client, &payer, &omega_contract_kp,
-                size_of::<OmegaContract>(), &omega_program_id
-            )?);
-            signers.push(&payer);
-            signers.push(&omega_contract_kp);
+            let (_, contract_keys) = create_omega_contract(
+                &client,
+                &payer,
+                &omega_program_id,
+                &oracle_pk,
+                &quote_mint_pk,
+                contract_name.as_str(),
+                &outcome_names,
+                &icon_urls,
+                details.as_str(),
+                exp_time.as_str(),
+                auto_exp_time.as_str(),
+                &registry_pk,
+                creator_quote_pk.as_ref()
+            )?;
+            write_contract_keys(&contract_keys, &contract_keys_paths)?;
+
+            if let Some(amount) = oracle_fee {
+                let deadline = oracle_fee_deadline.unwrap_or(auto_exp_time);
+                let deadline = NaiveDateTime::parse_from_str(deadline.as_str(), "%Y-%m-%d %H:%M:%S")?;
+                let funder_quote_pk = Pubkey::from_str(funder_quote_acc.unwrap().as_str())?;
+                let omega_contract_pk = Pubkey::from_str(contract_keys["omega_contract_pk"].as_str().unwrap())?;
+                let vault_pk = Pubkey::from_str(contract_keys["quote_vault_pk"].as_str().unwrap())?;
+                let instruction = init_oracle_fee(
+                    &omega_program_id,
+                    &omega_contract_pk,
+                    &payer.pubkey(),
+                    &funder_quote_pk,
+                    &vault_pk,
+                    amount,
+                    oracle_fee_bps,
+                    deadline.timestamp() as u64
+                )?;
+                send_instructions(&client, vec![instruction], vec![&payer], &payer.pubkey())?;
+            }
+        }
+        Command::InitBucketGroup {
+            payer,
+            omega_program_id,
+            oracle_keypair,
+            quote_mint,
+            contract_keys_paths,
+            contract_name,
+            breakpoints,
+            details,
+            exp_time,
+            auto_exp_time,
+            icon_urls,
+            registry,
+            creator_quote_acc
+        } => {
+            println!("InitBucketGroup");
+            assert!(!breakpoints.is_empty() && breakpoints.len() < MAX_OUTCOMES);
+
+            // One outcome per bucket: below the first breakpoint, between each pair, and from the last one up
+            let mut outcome_names = vec![format!("< {}", breakpoints[0])];
+            for w in breakpoints.windows(2) {
+                outcome_names.push(format!("{} - {}", w[0], w[1]));
+            }
+            outcome_names.push(format!(">= {}", breakpoints[breakpoints.len() - 1]));
+            let icon_urls = icon_urls.unwrap_or(vec![String::new(); outcome_names.len()]);
+
+            let payer = read_keypair_file(payer.as_str())?;
+            let oracle_keypair = read_keypair_file(oracle_keypair.as_str())?;
+            let omega_program_id = Pubkey::from_str(omega_program_id.as_str())?;
+            let quote_mint_pk = Pubkey::from_str(quote_mint.as_str())?;
+            let registry_pk = Pubkey::from_str(registry.as_str())?;
+
+            let (omega_contract_pk, mut contract_keys) = create_omega_contract(
+                &client,
+                &payer,
+                &omega_program_id,
+                &oracle_keypair.pubkey(),
+                &quote_mint_pk,
+                contract_name.as_str(),
+                &outcome_names,
+                &icon_urls,
+                details.as_str(),
+                exp_time.as_str(),
+                auto_exp_time.as_str(),
+                &registry_pk,
+                creator_quote_acc.map(|pk| Pubkey::from_str(pk.as_str())).transpose()?.as_ref()
+            )?;
+
+            let instruction = init_bucket_group(
+                &omega_program_id,
+                &omega_contract_pk,
+                &oracle_keypair.pubkey(),
+                breakpoints.as_slice()
+            )?;
+            let instructions = vec![instruction];
+            let mut signers = vec![&payer];
+            if oracle_keypair != payer {
+                signers.push(&oracle_keypair)
+            }
+            println!("Sending InitBucketGroup instruction");
+            send_instructions(&client, instructions, signers, &payer.pubkey())?;
 
+            contract_keys["breakpoints"] = json!(breakpoints);
+            write_contract_keys(&contract_keys, &contract_keys_paths)?;
+        }
+        Command::InitMarketSeries {
+            payer,
+            omega_program_id,
+            oracle,
+            quote_mint,
+            series_keys_path,
+            series_name,
+            outcome_names,
+            details,
+            first_exp_time,
+            period,
+            resolve_window,
+            icon_urls
+        } => {
+            println!("InitMarketSeries");
+            assert!(details.len() <= DETAILS_BUFFER_LEN);
+            let icon_urls = icon_urls.unwrap_or(vec![String::new(); outcome_names.len()]);
+            assert_eq!(icon_urls.len(), outcome_names.len());
+
+            let payer = read_keypair_file(payer.as_str())?;
+            let omega_program_id = Pubkey::from_str(omega_program_id.as_str())?;
+            let oracle_pk = Pubkey::from_str(oracle.as_str())?;
+            let quote_mint_pk = Pubkey::from_str(quote_mint.as_str())?;
+            let first_exp_time = NaiveDateTime::parse_from_str(first_exp_time.as_str(), "%Y-%m-%d %H:%M:%S")?;
+
+            let market_series_kp = Keypair::new();
+            let market_series_pk = market_series_kp.pubkey();
+            let instructions = vec![
+                create_account_instr(
+                    &client, &payer, &market_series_kp,
+                    size_of::<MarketSeries>(), &omega_program_id
+                )?,
+                init_market_series(
+                    &omega_program_id,
+                    &market_series_pk,
+                    &oracle_pk,
+                    &quote_mint_pk,
+                    &payer.pubkey(),
+                    outcome_names.len() as u64,
+                    first_exp_time.timestamp() as u64,
+                    period,
+                    resolve_window,
+                    details.as_str()
+                )?
+            ];
+            let signers = vec![&payer, &market_series_kp];
+            send_instructions(&client, instructions, signers, &payer.pubkey())?;
+
+            let outcome_infos: Vec<Value> = outcome_names.iter().zip(icon_urls.iter())
+                .map(|(name, icon)| json!({"name": name, "icon": icon}))
+                .collect();
+            let series_keys = json!({
+                "series_name": series_name,
+                "omega_program_id": omega_program_id.to_string(),
+                "market_series_pk": market_series_pk.to_string(),
+                "oracle_pk": oracle_pk.to_string(),
+                "quote_mint_pk": quote_mint_pk.to_string(),
+                "outcomes": outcome_infos,
+            });
+            let f = File::create(series_keys_path.as_str())?;
+            serde_json::to_writer_pretty(&f, &series_keys)?;
+            println!("series keys were written into: {}", series_keys_path);
+        }
+        Command::CreateSeriesContract {
+            payer,
+            series_keys_path,
+            contract_keys_paths
+        } => {
+            println!("CreateSeriesContract");
+
+            let payer = read_keypair_file(payer.as_str())?;
+            let series_keys: Value = serde_json::from_reader(File::open(series_keys_path)?)?;
+            let omega_program_id = Pubkey::from_str(series_keys["omega_program_id"].as_str().unwrap())?;
+            let market_series_pk = Pubkey::from_str(series_keys["market_series_pk"].as_str().unwrap())?;
+            let quote_mint_pk = Pubkey::from_str(series_keys["quote_mint_pk"].as_str().unwrap())?;
+
+            let market_series = client.get_account(&market_series_pk)?;
+            let market_series = MarketSeries::load_from_bytes(market_series.data.as_slice())?;
+            let index = market_series.next_index;
+            let (exp_time, auto_exp_time) = market_series.exp_times(index).unwrap();
+            let details = String::from_utf8(market_series.details_for(index, exp_time)?)?;
+
+            let (omega_contract_pk, contract_nonce) = find_series_contract_address(
+                &omega_program_id, &market_series_pk, index
+            );
             let (signer_key, signer_nonce) = create_signer_key_and_nonce(&omega_program_id, &omega_contract_pk);
 
+            // vault and outcome mints have to exist before the program creates the contract
+            let mut instructions = vec![];
+            let mut signers = vec![];
             let quote_vault_kp = Keypair::new();
-            let quote_vault_pk = quote_vault_kp.pubkey();
             create_token_account_instr(
                 &client,
                 &quote_vault_kp,
@@ -166,16 +822,9 @@ pub fn start(opts: Opts) -> Result<()> {
                 &mut instructions,
                 &mut signers
             )?;
-
             let quote_mint: Mint = get_account(&client, &quote_mint_pk)?;
-            let mut outcome_infos = Vec::<Value>::new();
-            let mut outcome_mint_pks = vec![];
-            let mut outcome_mint_kps = vec![];
-            for i in 0..num_outcomes {
-                outcome_mint_kps.push(Keypair::new());
-            }
-            for i in 0..num_outcomes {
-                let outcome_mint_kp = &outcome_mint_kps[i];
+            let outcome_mint_kps: Vec<Keypair> = (0..market_series.num_outcomes).map(|_| Keypair::new()).collect();
+            for outcome_mint_kp in outcome_mint_kps.iter() {
                 create_and_init_mint_instr(
                     &client,
                     outcome_mint_kp,
@@ -185,82 +834,757 @@ pub fn start(opts: Opts) -> Result<()> {
                     &mut instructions,
                     &mut signers
                 )?;
+            }
+            println!("Sending account creation instructions");
+            send_instructions(&client, instructions, signers, &payer.pubkey())?;
 
-                let outcome_json = json!(
-                    {
-                        "mint_pk": outcome_mint_kp.pubkey().to_string(),
-                        "name": outcome_names[i],
-                        "icon": icon_urls[i].clone()
-                    }
-                );
-                outcome_infos.push(outcome_json);
-                outcome_mint_pks.push(outcome_mint_kp.pubkey());
+            let outcome_mint_pks: Vec<Pubkey> = outcome_mint_kps.iter().map(|kp| kp.pubkey()).collect();
+            let instruction = create_series_contract(
+                &omega_program_id,
+                &market_series_pk,
+                &omega_contract_pk,
+                &payer.pubkey(),
+                &quote_mint_pk,
+                &quote_vault_kp.pubkey(),
+                &signer_key,
+                outcome_mint_pks.as_slice(),
+                contract_nonce,
+                signer_nonce
+            )?;
+            thread::sleep(time::Duration::from_secs(5));
+            println!("Sending CreateSeriesContract instruction");
+            send_instructions(&client, vec![instruction], vec![&payer], &payer.pubkey())?;
+
+            let outcome_infos: Vec<Value> = series_keys["outcomes"].as_array().unwrap().iter()
+                .zip(outcome_mint_pks.iter())
+                .map(|(outcome, mint_pk)| json!({
+                    "mint_pk": mint_pk.to_string(),
+                    "name": outcome["name"],
+                    "icon": outcome["icon"]
+                }))
+                .collect();
+            let contract_keys = json!({
+                "contract_name": format!("{} #{}", series_keys["series_name"].as_str().unwrap(), index),
+                "omega_program_id": omega_program_id.to_string(),
+                "omega_contract_pk": omega_contract_pk.to_string(),
+                "oracle_pk": series_keys["oracle_pk"],
+                "quote_mint_pk": quote_mint_pk.to_string(),
+                "quote_vault_pk": quote_vault_kp.pubkey().to_string(),
+                "signer_pk": signer_key.to_string(),
+                "signer_nonce": signer_nonce,
+                "exp_time": exp_time,
+                "auto_exp_time": auto_exp_time,
+
+                "outcomes": outcome_infos,
+                "details": details
+            });
+            write_contract_keys(&contract_keys, &contract_keys_paths)?;
+        }
+        Command::IssueSet { .. } => {
+            println!("IssueSet");
+            unimplemented!()
+        }
+        Command::Resolve {
+            payer,
+            contract_keys_path,
+            oracle_keypair,
+            winner,
+            evidence_uri,
+            evidence_file,
+            oracle_quote_acc
+        } => {
+            println!("Resolve");
+
+            let payer = read_keypair_file(payer.as_str())?;
+            let oracle_keypair = read_keypair_file(oracle_keypair.as_str())?;
+            let contract_keys: Value = serde_json::from_reader(File::open(contract_keys_path)?)?;
+
+            let outcomes = contract_keys["outcomes"].as_array().unwrap();
+            let outcome = outcomes.iter().find(
+                |v| v["name"].as_str().unwrap() == winner.as_str()
+            );
+            let winner_pk = match outcome {
+                None => Pubkey::from_str(winner.as_str())?,
+                Some(v) => Pubkey::from_str(v["mint_pk"].as_str().unwrap())?
+            };
+            let omega_program_id = Pubkey::from_str(contract_keys["omega_program_id"].as_str().unwrap())?;
+            let omega_contract_pk = Pubkey::from_str(contract_keys["omega_contract_pk"].as_str().unwrap())?;
+
+            println!("{} {} {} {}", omega_program_id.to_string(), omega_contract_pk.to_string(), oracle_keypair.pubkey().to_string(), winner_pk.to_string());
+
+            let contract = client.get_account(&omega_contract_pk)?;
+            let contract = OmegaContract::load_from_bytes(contract.data.as_slice())?;
+            println!("{}", contract.exp_time);
+
+            let evidence_hash = match evidence_file {
+                None => [0u8; 32],
+                Some(path) => hash(&std::fs::read(path)?).to_bytes()
+            };
+
+            let instruction = resolve(
+                &omega_program_id,
+                &omega_contract_pk,
+                &oracle_keypair.pubkey(),
+                &winner_pk,
+                &contract.vault,
+                &contract.outcomes[..contract.num_outcomes],
+                evidence_uri.as_str(),
+                evidence_hash
+            )?;
+            let instruction = if contract.oracle_fee.reserve > 0 {
+                let signer_pk = Pubkey::from_str(contract_keys["signer_pk"].as_str().unwrap())?;
+                let oracle_quote_pk = Pubkey::from_str(oracle_quote_acc.unwrap().as_str())?;
+                with_oracle_fee(instruction, &oracle_quote_pk, &signer_pk)
+            } else {
+                instruction
+            };
+            let instruction = if get_oracle_stats(&client, &omega_program_id, &oracle_keypair.pubkey())?.is_some() {
+                with_oracle_reputation(instruction, &oracle_keypair.pubkey())
+            } else {
+                instruction
+            };
+            let mut signers = vec![&payer];
+            if oracle_keypair != payer {
+                signers.push(&oracle_keypair)
+            }
+            send_instructions(&client, vec![instruction], signers, &payer.pubkey())?;
+
+        }
+        Command::WithdrawOracleFee {
+            payer,
+            contract_keys_path,
+            funder_quote_acc
+        } => {
+            println!("WithdrawOracleFee");
+            let payer = read_keypair_file(payer.as_str())?;
+            let contract_keys: Value = serde_json::from_reader(File::open(contract_keys_path)?)?;
+            let omega_program_id = Pubkey::from_str(contract_keys["omega_program_id"].as_str().unwrap())?;
+            let omega_contract_pk = Pubkey::from_str(contract_keys["omega_contract_pk"].as_str().unwrap())?;
+            let vault_pk = Pubkey::from_str(contract_keys["quote_vault_pk"].as_str().unwrap())?;
+            let signer_pk = Pubkey::from_str(contract_keys["signer_pk"].as_str().unwrap())?;
+            let contract = client.get_account(&omega_contract_pk)?;
+            let contract = OmegaContract::load_from_bytes(contract.data.as_slice())?;
+
+            let instruction = withdraw_oracle_fee(
+                &omega_program_id,
+                &omega_contract_pk,
+                &Pubkey::from_str(funder_quote_acc.as_str())?,
+                &vault_pk,
+                &signer_pk,
+                &contract.outcomes[..contract.num_outcomes]
+            )?;
+            send_instructions(&client, vec![instruction], vec![&payer], &payer.pubkey())?;
+        }
+        Command::ShowResolution {
+            contract_keys_path
+        } => {
+            let contract_keys: Value = serde_json::from_reader(File::open(contract_keys_path)?)?;
+            let omega_contract_pk = Pubkey::from_str(contract_keys["omega_contract_pk"].as_str().unwrap())?;
+
+            match get_resolution(&client, &omega_contract_pk)? {
+                None => println!("{} is not resolved", omega_contract_pk),
+                Some(resolution) => {
+                    let outcome = contract_keys["outcomes"].as_array().unwrap().iter().find(
+                        |v| v["mint_pk"].as_str().unwrap() == resolution.winner.to_string()
+                    );
+                    let winner = outcome.map_or(resolution.winner.to_string(), |v| v["name"].as_str().unwrap().to_string());
+                    let resolved_at = NaiveDateTime::from_timestamp(resolution.timestamp as i64, 0);
+                    println!("winner: {}", winner);
+                    println!("resolved: {} (slot {})", resolved_at, resolution.slot);
+                    println!("evidence uri: {}", resolution.evidence_uri);
+                    println!("evidence hash: {}", Hash::new_from_array(resolution.evidence_hash));
+                }
             }
 
-            // send first transaction because otherwise it's too big
-            println!("Sending account creation instructions");
+            let contract = client.get_account(&omega_contract_pk)?;
+            let contract = OmegaContract::load_from_bytes(contract.data.as_slice())?;
+            if contract.vote.is_enabled() {
+                let voting_end = NaiveDateTime::from_timestamp(contract.vote.voting_end(contract.exp_time) as i64, 0);
+                println!("voting ends: {}", voting_end);
+                println!("votes: {} (quorum {})", contract.vote.total_votes, contract.vote.quorum);
+                for (i, outcome) in contract_keys["outcomes"].as_array().unwrap().iter().enumerate() {
+                    println!("  {}: {}", outcome["name"].as_str().unwrap(), contract.vote.tallies[i]);
+                }
+            }
+        }
+        Command::ShowStats {
+            contract_keys_path
+        } => {
+            let contract_keys: Value = serde_json::from_reader(File::open(contract_keys_path)?)?;
+            let omega_contract_pk = Pubkey::from_str(contract_keys["omega_contract_pk"].as_str().unwrap())?;
+
+            let stats = get_market_stats(&client, &omega_contract_pk)?;
+            if stats.max_supply == 0 {
+                println!("open interest: {}", stats.open_interest);
+            } else {
+                println!("open interest: {} (max {})", stats.open_interest, stats.max_supply);
+            }
+            println!("sets issued: {}", stats.sets_issued);
+            println!("sets redeemed: {}", stats.sets_redeemed);
+            println!("outcomes issued: {}", stats.outcomes_issued);
+            println!("outcomes redeemed: {}", stats.outcomes_redeemed);
+            println!("winner redeemed: {}", stats.winner_redeemed);
+            println!("unique issuers: {}", stats.unique_issuers);
+            if stats.last_activity != 0 {
+                println!("last activity: {}", NaiveDateTime::from_timestamp(stats.last_activity as i64, 0));
+            }
+        }
+        Command::PostOracleBond {
+            payer,
+            contract_keys_path,
+            oracle_keypair,
+            oracle_quote_acc,
+            amount,
+            dispute_window
+        } => {
+            println!("PostOracleBond");
+            let payer = read_keypair_file(payer.as_str())?;
+            let oracle_keypair = read_keypair_file(oracle_keypair.as_str())?;
+            let contract_keys: Value = serde_json::from_reader(File::open(contract_keys_path)?)?;
+            let omega_program_id = Pubkey::from_str(contract_keys["omega_program_id"].as_str().unwrap())?;
+            let omega_contract_pk = Pubkey::from_str(contract_keys["omega_contract_pk"].as_str().unwrap())?;
+            let quote_mint_pk = Pubkey::from_str(contract_keys["quote_mint_pk"].as_str().unwrap())?;
+            let signer_pk = Pubkey::from_str(contract_keys["signer_pk"].as_str().unwrap())?;
+            let oracle_quote_pk = Pubkey::from_str(oracle_quote_acc.as_str())?;
+
+            let mut instructions = vec![];
+            let mut signers = vec![&payer];
+            let escrow_kp = Keypair::new();
+            create_token_account_instr(
+                &client,
+                &escrow_kp,
+                &quote_mint_pk,
+                &signer_pk,
+                &payer,
+                &mut instructions,
+                &mut signers
+            )?;
+            instructions.push(init_oracle_bond(
+                &omega_program_id,
+                &omega_contract_pk,
+                &oracle_keypair.pubkey(),
+                &oracle_quote_pk,
+                &escrow_kp.pubkey(),
+                amount,
+                dispute_window
+            )?);
+            if oracle_keypair != payer {
+                signers.push(&oracle_keypair)
+            }
+            send_instructions(&client, instructions, signers, &payer.pubkey())?;
+            println!("bond escrow: {}", escrow_kp.pubkey());
+        }
+        Command::ReleaseOracleBond {
+            payer,
+            contract_keys_path,
+            oracle_quote_acc
+        } => {
+            println!("ReleaseOracleBond");
+            let payer = read_keypair_file(payer.as_str())?;
+            let contract_keys: Value = serde_json::from_reader(File::open(contract_keys_path)?)?;
+            let omega_program_id = Pubkey::from_str(contract_keys["omega_program_id"].as_str().unwrap())?;
+            let omega_contract_pk = Pubkey::from_str(contract_keys["omega_contract_pk"].as_str().unwrap())?;
+            let signer_pk = Pubkey::from_str(contract_keys["signer_pk"].as_str().unwrap())?;
+
+            let contract = client.get_account(&omega_contract_pk)?;
+            let contract = OmegaContract::load_from_bytes(contract.data.as_slice())?;
+
+            let instruction = release_oracle_bond(
+                &omega_program_id,
+                &omega_contract_pk,
+                &contract.bond.escrow,
+                &Pubkey::from_str(oracle_quote_acc.as_str())?,
+                &signer_pk
+            )?;
+            send_instructions(&client, vec![instruction], vec![&payer], &payer.pubkey())?;
+        }
+        Command::SlashOracleBond {
+            payer,
+            contract_keys_path
+        } => {
+            println!("SlashOracleBond");
+            let payer = read_keypair_file(payer.as_str())?;
+            let contract_keys: Value = serde_json::from_reader(File::open(contract_keys_path)?)?;
+            let omega_program_id = Pubkey::from_str(contract_keys["omega_program_id"].as_str().unwrap())?;
+            let omega_contract_pk = Pubkey::from_str(contract_keys["omega_contract_pk"].as_str().unwrap())?;
+            let signer_pk = Pubkey::from_str(contract_keys["signer_pk"].as_str().unwrap())?;
+
+            let contract = client.get_account(&omega_contract_pk)?;
+            let contract = OmegaContract::load_from_bytes(contract.data.as_slice())?;
+
+            let instruction = slash_oracle_bond(
+                &omega_program_id,
+                &omega_contract_pk,
+                &contract.bond.escrow,
+                &contract.vault,
+                &signer_pk,
+                &contract.outcomes[..contract.num_outcomes]
+            )?;
+            send_instructions(&client, vec![instruction], vec![&payer], &payer.pubkey())?;
+        }
+        Command::OverturnResolution {
+            payer,
+            contract_keys_path,
+            admin_keypair,
+            winner
+        } => {
+            println!("OverturnResolution");
+            let payer = read_keypair_file(payer.as_str())?;
+            let admin_keypair = read_keypair_file(admin_keypair.as_str())?;
+            let contract_keys: Value = serde_json::from_reader(File::open(contract_keys_path)?)?;
+            let omega_program_id = Pubkey::from_str(contract_keys["omega_program_id"].as_str().unwrap())?;
+            let omega_contract_pk = Pubkey::from_str(contract_keys["omega_contract_pk"].as_str().unwrap())?;
+            let signer_pk = Pubkey::from_str(contract_keys["signer_pk"].as_str().unwrap())?;
+
+            let outcomes = contract_keys["outcomes"].as_array().unwrap();
+            let outcome = outcomes.iter().find(
+                |v| v["name"].as_str().unwrap() == winner.as_str()
+            );
+            let winner_pk = match outcome {
+                None => Pubkey::from_str(winner.as_str())?,
+                Some(v) => Pubkey::from_str(v["mint_pk"].as_str().unwrap())?
+            };
+
+            let contract = client.get_account(&omega_contract_pk)?;
+            let contract = OmegaContract::load_from_bytes(contract.data.as_slice())?;
+
+            let instruction = overturn_resolution(
+                &omega_program_id,
+                &omega_contract_pk,
+                &admin_keypair.pubkey(),
+                &winner_pk,
+                &contract.bond.escrow,
+                &contract.vault,
+                &signer_pk
+            )?;
+            let mut signers = vec![&payer];
+            if admin_keypair != payer {
+                signers.push(&admin_keypair)
+            }
+            send_instructions(&client, vec![instruction], signers, &payer.pubkey())?;
+        }
+        Command::ResolveLate {
+            payer,
+            contract_keys_path,
+            admin_keypair,
+            winner
+        } => {
+            println!("ResolveLate");
+            let payer = read_keypair_file(payer.as_str())?;
+            let admin_keypair = read_keypair_file(admin_keypair.as_str())?;
+            let contract_keys: Value = serde_json::from_reader(File::open(contract_keys_path)?)?;
+            let omega_program_id = Pubkey::from_str(contract_keys["omega_program_id"].as_str().unwrap())?;
+            let omega_contract_pk = Pubkey::from_str(contract_keys["omega_contract_pk"].as_str().unwrap())?;
+            let signer_pk = Pubkey::from_str(contract_keys["signer_pk"].as_str().unwrap())?;
+
+            let outcomes = contract_keys["outcomes"].as_array().unwrap();
+            let outcome = outcomes.iter().find(
+                |v| v["name"].as_str().unwrap() == winner.as_str()
+            );
+            let winner_pk = match outcome {
+                None => Pubkey::from_str(winner.as_str())?,
+                Some(v) => Pubkey::from_str(v["mint_pk"].as_str().unwrap())?
+            };
+
+            let contract = client.get_account(&omega_contract_pk)?;
+            let contract = OmegaContract::load_from_bytes(contract.data.as_slice())?;
+            if contract.auto_exp_redemptions > 0 {
+                println!("{} was already redeemed at the auto expiry rate", omega_contract_pk);
+            }
+            let bond = if contract.bond.status == BondStatus::Posted as u64 {
+                Some((&contract.bond.escrow, &contract.vault, &signer_pk))
+            } else {
+                None
+            };
+
+            let instruction = resolve_late(
+                &omega_program_id,
+                &omega_contract_pk,
+                &admin_keypair.pubkey(),
+                &winner_pk,
+                bond
+            )?;
+            let mut signers = vec![&payer];
+            if admin_keypair != payer {
+                signers.push(&admin_keypair)
+            }
+            send_instructions(&client, vec![instruction], signers, &payer.pubkey())?;
+        }
+        Command::InitOracleLiveness {
+            payer,
+            contract_keys_path,
+            oracle_keypair,
+            backup_oracle,
+            heartbeat_period
+        } => {
+            println!("InitOracleLiveness");
+            let payer = read_keypair_file(payer.as_str())?;
+            let oracle_keypair = read_keypair_file(oracle_keypair.as_str())?;
+            let contract_keys: Value = serde_json::from_reader(File::open(contract_keys_path)?)?;
+            let omega_program_id = Pubkey::from_str(contract_keys["omega_program_id"].as_str().unwrap())?;
+            let omega_contract_pk = Pubkey::from_str(contract_keys["omega_contract_pk"].as_str().unwrap())?;
+
+            let instruction = init_oracle_liveness(
+                &omega_program_id,
+                &omega_contract_pk,
+                &oracle_keypair.pubkey(),
+                &Pubkey::from_str(backup_oracle.as_str())?,
+                heartbeat_period
+            )?;
+            let mut signers = vec![&payer];
+            if oracle_keypair != payer {
+                signers.push(&oracle_keypair)
+            }
+            send_instructions(&client, vec![instruction], signers, &payer.pubkey())?;
+        }
+        Command::OracleHeartbeat {
+            payer,
+            oracle_keypair,
+            contract_keys_paths
+        } => {
+            let payer = read_keypair_file(payer.as_str())?;
+            let oracle_keypair = read_keypair_file(oracle_keypair.as_str())?;
+            let mut signers = vec![&payer];
+            if oracle_keypair != payer {
+                signers.push(&oracle_keypair)
+            }
+
+            for contract_keys_path in contract_keys_paths.iter() {
+                let contract_keys: Value = serde_json::from_reader(File::open(contract_keys_path)?)?;
+                let omega_program_id = Pubkey::from_str(contract_keys["omega_program_id"].as_str().unwrap())?;
+                let omega_contract_pk = Pubkey::from_str(contract_keys["omega_contract_pk"].as_str().unwrap())?;
+
+                let contract = client.get_account(&omega_contract_pk)?;
+                let contract = OmegaContract::load_from_bytes(contract.data.as_slice())?;
+                if contract.winner != Pubkey::default() {
+                    continue;
+                }
+
+                let instruction = oracle_heartbeat(&omega_program_id, &omega_contract_pk, &oracle_keypair.pubkey())?;
+                send_instructions(&client, vec![instruction], signers.clone(), &payer.pubkey())?;
+                println!("{} heartbeat {}", Utc::now().naive_utc(), omega_contract_pk);
+            }
+        }
+        Command::InitOracleReputation {
+            payer,
+            omega_program_id,
+            oracle
+        } => {
+            println!("InitOracleReputation");
+            let payer = read_keypair_file(payer.as_str())?;
+            let omega_program_id = Pubkey::from_str(omega_program_id.as_str())?;
+            let oracle_pk = Pubkey::from_str(oracle.as_str())?;
+            let instruction = init_oracle_reputation(&omega_program_id, &oracle_pk, &payer.pubkey())?;
+            send_instructions(&client, vec![instruction], vec![&payer], &payer.pubkey())?;
+        }
+        Command::RecordOracleReputation {
+            payer,
+            contract_keys_path
+        } => {
+            println!("RecordOracleReputation");
+            let payer = read_keypair_file(payer.as_str())?;
+            let contract_keys: Value = serde_json::from_reader(File::open(contract_keys_path)?)?;
+            let omega_program_id = Pubkey::from_str(contract_keys["omega_program_id"].as_str().unwrap())?;
+            let omega_contract_pk = Pubkey::from_str(contract_keys["omega_contract_pk"].as_str().unwrap())?;
+
+            // events can count against the oracle, the backup oracle or whoever resolved
+            let contract = client.get_account(&omega_contract_pk)?;
+            let contract = OmegaContract::load_from_bytes(contract.data.as_slice())?;
+            let mut oracle_pks = vec![contract.oracle, contract.liveness.backup_oracle, contract.resolver];
+            oracle_pks.retain(|pk| *pk != Pubkey::default());
+            oracle_pks.dedup();
+
+            let mut instructions = vec![];
+            for oracle_pk in oracle_pks.iter() {
+                if get_oracle_stats(&client, &omega_program_id, oracle_pk)?.is_some() {
+                    instructions.push(record_oracle_reputation(&omega_program_id, &omega_contract_pk, oracle_pk)?);
+                }
+            }
+            send_instructions(&client, instructions, vec![&payer], &payer.pubkey())?;
+        }
+        Command::OracleStats {
+            omega_program_id,
+            oracle
+        } => {
+            let omega_program_id = Pubkey::from_str(omega_program_id.as_str())?;
+            let oracle_pk = Pubkey::from_str(oracle.as_str())?;
+            match get_oracle_stats(&client, &omega_program_id, &oracle_pk)? {
+                None => println!("{} has no reputation yet", oracle_pk),
+                Some(stats) => {
+                    println!("oracle: {}", stats.oracle);
+                    println!("resolved: {}", stats.num_resolved);
+                    println!("average delay after exp_time: {}s", stats.average_delay);
+                    println!("disputes lost: {}", stats.disputes_lost);
+                    println!("auto expiries: {}", stats.auto_expiries);
+                }
+            }
+        }
+        Command::ReleaseCreatorBond {
+            payer,
+            contract_keys_path,
+            creator_quote_acc
+        } => {
+            println!("ReleaseCreatorBond");
+            let payer = read_keypair_file(payer.as_str())?;
+            let contract_keys: Value = serde_json::from_reader(File::open(contract_keys_path)?)?;
+            let omega_program_id = Pubkey::from_str(contract_keys["omega_program_id"].as_str().unwrap())?;
+            let omega_contract_pk = Pubkey::from_str(contract_keys["omega_contract_pk"].as_str().unwrap())?;
+            let signer_pk = Pubkey::from_str(contract_keys["signer_pk"].as_str().unwrap())?;
+
+            let contract = client.get_account(&omega_contract_pk)?;
+            let contract = OmegaContract::load_from_bytes(contract.data.as_slice())?;
+
+            let instruction = release_creator_bond(
+                &omega_program_id,
+                &omega_contract_pk,
+                &contract.creator_bond.escrow,
+                &Pubkey::from_str(creator_quote_acc.as_str())?,
+                &signer_pk
+            )?;
+            send_instructions(&client, vec![instruction], vec![&payer], &payer.pubkey())?;
+        }
+        Command::ForfeitCreatorBond {
+            payer,
+            contract_keys_path,
+            admin_keypair,
+            treasury_quote_acc
+        } => {
+            println!("ForfeitCreatorBond");
+            let payer = read_keypair_file(payer.as_str())?;
+            let admin_keypair = match admin_keypair {
+                None => None,
+                Some(admin_keypair) => Some(read_keypair_file(admin_keypair.as_str())?)
+            };
+            let contract_keys: Value = serde_json::from_reader(File::open(contract_keys_path)?)?;
+            let omega_program_id = Pubkey::from_str(contract_keys["omega_program_id"].as_str().unwrap())?;
+            let omega_contract_pk = Pubkey::from_str(contract_keys["omega_contract_pk"].as_str().unwrap())?;
+            let signer_pk = Pubkey::from_str(contract_keys["signer_pk"].as_str().unwrap())?;
+
+            let contract = client.get_account(&omega_contract_pk)?;
+            let contract = OmegaContract::load_from_bytes(contract.data.as_slice())?;
+
+            let admin_pk = admin_keypair.as_ref().map(|kp| kp.pubkey());
+            let instruction = forfeit_creator_bond(
+                &omega_program_id,
+                &omega_contract_pk,
+                admin_pk.as_ref(),
+                &contract.creator_bond.escrow,
+                &Pubkey::from_str(treasury_quote_acc.as_str())?,
+                &signer_pk
+            )?;
+            let mut signers = vec![&payer];
+            if let Some(admin_keypair) = admin_keypair.as_ref() {
+                if *admin_keypair != payer {
+                    signers.push(admin_keypair)
+                }
+            }
+            send_instructions(&client, vec![instruction], signers, &payer.pubkey())?;
+        }
+        Command::SetAllowList {
+            payer,
+            contract_keys_path,
+            admin_keypair,
+            csv
+        } => {
+            println!("SetAllowList");
+            let payer = read_keypair_file(payer.as_str())?;
+            let admin_keypair = read_keypair_file(admin_keypair.as_str())?;
+            let contract_keys: Value = serde_json::from_reader(File::open(contract_keys_path)?)?;
+            let omega_program_id = Pubkey::from_str(contract_keys["omega_program_id"].as_str().unwrap())?;
+            let omega_contract_pk = Pubkey::from_str(contract_keys["omega_contract_pk"].as_str().unwrap())?;
+
+            let root = match csv {
+                None => [0u8; 32],
+                Some(csv) => {
+                    let pubkeys = read_allow_list_csv(csv.as_str())?;
+                    println!("{} pubkeys on the allow-list", pubkeys.len());
+                    allow_list_root(&pubkeys)
+                }
+            };
+            println!("root: {}", Hash::new_from_array(root));
+
+            let instruction = set_allow_list(&omega_program_id, &omega_contract_pk, &admin_keypair.pubkey(), root)?;
+            let mut signers = vec![&payer];
+            if admin_keypair != payer {
+                signers.push(&admin_keypair);
+            }
+            send_instructions(&client, vec![instruction], signers, &payer.pubkey())?;
+        }
+        Command::SetIssuanceCaps {
+            payer,
+            contract_keys_path,
+            admin_keypair,
+            max_supply,
+            wallet_cap
+        } => {
+            println!("SetIssuanceCaps");
+            let payer = read_keypair_file(payer.as_str())?;
+            let admin_keypair = read_keypair_file(admin_keypair.as_str())?;
+            let contract_keys: Value = serde_json::from_reader(File::open(contract_keys_path)?)?;
+            let omega_program_id = Pubkey::from_str(contract_keys["omega_program_id"].as_str().unwrap())?;
+            let omega_contract_pk = Pubkey::from_str(contract_keys["omega_contract_pk"].as_str().unwrap())?;
+
+            let instruction = set_issuance_caps(
+                &omega_program_id,
+                &omega_contract_pk,
+                &admin_keypair.pubkey(),
+                max_supply,
+                wallet_cap
+            )?;
+            let mut signers = vec![&payer];
+            if admin_keypair != payer {
+                signers.push(&admin_keypair);
+            }
+            send_instructions(&client, vec![instruction], signers, &payer.pubkey())?;
+        }
+        Command::InitVoteResolution {
+            payer,
+            contract_keys_path,
+            oracle_keypair,
+            gov_mint,
+            voting_period,
+            quorum,
+            tie_break,
+            tie_break_outcome
+        } => {
+            println!("InitVoteResolution");
+            let payer = read_keypair_file(payer.as_str())?;
+            let oracle_keypair = read_keypair_file(oracle_keypair.as_str())?;
+            let contract_keys: Value = serde_json::from_reader(File::open(contract_keys_path)?)?;
+            let omega_program_id = Pubkey::from_str(contract_keys["omega_program_id"].as_str().unwrap())?;
+            let omega_contract_pk = Pubkey::from_str(contract_keys["omega_contract_pk"].as_str().unwrap())?;
+            let signer_pk = Pubkey::from_str(contract_keys["signer_pk"].as_str().unwrap())?;
+            let gov_mint_pk = Pubkey::from_str(gov_mint.as_str())?;
+            let tie_break = match tie_break.as_str() {
+                "lowest" => TieBreak::LowestIndex,
+                "designated" => TieBreak::Designated,
+                _ => TieBreak::NoWinner
+            };
+            let tie_break_outcome = match tie_break_outcome {
+                None => 0,
+                Some(name) => contract_keys["outcomes"].as_array().unwrap().iter()
+                    .position(|v| v["name"].as_str().unwrap() == name.as_str())
+                    .expect("unknown tie break outcome") as u64
+            };
+
+            let mut instructions = vec![];
+            let mut signers = vec![&payer];
+            let escrow_kp = Keypair::new();
+            create_token_account_instr(
+                &client,
+                &escrow_kp,
+                &gov_mint_pk,
+                &signer_pk,
+                &payer,
+                &mut instructions,
+                &mut signers
+            )?;
+            instructions.push(init_vote_resolution(
+                &omega_program_id,
+                &omega_contract_pk,
+                &oracle_keypair.pubkey(),
+                &gov_mint_pk,
+                &escrow_kp.pubkey(),
+                voting_period,
+                quorum,
+                tie_break,
+                tie_break_outcome
+            )?);
+            if oracle_keypair != payer {
+                signers.push(&oracle_keypair)
+            }
             send_instructions(&client, instructions, signers, &payer.pubkey())?;
+            println!("vote escrow: {}", escrow_kp.pubkey());
+        }
+        Command::CastVote {
+            contract_keys_path,
+            voter_keypair,
+            voter_gov_acc,
+            outcome,
+            amount
+        } => {
+            println!("CastVote");
+            let voter_keypair = read_keypair_file(voter_keypair.as_str())?;
+            let contract_keys: Value = serde_json::from_reader(File::open(contract_keys_path)?)?;
+            let omega_program_id = Pubkey::from_str(contract_keys["omega_program_id"].as_str().unwrap())?;
+            let omega_contract_pk = Pubkey::from_str(contract_keys["omega_contract_pk"].as_str().unwrap())?;
+            let outcome = contract_keys["outcomes"].as_array().unwrap().iter()
+                .position(|v| v["name"].as_str().unwrap() == outcome.as_str())
+                .expect("unknown outcome") as u64;
 
-            let exp_time = NaiveDateTime::parse_from_str(exp_time.as_str(), "%Y-%m-%d %H:%M:%S")?;
-            let exp_time = exp_time.timestamp() as u64;
-            let auto_exp_time = NaiveDateTime::parse_from_str(auto_exp_time.as_str(), "%Y-%m-%d %H:%M:%S")?;
-            let auto_exp_time = auto_exp_time.timestamp() as u64;
+            let contract = client.get_account(&omega_contract_pk)?;
+            let contract = OmegaContract::load_from_bytes(contract.data.as_slice())?;
 
-            let instruction = init_omega_contract(
+            let instruction = cast_vote(
                 &omega_program_id,
                 &omega_contract_pk,
-                &oracle_pk,
-                &quote_mint_pk,
-                &quote_vault_pk,
-                &signer_key,
-                outcome_mint_pks.as_slice(),
-                exp_time,
-                auto_exp_time,
-                signer_nonce,
-                details.as_str()
+                &voter_keypair.pubkey(),
+                &Pubkey::from_str(voter_gov_acc.as_str())?,
+                &contract.vote.escrow,
+                outcome,
+                amount
             )?;
+            send_instructions(&client, vec![instruction], vec![&voter_keypair], &voter_keypair.pubkey())?;
+        }
+        Command::FinalizeVote {
+            payer,
+            contract_keys_path
+        } => {
+            println!("FinalizeVote");
+            let payer = read_keypair_file(payer.as_str())?;
+            let contract_keys: Value = serde_json::from_reader(File::open(contract_keys_path)?)?;
+            let omega_program_id = Pubkey::from_str(contract_keys["omega_program_id"].as_str().unwrap())?;
+            let omega_contract_pk = Pubkey::from_str(contract_keys["omega_contract_pk"].as_str().unwrap())?;
 
-            let instructions = vec![instruction];
-            let signers = vec![&payer];
-            thread::sleep(time::Duration::from_secs(5));
-            println!("Sending InitOmegaContract instruction");
-            send_instructions(&client, instructions, signers, &payer.pubkey())?;
-
-            let contract_keys = json!({
-                "contract_name": contract_name,
-                "omega_program_id": omega_program_id.to_string(),
-                "omega_contract_pk": omega_contract_pk.to_string(),
-                "oracle_pk": oracle_pk.to_string(),
-                "quote_mint_pk": quote_mint_pk.to_string(),
-                "quote_vault_pk": quote_vault_pk.to_string(),
-                "signer_pk": signer_key.to_string(),
-                "signer_nonce": signer_nonce,
+            let contract = client.get_account(&omega_contract_pk)?;
+            let contract = OmegaContract::load_from_bytes(contract.data.as_slice())?;
+            let instruction = finalize_vote(
+                &omega_program_id,
+                &omega_contract_pk,
+                &contract.vault,
+                &contract.outcomes[..contract.num_outcomes]
+            )?;
+            send_instructions(&client, vec![instruction], vec![&payer], &payer.pubkey())?;
+        }
+        Command::WithdrawVote {
+            contract_keys_path,
+            voter_keypair,
+            voter_gov_acc
+        } => {
+            println!("WithdrawVote");
+            let voter_keypair = read_keypair_file(voter_keypair.as_str())?;
+            let contract_keys: Value = serde_json::from_reader(File::open(contract_keys_path)?)?;
+            let omega_program_id = Pubkey::from_str(contract_keys["omega_program_id"].as_str().unwrap())?;
+            let omega_contract_pk = Pubkey::from_str(contract_keys["omega_contract_pk"].as_str().unwrap())?;
+            let signer_pk = Pubkey::from_str(contract_keys["signer_pk"].as_str().unwrap())?;
 
-                "outcomes": outcome_infos,
-                "details": details
-            });
+            let contract = client.get_account(&omega_contract_pk)?;
+            let contract = OmegaContract::load_from_bytes(contract.data.as_slice())?;
 
-            for contract_keys_path in contract_keys_paths.iter() {
-                let f = File::create(contract_keys_path).unwrap();
-                serde_json::to_writer_pretty(&f, &contract_keys).unwrap();
-                println!("contract keys were written into: {}", contract_keys_path);
-            }
-        }
-        Command::IssueSet { .. } => {
-            println!("IssueSet");
-            unimplemented!()
+            let instruction = withdraw_vote(
+                &omega_program_id,
+                &omega_contract_pk,
+                &voter_keypair.pubkey(),
+                &Pubkey::from_str(voter_gov_acc.as_str())?,
+                &contract.vote.escrow,
+                &signer_pk
+            )?;
+            send_instructions(&client, vec![instruction], vec![&voter_keypair], &voter_keypair.pubkey())?;
         }
-        Command::Resolve {
+        Command::CommitResolution {
             payer,
             contract_keys_path,
             oracle_keypair,
-            winner
+            winner,
+            reveal_window,
+            salt_path
         } => {
-            println!("Resolve");
-
+            println!("CommitResolution");
             let payer = read_keypair_file(payer.as_str())?;
             let oracle_keypair = read_keypair_file(oracle_keypair.as_str())?;
             let contract_keys: Value = serde_json::from_reader(File::open(contract_keys_path)?)?;
+            let omega_program_id = Pubkey::from_str(contract_keys["omega_program_id"].as_str().unwrap())?;
+            let omega_contract_pk = Pubkey::from_str(contract_keys["omega_contract_pk"].as_str().unwrap())?;
 
             let outcomes = contract_keys["outcomes"].as_array().unwrap();
             let outcome = outcomes.iter().find(
@@ -270,20 +1594,143 @@ pub fn start(opts: Opts) -> Result<()> {
                 None => Pubkey::from_str(winner.as_str())?,
                 Some(v) => Pubkey::from_str(v["mint_pk"].as_str().unwrap())?
             };
+
+            // the secret half of a fresh keypair is as good a random salt as any
+            let salt: [u8; 32] = Keypair::new().to_bytes()[..32].try_into()?;
+            let f = File::create(salt_path.as_str())?;
+            serde_json::to_writer_pretty(&f, &json!({"winner": winner_pk.to_string(), "salt": salt.to_vec()}))?;
+            println!("keep the salt until the reveal: {}", salt_path);
+
+            let instruction = commit_resolution(
+                &omega_program_id,
+                &omega_contract_pk,
+                &oracle_keypair.pubkey(),
+                commitment_hash(&winner_pk, &salt),
+                reveal_window
+            )?;
+            let mut signers = vec![&payer];
+            if oracle_keypair != payer {
+                signers.push(&oracle_keypair)
+            }
+            send_instructions(&client, vec![instruction], signers, &payer.pubkey())?;
+        }
+        Command::RevealResolution {
+            payer,
+            contract_keys_path,
+            salt_path
+        } => {
+            println!("RevealResolution");
+            let payer = read_keypair_file(payer.as_str())?;
+            let contract_keys: Value = serde_json::from_reader(File::open(contract_keys_path)?)?;
             let omega_program_id = Pubkey::from_str(contract_keys["omega_program_id"].as_str().unwrap())?;
             let omega_contract_pk = Pubkey::from_str(contract_keys["omega_contract_pk"].as_str().unwrap())?;
 
-            println!("{} {} {} {}", omega_program_id.to_string(), omega_contract_pk.to_string(), oracle_keypair.pubkey().to_string(), winner_pk.to_string());
+            let committed: Value = serde_json::from_reader(File::open(salt_path)?)?;
+            let winner_pk = Pubkey::from_str(committed["winner"].as_str().unwrap())?;
+            let salt: Vec<u8> = serde_json::from_value(committed["salt"].clone())?;
+            let contract = client.get_account(&omega_contract_pk)?;
+            let contract = OmegaContract::load_from_bytes(contract.data.as_slice())?;
 
+            let instruction = reveal_resolution(
+                &omega_program_id,
+                &omega_contract_pk,
+                &winner_pk,
+                &contract.vault,
+                &contract.outcomes[..contract.num_outcomes],
+                salt.as_slice().try_into()?
+            )?;
+            send_instructions(&client, vec![instruction], vec![&payer], &payer.pubkey())?;
+        }
+        Command::Attest {
+            contract_keys_path,
+            oracle_keypair,
+            winner,
+            attestation_path
+        } => {
+            // only reads local files so it can run on the oracle's offline machine
+            println!("Attest");
+            let oracle_keypair = read_keypair_file(oracle_keypair.as_str())?;
+            let contract_keys: Value = serde_json::from_reader(File::open(contract_keys_path)?)?;
+            let omega_contract_pk = Pubkey::from_str(contract_keys["omega_contract_pk"].as_str().unwrap())?;
+
+            let outcomes = contract_keys["outcomes"].as_array().unwrap();
+            let winner_index = outcomes.iter().position(
+                |v| v["name"].as_str().unwrap() == winner.as_str() || v["mint_pk"].as_str().unwrap() == winner.as_str()
+            ).expect("winner is not one of the outcomes");
+            let timestamp = Utc::now().timestamp();
+
+            let signature = sign_attestation(&oracle_keypair, &omega_contract_pk, winner_index as u64, timestamp);
+            let attestation = json!({
+                "omega_contract_pk": omega_contract_pk.to_string(),
+                "oracle_pk": oracle_keypair.pubkey().to_string(),
+                "winner": winner_index,
+                "timestamp": timestamp,
+                "signature": Signature::new(&signature).to_string()
+            });
+            let f = File::create(attestation_path.as_str())?;
+            serde_json::to_writer_pretty(&f, &attestation)?;
+            println!("attestation was written into: {}", attestation_path);
+        }
+        Command::SubmitAttestation {
+            payer,
+            contract_keys_path,
+            attestation_path
+        } => {
+            println!("SubmitAttestation");
+            let payer = read_keypair_file(payer.as_str())?;
+            let contract_keys: Value = serde_json::from_reader(File::open(contract_keys_path)?)?;
+            let attestation: Value = serde_json::from_reader(File::open(attestation_path)?)?;
+            let omega_program_id = Pubkey::from_str(contract_keys["omega_program_id"].as_str().unwrap())?;
+            let omega_contract_pk = Pubkey::from_str(contract_keys["omega_contract_pk"].as_str().unwrap())?;
+            let oracle_pk = Pubkey::from_str(contract_keys["oracle_pk"].as_str().unwrap())?;
+            let winner = attestation["winner"].as_u64().unwrap();
+            let timestamp = attestation["timestamp"].as_i64().unwrap();
+            let signature = Signature::from_str(attestation["signature"].as_str().unwrap())?;
+            let signature: [u8; 64] = signature.as_ref().try_into()?;
+
+            assert_eq!(attestation["omega_contract_pk"].as_str().unwrap(), omega_contract_pk.to_string());
+            assert!(verify_attestation(&oracle_pk, &omega_contract_pk, winner, timestamp, &signature));
             let contract = client.get_account(&omega_contract_pk)?;
             let contract = OmegaContract::load_from_bytes(contract.data.as_slice())?;
-            println!("{}", contract.exp_time);
 
-            let instruction = resolve(
+            let instructions = resolve_with_attestation(
+                &omega_program_id,
+                &omega_contract_pk,
+                &oracle_pk,
+                &contract.vault,
+                &contract.outcomes[..contract.num_outcomes],
+                winner,
+                timestamp,
+                &signature
+            )?;
+            send_instructions(&client, instructions, vec![&payer], &payer.pubkey())?;
+        }
+        Command::ResolveValue {
+            payer,
+            contract_keys_path,
+            oracle_keypair,
+            value
+        } => {
+            println!("ResolveValue");
+
+            let payer = read_keypair_file(payer.as_str())?;
+            let oracle_keypair = read_keypair_file(oracle_keypair.as_str())?;
+            let contract_keys: Value = serde_json::from_reader(File::open(contract_keys_path)?)?;
+            let omega_program_id = Pubkey::from_str(contract_keys["omega_program_id"].as_str().unwrap())?;
+            let omega_contract_pk = Pubkey::from_str(contract_keys["omega_contract_pk"].as_str().unwrap())?;
+
+            let contract = client.get_account(&omega_contract_pk)?;
+            let contract = OmegaContract::load_from_bytes(contract.data.as_slice())?;
+            let outcomes = contract_keys["outcomes"].as_array().unwrap();
+            println!("value {} falls in bucket {}", value, outcomes[contract.bucket(value)]["name"]);
+
+            let instruction = resolve_value(
                 &omega_program_id,
                 &omega_contract_pk,
                 &oracle_keypair.pubkey(),
-                &winner_pk
+                &contract.vault,
+                &contract.outcomes[..contract.num_outcomes],
+                value
             )?;
             let instructions = vec![instruction];
             let mut signers = vec![&payer];
@@ -291,7 +1738,6 @@ pub fn start(opts: Opts) -> Result<()> {
                 signers.push(&oracle_keypair)
             }
             send_instructions(&client, instructions, signers, &payer.pubkey())?;
-
         }
 
         Command::SolletToLocal {
@@ -405,11 +1851,393 @@ pub fn start(opts: Opts) -> Result<()> {
                 }
             }
         }
+        Command::InitProgramConfig {
+            payer,
+            omega_program_id,
+            timelock,
+            fee_bps,
+            creator_bond,
+            treasury,
+            quote_mints,
+            creators
+        } => {
+            println!("InitProgramConfig");
+            let payer = read_keypair_file(payer.as_str())?;
+            let omega_program_id = Pubkey::from_str(omega_program_id.as_str())?;
+            let treasury_pk = match treasury {
+                None => payer.pubkey(),
+                Some(treasury) => Pubkey::from_str(treasury.as_str())?
+            };
+            let quote_mint_pks = parse_pubkeys(&quote_mints)?;
+            let creator_pks = match creators {
+                None => None,
+                Some(creators) => Some(parse_pubkeys(&creators)?)
+            };
+
+            let instruction = init_program_config(
+                &omega_program_id,
+                &payer.pubkey(),
+                timelock,
+                fee_bps,
+                creator_bond,
+                &treasury_pk,
+                quote_mint_pks.as_slice(),
+                creator_pks.as_deref()
+            )?;
+            send_instructions(&client, vec![instruction], vec![&payer], &payer.pubkey())?;
+            println!("program config: {}", find_program_config_address(&omega_program_id).0);
+        }
+        Command::ProposeConfig {
+            admin,
+            omega_program_id,
+            new_admin,
+            timelock,
+            fee_bps,
+            creator_bond,
+            treasury,
+            quote_mints,
+            creators
+        } => {
+            println!("ProposeConfig");
+            let admin = read_keypair_file(admin.as_str())?;
+            let omega_program_id = Pubkey::from_str(omega_program_id.as_str())?;
+            let new_admin_pk = match new_admin {
+                None => admin.pubkey(),
+                Some(new_admin) => Pubkey::from_str(new_admin.as_str())?
+            };
+            let treasury_pk = match treasury {
+                None => new_admin_pk,
+                Some(treasury) => Pubkey::from_str(treasury.as_str())?
+            };
+            let quote_mint_pks = parse_pubkeys(&quote_mints)?;
+            let creator_pks = match creators {
+                None => None,
+                Some(creators) => Some(parse_pubkeys(&creators)?)
+            };
+
+            let instruction = propose_config(
+                &omega_program_id,
+                &admin.pubkey(),
+                &new_admin_pk,
+                timelock,
+                fee_bps,
+                creator_bond,
+                &treasury_pk,
+                quote_mint_pks.as_slice(),
+                creator_pks.as_deref()
+            )?;
+            send_instructions(&client, vec![instruction], vec![&admin], &admin.pubkey())?;
+        }
+        Command::ApplyConfig {
+            payer,
+            omega_program_id
+        } => {
+            println!("ApplyConfig");
+            let payer = read_keypair_file(payer.as_str())?;
+            let omega_program_id = Pubkey::from_str(omega_program_id.as_str())?;
+            let instruction = apply_config(&omega_program_id)?;
+            send_instructions(&client, vec![instruction], vec![&payer], &payer.pubkey())?;
+        }
+        Command::InitPriceResolution {
+            payer,
+            contract_keys_path,
+            oracle_keypair,
+            price_account,
+            source,
+            price_program,
+            comparison,
+            threshold,
+            max_staleness
+        } => {
+            println!("InitPriceResolution");
+            let payer = read_keypair_file(payer.as_str())?;
+            let oracle_keypair = read_keypair_file(oracle_keypair.as_str())?;
+            let contract_keys: Value = serde_json::from_reader(File::open(contract_keys_path)?)?;
+            let omega_program_id = Pubkey::from_str(contract_keys["omega_program_id"].as_str().unwrap())?;
+            let omega_contract_pk = Pubkey::from_str(contract_keys["omega_contract_pk"].as_str().unwrap())?;
+            let price_pk = Pubkey::from_str(price_account.as_str())?;
+            let price_program_pk = match price_program {
+                None => client.get_account(&price_pk)?.owner,
+                Some(price_program) => Pubkey::from_str(price_program.as_str())?
+            };
+            let source = match source.as_str() {
+                "simple" => PriceSourceKind::Simple,
+                _ => PriceSourceKind::Pyth
+            };
+            let comparison = match comparison.as_str() {
+                "gt" => Comparison::GreaterThan,
+                "ge" => Comparison::GreaterOrEqual,
+                "lt" => Comparison::LessThan,
+                _ => Comparison::LessOrEqual
+            };
+
+            let instruction = init_price_resolution(
+                &omega_program_id,
+                &omega_contract_pk,
+                &oracle_keypair.pubkey(),
+                &price_pk,
+                &price_program_pk,
+                source,
+                comparison,
+                threshold,
+                max_staleness
+            )?;
+            let mut signers = vec![&payer];
+            if oracle_keypair != payer {
+                signers.push(&oracle_keypair)
+            }
+            send_instructions(&client, vec![instruction], signers, &payer.pubkey())?;
+        }
+        Command::ResolveFromPrice {
+            payer,
+            contract_keys_path
+        } => {
+            println!("ResolveFromPrice");
+            let payer = read_keypair_file(payer.as_str())?;
+            let contract_keys: Value = serde_json::from_reader(File::open(contract_keys_path)?)?;
+            let omega_program_id = Pubkey::from_str(contract_keys["omega_program_id"].as_str().unwrap())?;
+            let omega_contract_pk = Pubkey::from_str(contract_keys["omega_contract_pk"].as_str().unwrap())?;
+
+            let contract = client.get_account(&omega_contract_pk)?;
+            let contract = OmegaContract::load_from_bytes(contract.data.as_slice())?;
+            let instruction = resolve_from_price(
+                &omega_program_id,
+                &omega_contract_pk,
+                &contract.price_resolution.price_acc,
+                &contract.vault,
+                &contract.outcomes[..contract.num_outcomes]
+            )?;
+            let instruction = if get_oracle_stats(&client, &omega_program_id, &contract.oracle)?.is_some() {
+                with_oracle_reputation(instruction, &contract.oracle)
+            } else {
+                instruction
+            };
+            send_instructions(&client, vec![instruction], vec![&payer], &payer.pubkey())?;
+        }
+        Command::InitRegistry {
+            payer,
+            omega_program_id
+        } => {
+            println!("InitRegistry");
+            let payer = read_keypair_file(payer.as_str())?;
+            let omega_program_id = Pubkey::from_str(omega_program_id.as_str())?;
+
+            let registry_kp = Keypair::new();
+            let instructions = vec![
+                create_account_instr(&client, &payer, &registry_kp, size_of::<Registry>(), &omega_program_id)?,
+                init_registry(&omega_program_id, &registry_kp.pubkey(), &payer.pubkey())?
+            ];
+            let signers = vec![&payer, &registry_kp];
+            send_instructions(&client, instructions, signers, &payer.pubkey())?;
+            println!("registry: {}", registry_kp.pubkey());
+        }
+        Command::ListContracts {
+            registry,
+            category,
+            page,
+            page_size
+        } => {
+            let registry_pk = Pubkey::from_str(registry.as_str())?;
+            for listed in list_contracts(&client, &registry_pk, category, page, page_size)? {
+                let created_at = NaiveDateTime::from_timestamp(listed.created_at as i64, 0);
+                println!(
+                    "{} oracle: {} category: {} created: {} status: {:?}",
+                    listed.contract, listed.oracle, listed.category, created_at, listed.status
+                );
+            }
+        }
+        Command::CloseContract {
+            payer,
+            contract_keys_path,
+            registry
+        } => {
+            println!("CloseContract");
+            let payer = read_keypair_file(payer.as_str())?;
+            let contract_keys: Value = serde_json::from_reader(File::open(contract_keys_path)?)?;
+            let omega_program_id = Pubkey::from_str(contract_keys["omega_program_id"].as_str().unwrap())?;
+            let omega_contract_pk = Pubkey::from_str(contract_keys["omega_contract_pk"].as_str().unwrap())?;
+            let quote_vault_pk = Pubkey::from_str(contract_keys["quote_vault_pk"].as_str().unwrap())?;
+
+            let instruction = close_contract(
+                &omega_program_id,
+                &omega_contract_pk,
+                &Pubkey::from_str(registry.as_str())?,
+                &payer.pubkey(),
+                &quote_vault_pk
+            )?;
+            send_instructions(&client, vec![instruction], vec![&payer], &payer.pubkey())?;
+        }
     }
     Ok(())
 }
 
 
+/// Create the contract, vault and outcome mint accounts and initialize the contract
+/// Returns the contract pubkey and the contract keys used by the UI
+fn create_omega_contract(
+    client: &RpcClient,
+    payer: &Keypair,
+    omega_program_id: &Pubkey,
+    oracle_pk: &Pubkey,
+    quote_mint_pk: &Pubkey,
+    contract_name: &str,
+    outcome_names: &[String],
+    icon_urls: &[String],
+    details: &str,
+    exp_time: &str,
+    auto_exp_time: &str,
+    registry_pk: &Pubkey,
+    creator_quote_pk: Option<&Pubkey>
+) -> Result<(Pubkey, Value)> {
+    let num_outcomes = outcome_names.len();
+    assert!(details.len() <= DETAILS_BUFFER_LEN);
+    assert_eq!(icon_urls.len(), num_outcomes);
+
+    let mut instructions = vec![];
+    let mut signers = vec![];
+
+    let omega_contract_kp = Keypair::new();
+    let omega_contract_pk = omega_contract_kp.pubkey();
+    instructions.push(create_account_instr(
+        client, payer, &omega_contract_kp,
+        size_of::<OmegaContract>(), omega_program_id
+    )?);
+    signers.push(payer);
+    signers.push(&omega_contract_kp);
+
+    let (signer_key, signer_nonce) = create_signer_key_and_nonce(omega_program_id, &omega_contract_pk);
+
+    let quote_vault_kp = Keypair::new();
+    let quote_vault_pk = quote_vault_kp.pubkey();
+    create_token_account_instr(
+        client,
+        &quote_vault_kp,
+        quote_mint_pk,
+        &signer_key,
+        payer,
+        &mut instructions,
+        &mut signers
+    )?;
+
+    let quote_mint: Mint = get_account(client, quote_mint_pk)?;
+    let mut outcome_infos = Vec::<Value>::new();
+    let mut outcome_mint_pks = vec![];
+    let mut outcome_mint_kps = vec![];
+    for _ in 0..num_outcomes {
+        outcome_mint_kps.push(Keypair::new());
+    }
+    for i in 0..num_outcomes {
+        let outcome_mint_kp = &outcome_mint_kps[i];
+        create_and_init_mint_instr(
+            client,
+            outcome_mint_kp,
+            payer,
+            &signer_key,
+            quote_mint.decimals,
+            &mut instructions,
+            &mut signers
+        )?;
+
+        let outcome_json = json!(
+            {
+                "mint_pk": outcome_mint_kp.pubkey().to_string(),
+                "name": outcome_names[i],
+                "icon": icon_urls[i].clone()
+            }
+        );
+        outcome_infos.push(outcome_json);
+        outcome_mint_pks.push(outcome_mint_kp.pubkey());
+    }
+
+    let (program_config_pk, _) = find_program_config_address(omega_program_id);
+    let program_config = client.get_account(&program_config_pk)?;
+    let creator_bond = ProgramConfig::load_from_bytes(program_config.data.as_slice())?.settings.creator_bond;
+    let creator_bond_escrow_kp = Keypair::new();
+    if creator_bond > 0 {
+        println!("creator bond: {}", creator_bond);
+        create_token_account_instr(
+            client,
+            &creator_bond_escrow_kp,
+            quote_mint_pk,
+            &signer_key,
+            payer,
+            &mut instructions,
+            &mut signers
+        )?;
+    }
+
+    // send first transaction because otherwise it's too big
+    println!("Sending account creation instructions");
+    send_instructions(client, instructions, signers, &payer.pubkey())?;
+
+    let exp_time = NaiveDateTime::parse_from_str(exp_time, "%Y-%m-%d %H:%M:%S")?;
+    let exp_time = exp_time.timestamp() as u64;
+    let auto_exp_time = NaiveDateTime::parse_from_str(auto_exp_time, "%Y-%m-%d %H:%M:%S")?;
+    let auto_exp_time = auto_exp_time.timestamp() as u64;
+
+    let instruction = init_omega_contract(
+        omega_program_id,
+        &omega_contract_pk,
+        oracle_pk,
+        quote_mint_pk,
+        &quote_vault_pk,
+        &signer_key,
+        &payer.pubkey(),
+        registry_pk,
+        outcome_mint_pks.as_slice(),
+        exp_time,
+        auto_exp_time,
+        signer_nonce,
+        details
+    )?;
+    let instruction = if creator_bond > 0 {
+        let creator_quote_pk = creator_quote_pk.ok_or_else(
+            || anyhow!("the program config requires a creator bond, pass a creator quote account")
+        )?;
+        with_creator_bond(instruction, creator_quote_pk, &creator_bond_escrow_kp.pubkey())
+    } else {
+        instruction
+    };
+
+    let instructions = vec![instruction];
+    let signers = vec![payer];
+    thread::sleep(time::Duration::from_secs(5));
+    println!("Sending InitOmegaContract instruction");
+    send_instructions(client, instructions, signers, &payer.pubkey())?;
+
+    let contract_keys = json!({
+        "contract_name": contract_name,
+        "omega_program_id": omega_program_id.to_string(),
+        "omega_contract_pk": omega_contract_pk.to_string(),
+        "oracle_pk": oracle_pk.to_string(),
+        "quote_mint_pk": quote_mint_pk.to_string(),
+        "quote_vault_pk": quote_vault_pk.to_string(),
+        "signer_pk": signer_key.to_string(),
+        "signer_nonce": signer_nonce,
+
+        "outcomes": outcome_infos,
+        "details": details
+    });
+    Ok((omega_contract_pk, contract_keys))
+}
+
+fn parse_pubkeys(pks: &[String]) -> Result<Vec<Pubkey>> {
+    let mut parsed = vec![];
+    for pk in pks.iter() {
+        parsed.push(Pubkey::from_str(pk.as_str())?);
+    }
+    Ok(parsed)
+}
+
+fn write_contract_keys(contract_keys: &Value, contract_keys_paths: &[String]) -> Result<()> {
+    for contract_keys_path in contract_keys_paths.iter() {
+        let f = File::create(contract_keys_path)?;
+        serde_json::to_writer_pretty(&f, contract_keys)?;
+        println!("contract keys were written into: {}", contract_keys_path);
+    }
+    Ok(())
+}
+
 
 fn main() {
     let opts = Opts::parse();
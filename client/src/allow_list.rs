@@ -0,0 +1,39 @@
+use std::fs;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+use omega::allow_list::{merkle_proof, merkle_root};
+use solana_sdk::pubkey::Pubkey;
+
+/// Pubkeys from the first column of a CSV. Blank lines and a non-pubkey header are skipped
+pub fn parse_allow_list_csv(text: &str) -> Result<Vec<Pubkey>> {
+    let mut pubkeys = vec![];
+    for (i, line) in text.lines().enumerate() {
+        let field = line.split(',').next().unwrap_or("").trim();
+        if field.is_empty() {
+            continue;
+        }
+        match Pubkey::from_str(field) {
+            Ok(pubkey) => pubkeys.push(pubkey),
+            Err(_) if i == 0 => continue,
+            Err(_) => return Err(anyhow!("line {}: {} is not a pubkey", i + 1, field))
+        }
+    }
+    if pubkeys.is_empty() {
+        return Err(anyhow!("allow-list is empty"));
+    }
+    Ok(pubkeys)
+}
+
+pub fn read_allow_list_csv(path: &str) -> Result<Vec<Pubkey>> {
+    parse_allow_list_csv(&fs::read_to_string(path)?)
+}
+
+pub fn allow_list_root(pubkeys: &[Pubkey]) -> [u8; 32] {
+    merkle_root(pubkeys)
+}
+
+/// Proof to pass to `with_allow_list_proof`, or None if `user` is not on the list
+pub fn allow_list_proof(pubkeys: &[Pubkey], user: &Pubkey) -> Option<Vec<[u8; 32]>> {
+    pubkeys.iter().position(|pubkey| pubkey == user).map(|index| merkle_proof(pubkeys, index))
+}
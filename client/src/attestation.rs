@@ -0,0 +1,23 @@
+use std::convert::TryInto;
+
+use omega::attestation::attestation_message;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+
+/// Oracle's signature declaring `winner` (outcome index) of the contract at `timestamp`
+/// Can be produced on an offline machine and submitted by anyone with `resolve_with_attestation`
+pub fn sign_attestation(oracle: &Keypair, omega_contract_pk: &Pubkey, winner: u64, timestamp: i64) -> [u8; 64] {
+    let message = attestation_message(omega_contract_pk, winner, timestamp);
+    oracle.sign_message(&message).as_ref().try_into().unwrap()
+}
+
+pub fn verify_attestation(
+    oracle_pk: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    winner: u64,
+    timestamp: i64,
+    signature: &[u8; 64]
+) -> bool {
+    let message = attestation_message(omega_contract_pk, winner, timestamp);
+    Signature::new(signature).verify(oracle_pk.as_ref(), &message)
+}
@@ -1,9 +1,75 @@
+pub mod allow_list;
+pub mod attestation;
+pub mod registry;
+pub mod reputation;
+pub mod resolution;
+pub mod stats;
 pub mod utils;
 
 #[cfg(test)]
 mod tests {
+    use bytemuck::Zeroable;
+    use omega::state::OmegaContract;
+    use solana_sdk::pubkey::Pubkey;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use crate::allow_list::{allow_list_proof, allow_list_root, parse_allow_list_csv};
+    use crate::attestation::{sign_attestation, verify_attestation};
+    use crate::resolution::Resolution;
+    use crate::stats::MarketStats;
+
     #[test]
     fn it_works() {
         assert_eq!(2 + 2, 4);
     }
+
+    #[test]
+    fn test_allow_list_csv() {
+        let pubkeys: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        let csv = format!("pubkey,name\n{},alice\n\n{}\n{},carol\n", pubkeys[0], pubkeys[1], pubkeys[2]);
+        assert_eq!(parse_allow_list_csv(&csv).unwrap(), pubkeys);
+        assert!(parse_allow_list_csv("pubkey\nnot-a-pubkey\n").is_err());
+
+        let root = allow_list_root(&pubkeys);
+        let proof = allow_list_proof(&pubkeys, &pubkeys[2]).unwrap().concat();
+        assert!(omega::allow_list::verify_proof(&root, &pubkeys[2], &proof));
+        assert!(allow_list_proof(&pubkeys, &Pubkey::new_unique()).is_none());
+    }
+
+    #[test]
+    fn test_attestation() {
+        let oracle = Keypair::new();
+        let omega_contract_pk = Pubkey::new_unique();
+        let signature = sign_attestation(&oracle, &omega_contract_pk, 1, 1_612_137_600);
+        assert!(verify_attestation(&oracle.pubkey(), &omega_contract_pk, 1, 1_612_137_600, &signature));
+        assert!(!verify_attestation(&oracle.pubkey(), &omega_contract_pk, 0, 1_612_137_600, &signature));
+        assert!(!verify_attestation(&Pubkey::new_unique(), &omega_contract_pk, 1, 1_612_137_600, &signature));
+    }
+
+    #[test]
+    fn test_verify_evidence() {
+        let resolution = Resolution {
+            winner: Pubkey::new_unique(),
+            slot: 42,
+            timestamp: 1_612_137_600,
+            evidence_uri: "https://example.com/results.json".to_string(),
+            evidence_hash: solana_sdk::hash::hash(b"final score 2-1").to_bytes(),
+        };
+        assert!(resolution.verify_evidence(b"final score 2-1"));
+        assert!(!resolution.verify_evidence(b"final score 1-2"));
+    }
+
+    #[test]
+    fn test_market_stats() {
+        let mut contract = OmegaContract::zeroed();
+        contract.num_outcomes = 2;
+        contract.supplies[..2].copy_from_slice(&[70, 40]);
+        contract.stats.sets_issued = 100;
+        contract.stats.sets_redeemed = 30;
+        contract.stats.unique_issuers = 3;
+        contract.stats.last_activity = 1_612_137_600;
+        let stats = MarketStats::from(&contract);
+        assert_eq!(stats.open_interest, stats.sets_issued - stats.sets_redeemed);
+        assert_eq!((stats.unique_issuers, stats.last_activity), (3, 1_612_137_600));
+    }
 }
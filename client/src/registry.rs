@@ -0,0 +1,51 @@
+use std::convert::TryFrom;
+
+use anyhow::{anyhow, Result};
+use omega::state::{AccountFlag, ContractStatus, Loadable, Registry, RegistryEntry};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+
+/// Contract listed in a registry
+#[derive(Clone, Debug)]
+pub struct ListedContract {
+    pub contract: Pubkey,
+    pub oracle: Pubkey,
+    pub category: u64,
+    pub created_at: u64,
+    pub status: ContractStatus,
+}
+
+impl From<&RegistryEntry> for ListedContract {
+    fn from(entry: &RegistryEntry) -> Self {
+        ListedContract {
+            contract: entry.contract,
+            oracle: entry.oracle,
+            category: entry.category,
+            created_at: entry.created_at,
+            status: ContractStatus::try_from(entry.status).unwrap_or(ContractStatus::Open),
+        }
+    }
+}
+
+/// One page of a registry, newest contracts first
+/// Pass `category` to only list contracts with that tag
+pub fn list_contracts(
+    client: &RpcClient,
+    registry_pk: &Pubkey,
+    category: Option<u64>,
+    page: usize,
+    page_size: usize
+) -> Result<Vec<ListedContract>> {
+    let account = client.get_account(registry_pk)?;
+    let registry = Registry::load_from_bytes(account.data.as_slice())?;
+    if registry.account_flags != (AccountFlag::Initialized | AccountFlag::Registry).bits() {
+        return Err(anyhow!("{} is not a registry", registry_pk));
+    }
+
+    Ok(registry.entries().iter().rev()
+        .filter(|entry| category.map_or(true, |c| entry.category == c))
+        .skip(page * page_size)
+        .take(page_size)
+        .map(ListedContract::from)
+        .collect())
+}
@@ -0,0 +1,41 @@
+use anyhow::{anyhow, Result};
+use omega::instruction::find_oracle_reputation_address;
+use omega::state::{AccountFlag, Loadable, OracleReputation};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+
+/// Track record of an oracle
+#[derive(Clone, Debug, PartialEq)]
+pub struct OracleStats {
+    pub oracle: Pubkey,
+    pub num_resolved: u64,
+    pub average_delay: u64,  // seconds between exp_time and resolution
+    pub disputes_lost: u64,
+    pub auto_expiries: u64,
+}
+
+impl From<&OracleReputation> for OracleStats {
+    fn from(reputation: &OracleReputation) -> Self {
+        OracleStats {
+            oracle: reputation.oracle,
+            num_resolved: reputation.num_resolved,
+            average_delay: reputation.average_delay(),
+            disputes_lost: reputation.disputes_lost,
+            auto_expiries: reputation.auto_expiries,
+        }
+    }
+}
+
+/// Returns None if nobody created a reputation for this oracle yet
+pub fn get_oracle_stats(client: &RpcClient, omega_program_id: &Pubkey, oracle_pk: &Pubkey) -> Result<Option<OracleStats>> {
+    let (oracle_reputation_pk, _) = find_oracle_reputation_address(omega_program_id, oracle_pk);
+    let account = match client.get_account(&oracle_reputation_pk) {
+        Ok(account) => account,
+        Err(_) => return Ok(None)
+    };
+    let reputation = OracleReputation::load_from_bytes(account.data.as_slice())?;
+    if reputation.account_flags != (AccountFlag::Initialized | AccountFlag::OracleReputation).bits() {
+        return Err(anyhow!("{} is not an oracle reputation", oracle_reputation_pk));
+    }
+    Ok(Some(OracleStats::from(reputation)))
+}
@@ -0,0 +1,45 @@
+use anyhow::{anyhow, Result};
+use omega::state::{AccountFlag, Loadable, OmegaContract};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::hash::hash;
+use solana_sdk::pubkey::Pubkey;
+
+/// How and when a contract was resolved
+#[derive(Clone, Debug, PartialEq)]
+pub struct Resolution {
+    pub winner: Pubkey,
+    pub slot: u64,
+    pub timestamp: u64,
+    pub evidence_uri: String,
+    pub evidence_hash: [u8; 32],
+}
+
+impl Resolution {
+    /// Returns None if the contract has not been resolved yet
+    pub fn from_contract(contract: &OmegaContract) -> Option<Self> {
+        if contract.winner == Pubkey::default() {
+            return None;
+        }
+        Some(Resolution {
+            winner: contract.winner,
+            slot: contract.resolved_slot,
+            timestamp: contract.resolved_time,
+            evidence_uri: String::from_utf8_lossy(contract.evidence_uri()).into_owned(),
+            evidence_hash: contract.evidence_hash,
+        })
+    }
+
+    /// Check that `content` is the evidence the oracle committed to
+    pub fn verify_evidence(&self, content: &[u8]) -> bool {
+        hash(content).to_bytes() == self.evidence_hash
+    }
+}
+
+pub fn get_resolution(client: &RpcClient, omega_contract_pk: &Pubkey) -> Result<Option<Resolution>> {
+    let account = client.get_account(omega_contract_pk)?;
+    let contract = OmegaContract::load_from_bytes(account.data.as_slice())?;
+    if contract.account_flags != (AccountFlag::Initialized | AccountFlag::OmegaContract).bits() {
+        return Err(anyhow!("{} is not an omega contract", omega_contract_pk));
+    }
+    Ok(Resolution::from_contract(contract))
+}
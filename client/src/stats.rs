@@ -0,0 +1,43 @@
+use anyhow::{anyhow, Result};
+use omega::state::{AccountFlag, Loadable, OmegaContract};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+
+/// What is at stake in a contract, read from the contract account alone
+#[derive(Clone, Debug, PartialEq)]
+pub struct MarketStats {
+    pub open_interest: u64,  // supply of the most issued outcome
+    pub max_supply: u64,  // 0 if uncapped
+    pub sets_issued: u64,
+    pub sets_redeemed: u64,
+    pub outcomes_issued: u64,  // single outcome tokens from the LMSR market maker or parimutuel stakes
+    pub outcomes_redeemed: u64,
+    pub winner_redeemed: u64,
+    pub unique_issuers: u64,
+    pub last_activity: u64,  // 0 if nothing was issued or redeemed yet
+}
+
+impl From<&OmegaContract> for MarketStats {
+    fn from(contract: &OmegaContract) -> Self {
+        MarketStats {
+            open_interest: contract.open_interest(),
+            max_supply: contract.max_supply,
+            sets_issued: contract.stats.sets_issued,
+            sets_redeemed: contract.stats.sets_redeemed,
+            outcomes_issued: contract.stats.outcomes_issued,
+            outcomes_redeemed: contract.stats.outcomes_redeemed,
+            winner_redeemed: contract.stats.winner_redeemed,
+            unique_issuers: contract.stats.unique_issuers,
+            last_activity: contract.stats.last_activity,
+        }
+    }
+}
+
+pub fn get_market_stats(client: &RpcClient, omega_contract_pk: &Pubkey) -> Result<MarketStats> {
+    let account = client.get_account(omega_contract_pk)?;
+    let contract = OmegaContract::load_from_bytes(account.data.as_slice())?;
+    if contract.account_flags != (AccountFlag::Initialized | AccountFlag::OmegaContract).bits() {
+        return Err(anyhow!("{} is not an omega contract", omega_contract_pk));
+    }
+    Ok(MarketStats::from(contract))
+}
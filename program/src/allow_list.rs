@@ -0,0 +1,110 @@
+//! Merkle tree over the pubkeys allowed to issue sets of a gated contract. Only the root is stored
+//! on-chain, and each instruction issuing new tokens carries the proof for its signer.
+
+use solana_program::hash::hashv;
+use solana_program::pubkey::Pubkey;
+
+/// Keep leaves and inner nodes from ever hashing to the same value
+const LEAF_PREFIX: &[u8] = &[0];
+const NODE_PREFIX: &[u8] = &[1];
+
+pub fn leaf_hash(pubkey: &Pubkey) -> [u8; 32] {
+    hashv(&[LEAF_PREFIX, pubkey.as_ref()]).to_bytes()
+}
+
+/// Children are sorted so that a proof does not need to say which side each sibling is on
+fn node_hash(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[NODE_PREFIX, lo, hi]).to_bytes()
+}
+
+/// Each level of the tree, from the leaves up to the root. An odd node out is carried up as is
+fn levels(pubkeys: &[Pubkey]) -> Vec<Vec<[u8; 32]>> {
+    let mut levels = vec![pubkeys.iter().map(leaf_hash).collect::<Vec<_>>()];
+    while levels.last().unwrap().len() > 1 {
+        let next = levels.last().unwrap().chunks(2).map(|pair| match pair {
+            [a, b] => node_hash(a, b),
+            [a] => *a,
+            _ => unreachable!()
+        }).collect();
+        levels.push(next);
+    }
+    levels
+}
+
+/// Root of the allow-list made of `pubkeys`
+pub fn merkle_root(pubkeys: &[Pubkey]) -> [u8; 32] {
+    assert!(!pubkeys.is_empty());
+    levels(pubkeys).last().unwrap()[0]
+}
+
+/// Siblings on the path from `pubkeys[index]` to the root
+pub fn merkle_proof(pubkeys: &[Pubkey], index: usize) -> Vec<[u8; 32]> {
+    let levels = levels(pubkeys);
+    let mut proof = vec![];
+    let mut i = index;
+    for level in &levels[..levels.len() - 1] {
+        if let Some(sibling) = level.get(i ^ 1) {
+            proof.push(*sibling);
+        }
+        i /= 2;
+    }
+    proof
+}
+
+/// Check that `pubkey` is in the allow-list with `root`. `proof` is the concatenated siblings
+pub fn verify_proof(root: &[u8; 32], pubkey: &Pubkey, proof: &[u8]) -> bool {
+    if !proof.len().is_multiple_of(32) {
+        return false;
+    }
+    let computed = proof.chunks(32).fold(leaf_hash(pubkey), |node, sibling| {
+        let mut sibling_hash = [0u8; 32];
+        sibling_hash.copy_from_slice(sibling);
+        node_hash(&node, &sibling_hash)
+    });
+    computed == *root
+}
+
+/// Split the proofs IssueConditionalSet carries for the parent and the conditional contract: the number
+/// of parent siblings as a little-endian u64, then the parent siblings and the conditional ones.
+/// No data at all means no proofs
+pub fn split_proofs(data: &[u8]) -> Option<(&[u8], &[u8])> {
+    if data.is_empty() {
+        return Some((&[], &[]));
+    }
+    let mut num_siblings = [0u8; 8];
+    num_siblings.copy_from_slice(data.get(..8)?);
+    let parent_len = (u64::from_le_bytes(num_siblings) as usize).checked_mul(32)?;
+    let proofs = &data[8..];
+    if parent_len > proofs.len() {
+        return None;
+    }
+    Some(proofs.split_at(parent_len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merkle_proofs() {
+        let pubkeys: Vec<Pubkey> = (0..5).map(|_| Pubkey::new_unique()).collect();
+        let root = merkle_root(&pubkeys);
+        for (i, pubkey) in pubkeys.iter().enumerate() {
+            let proof = merkle_proof(&pubkeys, i).concat();
+            assert!(verify_proof(&root, pubkey, &proof));
+            assert!(!verify_proof(&root, &Pubkey::new_unique(), &proof));
+            assert!(!verify_proof(&root, pubkey, &proof[..proof.len() - 1]));
+        }
+
+        // a single pubkey is its own root
+        assert!(verify_proof(&merkle_root(&pubkeys[..1]), &pubkeys[0], &[]));
+
+        let mut proofs = 1u64.to_le_bytes().to_vec();
+        proofs.extend_from_slice(&[1u8; 32]);
+        proofs.extend_from_slice(&[2u8; 64]);
+        assert_eq!(split_proofs(&proofs), Some((&[1u8; 32][..], &[2u8; 64][..])));
+        assert_eq!(split_proofs(&[]), Some((&[][..], &[][..])));
+        assert_eq!(split_proofs(&proofs[..39]), None);
+    }
+}
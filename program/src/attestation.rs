@@ -0,0 +1,99 @@
+//! Off-chain attestations of a contract's winner, signed by the oracle and checked on-chain
+//! through an ed25519 signature verification instruction in the same transaction.
+
+use std::convert::TryInto;
+
+use solana_program::pubkey::Pubkey;
+
+pub mod ed25519_program {
+    solana_program::declare_id!("Ed25519SigVerify111111111111111111111111111");
+}
+
+/// Keeps attestations from being valid messages in any other context
+pub const ATTESTATION_PREFIX: &[u8] = b"omega:resolve:";
+pub const ATTESTATION_LEN: usize = 14 + 32 + 8 + 8;
+
+/// Header of ed25519 instruction data: number of signatures and a padding byte
+const ED25519_HEADER_LEN: usize = 2;
+/// Offsets of signature, public key and message, each with the index of the instruction holding it
+const ED25519_OFFSETS_LEN: usize = 14;
+/// Instruction index meaning the data is in the ed25519 instruction itself
+const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+/// Message the oracle signs to declare `winner` (outcome index) of the contract at `timestamp`
+pub fn attestation_message(omega_contract: &Pubkey, winner: u64, timestamp: i64) -> Vec<u8> {
+    let mut message = Vec::with_capacity(ATTESTATION_LEN);
+    message.extend_from_slice(ATTESTATION_PREFIX);
+    message.extend_from_slice(omega_contract.as_ref());
+    message.extend_from_slice(&winner.to_le_bytes());
+    message.extend_from_slice(&timestamp.to_le_bytes());
+    message
+}
+
+/// Data of an ed25519 program instruction verifying one signature, with everything stored inline
+pub fn ed25519_instruction_data(signer: &Pubkey, signature: &[u8; 64], message: &[u8]) -> Vec<u8> {
+    let public_key_offset = ED25519_HEADER_LEN + ED25519_OFFSETS_LEN;
+    let signature_offset = public_key_offset + 32;
+    let message_offset = signature_offset + 64;
+
+    let mut data = vec![1u8, 0];
+    for &x in &[
+        signature_offset as u16,
+        CURRENT_INSTRUCTION,
+        public_key_offset as u16,
+        CURRENT_INSTRUCTION,
+        message_offset as u16,
+        message.len() as u16,
+        CURRENT_INSTRUCTION,
+    ] {
+        data.extend_from_slice(&x.to_le_bytes());
+    }
+    data.extend_from_slice(signer.as_ref());
+    data.extend_from_slice(signature);
+    data.extend_from_slice(message);
+    data
+}
+
+/// Signer and message of an ed25519 instruction that verifies exactly one signature over data
+/// stored in the instruction itself. The ed25519 program fails the transaction if the signature
+/// is invalid, so a successful parse means `signer` signed `message`.
+pub fn parse_ed25519_instruction(data: &[u8]) -> Option<(Pubkey, &[u8])> {
+    if data.len() < ED25519_HEADER_LEN + ED25519_OFFSETS_LEN || data[0] != 1 {
+        return None;
+    }
+    let offsets = &data[ED25519_HEADER_LEN..ED25519_HEADER_LEN + ED25519_OFFSETS_LEN];
+    let field = |i: usize| u16::from_le_bytes(offsets[2 * i..2 * i + 2].try_into().unwrap());
+    let (signature_index, public_key_offset, public_key_index) = (field(1), field(2) as usize, field(3));
+    let (message_offset, message_len, message_index) = (field(4) as usize, field(5) as usize, field(6));
+    if signature_index != CURRENT_INSTRUCTION
+        || public_key_index != CURRENT_INSTRUCTION
+        || message_index != CURRENT_INSTRUCTION {
+        return None;
+    }
+
+    let signer = data.get(public_key_offset..public_key_offset + 32)?;
+    let message = data.get(message_offset..message_offset + message_len)?;
+    Some((Pubkey::new(signer), message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ed25519_instruction_round_trip() {
+        let oracle = Pubkey::new_unique();
+        let message = attestation_message(&Pubkey::new_unique(), 1, 1_612_137_600);
+        assert_eq!(message.len(), ATTESTATION_LEN);
+
+        let data = ed25519_instruction_data(&oracle, &[7u8; 64], &message);
+        assert_eq!(parse_ed25519_instruction(&data), Some((oracle, message.as_slice())));
+
+        // data pointing into another instruction could be anything
+        let mut other = data.clone();
+        other[ED25519_HEADER_LEN + 12] = 0;
+        assert_eq!(parse_ed25519_instruction(&other), None);
+
+        assert_eq!(parse_ed25519_instruction(&data[..data.len() - 1]), None);
+    }
+}
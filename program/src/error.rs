@@ -37,6 +37,27 @@ pub enum OmegaErrorCode {
     BorrowError,
     InvalidOutcomeMintAuthority,
     InvalidWinner,
+    InvalidOutcome,
+    SlippageExceeded,
+    InvalidOrder,
+    OrderBookFull,
+    InvalidContractMode,
+    InvalidBreakpoints,
+    RegistryFull,
+    QuoteMintNotAllowed,
+    CreatorNotAllowed,
+    TimelockActive,
+    StalePrice,
+    InvalidAttestation,
+    TradingClosed,
+    InvalidReveal,
+    BondAtStake,
+    OracleLapsed,
+    VoteInconclusive,
+    NotOnAllowList,
+    IssuanceCapExceeded,
+    VaultInsolvent,
+    MathOverflow,
     Unknown = 1000,
 
     // This contains the line number in the lower 16 bits,
@@ -54,6 +75,8 @@ pub enum SourceFileId {
     State = 1,
     #[error("src/instruction.rs")]
     Instruction = 2,
+    #[error("src/price.rs")]
+    Price = 3,
 }
 
 #[macro_export]
@@ -4,6 +4,11 @@ use solana_program::instruction::{AccountMeta, Instruction};
 use solana_program::program_error::ProgramError;
 use solana_program::pubkey::Pubkey;
 
+use crate::attestation::{attestation_message, ed25519_instruction_data, ed25519_program};
+use crate::price::PriceSourceKind;
+use crate::state::{Comparison, ISSUANCE_RECORD_SEED, MAX_OUTCOMES, ORACLE_REPUTATION_SEED, PROGRAM_CONFIG_SEED, Side,
+                   TieBreak, VOTE_RECORD_SEED};
+
 #[repr(C)]
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum OmegaInstruction {
@@ -17,6 +22,18 @@ pub enum OmegaInstruction {
     ///   3. `[]` quote_vault - Quote currency SPL token account owned by Omega program
     ///   4. `[]` signer_acc - The account pointed to by signer key
     ///   5. `[]` Rent sysvar account
+    ///   6. `[]` program_config_acc - ProgramConfig at the address derived from PROGRAM_CONFIG_SEED.
+    ///      Its default oracle fee rate is copied to the contract
+    ///   7. `[signer]` creator_acc - must be allowed by the program config
+    ///   8. `[writable]` registry_acc - Registry of creator_acc the contract is appended to
+    ///   9. `[]` clock_acc - sysvar Clock
+    ///
+    ///   Then `[]` mint account for each outcome
+    ///
+    ///   Then, if the program config sets a creator bond:
+    ///   `[writable]` creator_quote_acc - quote token account the bond is paid from
+    ///   `[writable]` creator_bond_escrow_acc - quote token account owned by signer_acc
+    ///   `[]` spl_token_program_acc
     InitOmegaContract {
         exp_time: u64,
         auto_exp_time: u64,
@@ -27,7 +44,7 @@ pub enum OmegaInstruction {
     ///
     /// Accounts expected by this instruction:
     ///
-    ///   0. `[]` OmegaContract data
+    ///   0. `[writable]` OmegaContract data
     ///   1. `[signer]` user's solana account (the owner)
     ///   2. `[writable]` user's quote currency wallet
     ///   3. `[writable]` omega's quote currency vault
@@ -36,8 +53,17 @@ pub enum OmegaInstruction {
     ///   6. `[writable]` outcome0 mint account
     ///   7. `[writable]` outcome0 user wallet account
     ///
-    ///   Repeat 6, 7 for each outcome.
-    ///   Total accounts: 6 + 2 * num_outcomes
+    ///   Repeat 6, 7 for each outcome, then optionally:
+    ///
+    ///   `[]` clock_acc - sysvar Clock, to stamp the contract's last activity
+    ///
+    ///   Total accounts: 6 + 2 * num_outcomes, or 7 with the clock
+    ///
+    ///   Optionally, to count the issuance on the user's IssuanceRecord (required with a wallet cap),
+    ///   the same three accounts as LmsrBuy, see `with_issuance_record`
+    ///
+    ///   If the contract has an allow-list, the instruction data continues with the merkle proof
+    ///   that the user is on it, see `with_allow_list_proof`
     IssueSet {
         quantity: u64,
     },
@@ -47,18 +73,21 @@ pub enum OmegaInstruction {
     ///
     /// Accounts expected by this instruction:
     ///
-    ///   0. `[]` omega_contract_acc - OmegaContract data
+    ///   0. `[writable]` omega_contract_acc - OmegaContract data
     ///   1. `[signer]` user_acc - user's solana account (the owner)
     ///   2. `[writable]` user_quote_acc - user's quote currency wallet
     ///   3. `[writable]` vault_acc - omega's quote currency vault
     ///   4. `[]` spl_token_program_acc - account pointed to by SPL token program id
     ///   5. `[]` omega_signer_acc - account pointed to by hashing signer nonce, omega contract
-    ///           pubkey and omega program id
+    ///      pubkey and omega program id
     ///   6. `[writable]` outcome0_mint_acc - outcome0 mint account
     ///   7. `[writable]` outcome0_user_acc - user wallet account for outcome0
     ///
-    ///   Repeat 6, 7 for each outcome.
-    ///   Total accounts: 6 + 2 * num_outcomes
+    ///   Repeat 6, 7 for each outcome, then optionally:
+    ///
+    ///   `[]` clock_acc - sysvar Clock, to stamp the contract's last activity
+    ///
+    ///   Total accounts: 6 + 2 * num_outcomes, or 7 with the clock
     RedeemSet {
         quantity: u64
     },
@@ -68,13 +97,13 @@ pub enum OmegaInstruction {
     ///
     /// Accounts expected by this instruction:
     ///
-    ///   0. `[]` omega_contract_acc - OmegaContract data
+    ///   0. `[writable]` omega_contract_acc - OmegaContract data
     ///   1. `[signer]` user_acc - user's solana account (the owner)
     ///   2. `[writable]` user_quote_acc - user's quote currency wallet
     ///   3. `[writable]` vault_acc - omega's quote currency vault
     ///   4. `[]` spl_token_program_acc - account pointed to by SPL token program id
     ///   5. `[]` omega_signer_acc - account pointed to by hashing signer nonce, omega contract
-    ///           pubkey and omega program id
+    ///      pubkey and omega program id
     ///   6. `[writable]` winner_mint_acc - mint of winning outcome
     ///   7. `[writable]` winner_user_acc - user wallet of winning outcome
     ///   8. `[]` clock_acc - sysvar Clock
@@ -84,6 +113,8 @@ pub enum OmegaInstruction {
 
     /// Designated oracle will pick winner
     /// This will fail if time < expiration time specified in contract
+    /// The backup oracle picks the winner instead once the oracle missed a heartbeat
+    /// Remaining instruction data is the utf-8 URI of the evidence whose hash is `evidence_hash`
     ///
     /// Accounts expected by this instruction:
     ///
@@ -91,168 +122,2834 @@ pub enum OmegaInstruction {
     ///   1. `[signer]` oracle_acc - pubkey of oracle
     ///   2. `[]` winner_acc - mint pubkey of winning outcome
     ///   3. `[]` clock_acc - sysvar Clock
-    Resolve
-}
+    ///   4. `[writable]` vault_acc - omega's quote currency vault
+    ///   5. `[]` outcome0_mint_acc - outcome0 mint account, to check the vault against
+    ///
+    ///   Repeat 5 for each outcome.
+    ///
+    ///   Only if the contract has an oracle fee, to pay it:
+    ///   5 + num_outcomes. `[writable]` oracle_quote_acc - quote token account of oracle
+    ///   6 + num_outcomes. `[]` spl_token_program_acc
+    ///   7 + num_outcomes. `[]` omega_signer_acc
+    ///
+    ///   Optionally, to record the resolution in the oracle's reputation right away:
+    ///   8 + num_outcomes. `[writable]` oracle_reputation_acc - 5 + num_outcomes if the contract has no oracle fee
+    Resolve {
+        evidence_hash: [u8; 32],
+    },
 
-impl OmegaInstruction {
-    /// First four bytes of instruction data is the index of the instruction (e.g. 0 -> InitOmegaContract)
-    /// Remaining data is the actual instruction contents
-    pub fn unpack(input: &[u8]) -> Option<Self> {
-        let (&discrim, data) = array_refs![input, 4; ..;];
-        let discrim = u32::from_le_bytes(discrim);
+    /// Turn on the LMSR market maker for this contract with liquidity parameter `b`
+    /// The creator deposits b * ln(num_outcomes) quote tokens, which is the most the market maker can lose
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` omega_contract_acc
+    ///   1. `[signer]` creator_acc - funds the market maker and receives the surplus after resolution
+    ///   2. `[writable]` creator_quote_acc - creator's quote currency wallet
+    ///   3. `[writable]` vault_acc - omega's quote currency vault
+    ///   4. `[]` spl_token_program_acc - account pointed to by SPL token program id
+    InitLmsr {
+        liquidity: u64
+    },
 
-        Some(match discrim {
-            0 => {
-                let data = array_ref![data, 0, 24];
-                let (exp_time, auto_exp_time, signer_nonce) = array_refs![data, 8, 8, 8];
-                OmegaInstruction::InitOmegaContract {
-                    exp_time: u64::from_le_bytes(*exp_time),
-                    auto_exp_time: u64::from_le_bytes(*auto_exp_time),
-                    signer_nonce: u64::from_le_bytes(*signer_nonce),
-                }
-            }
-            1 => {
-                let quantity = array_ref![data, 0, 8];
-                OmegaInstruction::IssueSet {
-                    quantity: u64::from_le_bytes(*quantity)
-                }
-            }
-            2 => {
-                let quantity = array_ref![data, 0, 8];
-                OmegaInstruction::RedeemSet {
-                    quantity: u64::from_le_bytes(*quantity)
-                }
-            }
-            3 => {
-                let quantity = array_ref![data, 0, 8];
-                OmegaInstruction::RedeemWinner {
-                    quantity: u64::from_le_bytes(*quantity)
-                }
-            }
-            4 => {
-                OmegaInstruction::Resolve
-            }
-            _ => { return None; }
-        })
-    }
-    pub fn pack(&self) -> Vec<u8> {
-        bincode::serialize(self).unwrap()
-    }
+    /// Buy `quantity` of one outcome token from the LMSR market maker
+    /// Will fail if the cost is greater than `max_cost` or the contract has expired
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` omega_contract_acc
+    ///   1. `[signer]` user_acc - user's solana account (the owner)
+    ///   2. `[writable]` user_quote_acc - user's quote currency wallet
+    ///   3. `[writable]` vault_acc - omega's quote currency vault
+    ///   4. `[]` spl_token_program_acc - account pointed to by SPL token program id
+    ///   5. `[]` omega_signer_acc - account pointed to by hashing signer nonce, omega contract
+    ///      pubkey and omega program id
+    ///   6. `[writable]` outcome_mint_acc - mint of the outcome being bought
+    ///   7. `[writable]` outcome_user_acc - user wallet of the outcome being bought
+    ///   8. `[]` clock_acc - sysvar Clock
+    ///   9. `[]` outcome0_mint_acc - outcome0 mint account, to check the vault against
+    ///
+    ///   Repeat 9 for each outcome.
+    ///
+    ///   Optionally, to count the issuance on the user's IssuanceRecord (required with a wallet cap),
+    ///   see `with_issuance_record`:
+    ///   9 + num_outcomes. `[writable]` issuance_record_acc - user's IssuanceRecord, created at the user's
+    ///           expense if empty
+    ///   10 + num_outcomes. `[]` system_program_acc
+    ///   11 + num_outcomes. `[]` rent_acc - sysvar Rent
+    ///
+    ///   If the contract has an allow-list, the instruction data continues with the merkle proof
+    ///   that the user is on it, see `with_allow_list_proof`
+    LmsrBuy {
+        outcome: u64,
+        quantity: u64,
+        max_cost: u64
+    },
 
-}
+    /// Sell `quantity` of one outcome token back to the LMSR market maker
+    /// Will fail if the proceeds are less than `min_proceeds` or the contract has expired
+    ///
+    /// Accounts expected by this instruction are the same as LmsrBuy, without the issuance record
+    LmsrSell {
+        outcome: u64,
+        quantity: u64,
+        min_proceeds: u64
+    },
 
+    /// Creator withdraws whatever the vault holds beyond what outcome token holders can redeem
+    /// Will fail if contract not yet resolved or auto expired, or while the oracle bond is at stake
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` omega_contract_acc
+    ///   1. `[signer]` creator_acc
+    ///   2. `[writable]` creator_quote_acc - creator's quote currency wallet
+    ///   3. `[writable]` vault_acc - omega's quote currency vault
+    ///   4. `[]` spl_token_program_acc - account pointed to by SPL token program id
+    ///   5. `[]` omega_signer_acc - account pointed to by hashing signer nonce, omega contract
+    ///      pubkey and omega program id
+    ///   6. `[]` clock_acc - sysvar Clock
+    ///   7. `[]` outcome0_mint_acc - outcome0 mint account
+    ///
+    ///   Repeat 7 for each outcome.
+    ///   Total accounts: 7 + num_outcomes
+    LmsrWithdraw,
 
-/// The outcome_pks are public keys for the SPL token mint of each outcome
-/// Make sure the token has 0 supply and the authority is the key generated by gen_vault_signer_key
-/// using the signer nonce
-pub fn init_omega_contract(
-    program_id: &Pubkey,
-    omega_contract_pk: &Pubkey,
-    oracle_pk: &Pubkey,
-    quote_mint_pk: &Pubkey,
-    vault_pk: &Pubkey,
-    signer_pk: &Pubkey,
-    outcome_pks: &[Pubkey],
-    exp_time: u64,
-    auto_exp_time: u64,
-    signer_nonce: u64,
-    details_str: &str
-) -> Result<Instruction, ProgramError> {
+    /// Initialize an order book for one outcome of the contract
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[]` omega_contract_acc
+    ///   1. `[writable]` order_book_acc - zeroed account owned by Omega program
+    ///   2. `[]` outcome_mint_acc - mint of the outcome traded on this book
+    ///   3. `[]` quote_escrow_acc - quote currency SPL token account owned by omega signer
+    ///   4. `[]` outcome_escrow_acc - outcome SPL token account owned by omega signer
+    ///   5. `[]` Rent sysvar account
+    InitOrderBook,
 
-    let mut accounts = vec![
-        AccountMeta::new(*omega_contract_pk, false),
-        AccountMeta::new_readonly(*oracle_pk, false),
-        AccountMeta::new_readonly(*quote_mint_pk, false),
-        AccountMeta::new_readonly(*vault_pk, false),
-        AccountMeta::new_readonly(*signer_pk, false),
-        AccountMeta::new_readonly(solana_program::sysvar::rent::ID, false),
-    ];
+    /// Place a limit order. Bids escrow price * quantity quote tokens, asks escrow quantity outcome tokens.
+    /// `price` is in basis points of one quote token and `quantity` must be a multiple of PRICE_SCALE
+    /// Will fail if the contract has expired or is resolved
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[]` omega_contract_acc
+    ///   1. `[writable]` order_book_acc
+    ///   2. `[signer]` owner_acc - user's solana account (the owner)
+    ///   3. `[writable]` owner_src_acc - user's quote wallet for bids, outcome wallet for asks
+    ///   4. `[writable]` escrow_acc - book's quote escrow for bids, outcome escrow for asks
+    ///   5. `[]` spl_token_program_acc - account pointed to by SPL token program id
+    ///   6. `[]` clock_acc - sysvar Clock
+    ///   7. `[]` vault_acc - omega's quote currency vault
+    ///   8. `[]` outcome0_mint_acc - outcome0 mint account, to check the vault against
+    ///
+    ///   Repeat 8 for each outcome.
+    ///   Total accounts: 8 + num_outcomes
+    PlaceOrder {
+        side: u64,
+        price: u64,
+        quantity: u64
+    },
 
-    for pk in outcome_pks {
-        accounts.push(AccountMeta::new(*pk, false));
-    }
+    /// Remove the unfilled part of an order from the book and withdraw everything it is owed
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[]` omega_contract_acc
+    ///   1. `[writable]` order_book_acc
+    ///   2. `[signer]` owner_acc - user's solana account (the owner)
+    ///   3. `[writable]` owner_quote_acc - user's quote currency wallet
+    ///   4. `[writable]` owner_outcome_acc - user's outcome wallet
+    ///   5. `[writable]` quote_escrow_acc
+    ///   6. `[writable]` outcome_escrow_acc
+    ///   7. `[]` spl_token_program_acc - account pointed to by SPL token program id
+    ///   8. `[]` omega_signer_acc - account pointed to by hashing signer nonce, omega contract
+    ///      pubkey and omega program id
+    ///   9. `[]` vault_acc - omega's quote currency vault
+    ///   10. `[]` outcome0_mint_acc - outcome0 mint account, to check the vault against
+    ///
+    ///   Repeat 10 for each outcome.
+    ///   Total accounts: 10 + num_outcomes
+    CancelOrder {
+        order_id: u64
+    },
 
-    let instr = OmegaInstruction::InitOmegaContract {
-        exp_time,
-        auto_exp_time,
-        signer_nonce
-    };
+    /// Withdraw the filled part of an order. The order stays on the book if it is not completely filled
+    ///
+    /// Accounts expected by this instruction are the same as CancelOrder
+    SettleOrder {
+        order_id: u64
+    },
 
-    let details = details_str.as_bytes();
-    let mut data = instr.pack();
-    data.extend_from_slice(details);
+    /// Crank: match the best bid against the best ask of one book up to `limit` times
+    /// Trades execute at the price of the order that was placed first
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[]` omega_contract_acc
+    ///   1. `[writable]` order_book_acc
+    MatchOrders {
+        limit: u64
+    },
 
-    Ok(Instruction {
-        program_id: *program_id,
-        accounts,
-        data
-    })
-}
+    /// Crank: match the best orders on `side` across the books of every outcome up to `limit` times
+    /// Bids whose prices add up to at least 1 are filled by issuing new sets,
+    /// asks whose prices add up to at most 1 are filled by redeeming sets.
+    /// The difference goes to the most recent orders.
+    /// Bids are not matched while the contract has an allow-list or a wallet cap, since the bidders can't prove
+    /// they are on it nor have their issuance counted
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` omega_contract_acc
+    ///   1. `[writable]` vault_acc - omega's quote currency vault
+    ///   2. `[]` spl_token_program_acc - account pointed to by SPL token program id
+    ///   3. `[]` omega_signer_acc - account pointed to by hashing signer nonce, omega contract
+    ///      pubkey and omega program id
+    ///   4. `[]` clock_acc - sysvar Clock
+    ///   5. `[writable]` outcome0_order_book_acc
+    ///   6. `[writable]` outcome0_quote_escrow_acc
+    ///   7. `[writable]` outcome0_outcome_escrow_acc
+    ///   8. `[writable]` outcome0_mint_acc
+    ///
+    ///   Repeat 5 - 8 for each outcome.
+    ///   Total accounts: 5 + 4 * num_outcomes
+    MatchSets {
+        side: u64,
+        limit: u64
+    },
 
-pub fn issue_set(
-    program_id: &Pubkey,
-    omega_contract_pk: &Pubkey,
-    user_pk: &Pubkey,
-    user_quote_pk: &Pubkey,
-    vault_pk: &Pubkey,
-    signer_pk: &Pubkey,
-    outcome_pks: &[(Pubkey, Pubkey)],  // (mint, user_acc)
-    quantity: u64
-) -> Result<Instruction, ProgramError> {
+    /// Designated oracle turns the contract into a parimutuel pool before anything is issued
+    /// After resolution the winning tickets split the whole pot pro rata, minus `fee_bps` of the pot
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` omega_contract_acc
+    ///   1. `[signer]` oracle_acc - pubkey of oracle
+    ///   2. `[]` fee_acc - quote currency wallet receiving the fee
+    ///   3. `[]` outcome0_mint_acc - outcome0 mint account
+    ///
+    ///   Repeat 3 for each outcome.
+    ///   Total accounts: 3 + num_outcomes
+    InitParimutuel {
+        fee_bps: u64
+    },
 
-    let mut accounts = vec![
-        AccountMeta::new_readonly(*omega_contract_pk, false),
-        AccountMeta::new_readonly(*user_pk, true),
-        AccountMeta::new(*user_quote_pk, false),
-        AccountMeta::new(*vault_pk, false),
-        AccountMeta::new_readonly(spl_token::id(), false),
-        AccountMeta::new_readonly(*signer_pk, false),
-    ];
+    /// Stake quote tokens on one outcome of a parimutuel contract and receive as many tickets
+    /// Will fail if the contract has expired
+    ///
+    /// Accounts expected by this instruction, the optional issuance record and the allow-list proof are the same as LmsrBuy
+    Stake {
+        outcome: u64,
+        quantity: u64
+    },
 
-    for (outcome_mint_pk, outcome_user_pk) in outcome_pks {
-        accounts.push(AccountMeta::new(*outcome_mint_pk, false));
-        accounts.push(AccountMeta::new(*outcome_user_pk, false));
-    }
+    /// Send the fee and any rounding dust of a resolved parimutuel contract to the fee wallet
+    /// Will fail while the oracle bond is at stake
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[]` omega_contract_acc
+    ///   1. `[writable]` fee_acc - quote currency wallet receiving the fee
+    ///   2. `[writable]` vault_acc - omega's quote currency vault
+    ///   3. `[]` spl_token_program_acc - account pointed to by SPL token program id
+    ///   4. `[]` omega_signer_acc - account pointed to by hashing signer nonce, omega contract
+    ///      pubkey and omega program id
+    ///   5. `[]` winner_mint_acc - mint of winning outcome
+    ///   6. `[]` clock_acc - sysvar Clock
+    CollectParimutuelFee,
 
-    let instr = OmegaInstruction::IssueSet { quantity };
-    let data = instr.pack();
+    /// Buy `quantity` of one outcome with quote tokens only: issue `quantity` sets and sell every
+    /// other outcome into the best bids of its order book. Will fail if the user would pay more than `max_cost`
+    /// `quantity` must be a multiple of PRICE_SCALE
+    /// If the contract has the LMSR market maker on, the outcome is bought from it instead,
+    /// with the same accounts as LmsrBuy
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` omega_contract_acc
+    ///   1. `[signer]` user_acc - user's solana account (the owner)
+    ///   2. `[writable]` user_quote_acc - user's quote currency wallet
+    ///   3. `[writable]` vault_acc - omega's quote currency vault
+    ///   4. `[]` spl_token_program_acc - account pointed to by SPL token program id
+    ///   5. `[]` omega_signer_acc - account pointed to by hashing signer nonce, omega contract
+    ///      pubkey and omega program id
+    ///   6. `[writable]` outcome_mint_acc - mint of the outcome being bought
+    ///   7. `[writable]` outcome_user_acc - user wallet of the outcome being bought
+    ///   8. `[]` clock_acc - sysvar Clock
+    ///   9. `[writable]` order_book_acc - order book of the first other outcome
+    ///   10. `[writable]` quote_escrow_acc
+    ///   11. `[writable]` outcome_escrow_acc
+    ///   12. `[writable]` other_mint_acc
+    ///
+    ///   Repeat 9 - 12 for each other outcome in order.
+    ///   Total accounts: 9 + 4 * (num_outcomes - 1)
+    ///
+    ///   Optionally, to count the issuance on the user's IssuanceRecord (required with a wallet cap),
+    ///   the same three accounts as LmsrBuy, see `with_issuance_record`
+    ///
+    ///   If the contract has an allow-list, the instruction data continues with the merkle proof
+    ///   that the user is on it, see `with_allow_list_proof`
+    BuyOutcome {
+        outcome: u64,
+        quantity: u64,
+        max_cost: u64
+    },
 
-    Ok(Instruction {
-        program_id: *program_id,
-        accounts,
-        data
-    })
-}
+    /// Sell `quantity` of one outcome for quote tokens only: buy every other outcome from the best asks
+    /// of its order book and redeem the sets. Will fail if the user would receive less than `min_proceeds`
+    /// `quantity` must be a multiple of PRICE_SCALE
+    /// If the contract has the LMSR market maker on, the outcome is sold to it instead,
+    /// with the same accounts as LmsrSell
+    ///
+    /// Accounts expected by this instruction are the same as BuyOutcome, without the issuance record
+    SellOutcome {
+        outcome: u64,
+        quantity: u64,
+        min_proceeds: u64
+    },
 
+    /// Designated oracle makes this a conditional contract of `parent_outcome` in the parent contract
+    /// The quote mint of this contract must be the mint of that outcome
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` omega_contract_acc
+    ///   1. `[signer]` oracle_acc - pubkey of oracle
+    ///   2. `[]` parent_contract_acc - the parent OmegaContract
+    InitConditional {
+        parent_outcome: u64
+    },
 
-pub fn redeem_set(
-    program_id: &Pubkey,
-    omega_contract_pk: &Pubkey,
-    user_pk: &Pubkey,
-    user_quote_pk: &Pubkey,
-    vault_pk: &Pubkey,
-    signer_pk: &Pubkey,
-    outcome_pks: &[(Pubkey, Pubkey)],  // (mint, user_acc)
-    quantity: u64
-) -> Result<Instruction, ProgramError> {
+    /// Issue one set of the parent contract for each parent quote token deposited, keep the parent outcome
+    /// backing the conditional contract in its vault and issue one set of the conditional contract instead
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` omega_contract_acc - the conditional OmegaContract
+    ///   1. `[writable]` parent_contract_acc - the parent OmegaContract
+    ///   2. `[signer]` user_acc - user's solana account (the owner)
+    ///   3. `[writable]` user_quote_acc - user's wallet of the parent's quote currency
+    ///   4. `[writable]` parent_vault_acc - parent's quote currency vault
+    ///   5. `[writable]` vault_acc - conditional contract's vault
+    ///   6. `[]` spl_token_program_acc - account pointed to by SPL token program id
+    ///   7. `[]` parent_signer_acc - signer key of the parent contract
+    ///   8. `[]` omega_signer_acc - signer key of the conditional contract
+    ///   9. `[]` clock_acc - sysvar Clock
+    ///   10. `[writable]` parent_outcome0_mint_acc
+    ///   11. `[writable]` parent_outcome0_user_acc - must be vault_acc for the outcome backing this contract
+    ///
+    ///   Repeat 10, 11 for each parent outcome, then
+    ///   Repeat `[writable]` outcome mint, `[writable]` outcome user wallet for each conditional outcome
+    ///   Total accounts: 10 + 2 * parent num_outcomes + 2 * num_outcomes
+    ///
+    ///   Optionally, to count the issuance on the user's IssuanceRecords (required if either contract has
+    ///   a wallet cap), the same three accounts as LmsrBuy for the parent and then for the conditional
+    ///   contract, see `with_issuance_record`
+    ///
+    ///   If either contract has an allow-list, the instruction data continues with the merkle proofs
+    ///   that the user is on them, see `with_conditional_allow_list_proofs`
+    IssueConditionalSet {
+        quantity: u64
+    },
 
-    let mut accounts = vec![
-        AccountMeta::new_readonly(*omega_contract_pk, false),
-        AccountMeta::new_readonly(*user_pk, true),
-        AccountMeta::new(*user_quote_pk, false),
-        AccountMeta::new(*vault_pk, false),
-        AccountMeta::new_readonly(spl_token::id(), false),
-        AccountMeta::new_readonly(*signer_pk, false),
-    ];
+    /// Reverse of IssueConditionalSet: burn one set of the conditional contract and the other parent outcomes
+    /// to receive the parent's quote currency
+    ///
+    /// Accounts expected by this instruction are the same as IssueConditionalSet, without the issuance records
+    RedeemConditionalSet {
+        quantity: u64
+    },
 
-    for (outcome_mint_pk, outcome_user_pk) in outcome_pks {
-        accounts.push(AccountMeta::new(*outcome_mint_pk, false));
+    /// Redeem winning tokens of a resolved conditional contract for the parent's quote currency
+    /// Will fail unless the parent outcome backing this contract has won or the parent auto expired,
+    /// and while the oracle bond of either contract is at stake
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` omega_contract_acc - the conditional OmegaContract
+    ///   1. `[writable]` parent_contract_acc - the parent OmegaContract
+    ///   2. `[signer]` user_acc - user's solana account (the owner)
+    ///   3. `[writable]` user_quote_acc - user's wallet of the parent's quote currency
+    ///   4. `[writable]` parent_vault_acc - parent's quote currency vault
+    ///   5. `[writable]` vault_acc - conditional contract's vault
+    ///   6. `[]` spl_token_program_acc - account pointed to by SPL token program id
+    ///   7. `[]` parent_signer_acc - signer key of the parent contract
+    ///   8. `[]` omega_signer_acc - signer key of the conditional contract
+    ///   9. `[writable]` winner_mint_acc - mint of winning conditional outcome
+    ///   10. `[writable]` winner_user_acc - user wallet of winning conditional outcome
+    ///   11. `[writable]` collateral_mint_acc - mint of the parent outcome backing this contract
+    ///   12. `[]` clock_acc - sysvar Clock
+    ///   13. `[]` parent_outcome0_mint_acc - mint of the parent's outcome0, to check the parent vault against
+    ///
+    ///   Repeat 13 for each outcome of the parent.
+    ///   Total accounts: 13 + parent num_outcomes
+    RedeemConditionalWinner {
+        quantity: u64
+    },
+
+    /// Designated oracle turns the contract into a group of mutually exclusive buckets split at `breakpoints`
+    /// Outcome i covers breakpoints[i - 1] <= value < breakpoints[i], so there must be num_outcomes - 1
+    /// strictly increasing breakpoints
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` omega_contract_acc
+    ///   1. `[signer]` oracle_acc - pubkey of oracle
+    InitBucketGroup {
+        num_breakpoints: u64,
+        breakpoints: [i64; MAX_OUTCOMES - 1]
+    },
+
+    /// Designated oracle resolves a bucket group with the observed value
+    /// This will fail if time < expiration time specified in contract
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` omega_contract_acc
+    ///   1. `[signer]` oracle_acc - pubkey of oracle
+    ///   2. `[]` clock_acc - sysvar Clock
+    ///   3. `[]` vault_acc - omega's quote currency vault
+    ///   4. `[]` outcome0_mint_acc - outcome0 mint account, to check the vault against
+    ///
+    ///   Repeat 4 for each outcome.
+    ///   Total accounts: 4 + num_outcomes
+    ResolveValue {
+        value: i64
+    },
+
+    /// Initialize a template for a recurring contract. Remaining instruction data is the details
+    /// template, which may contain the placeholders {index}, {exp_time} and {exp_date}
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` market_series_acc
+    ///   1. `[]` oracle_acc - pubkey of oracle for every contract in the series
+    ///   2. `[]` Quote currency mint
+    ///   3. `[]` Rent sysvar account
+    ///   4. `[]` program_config_acc - ProgramConfig at the address derived from PROGRAM_CONFIG_SEED
+    ///   5. `[signer]` creator_acc - must be allowed by the program config
+    InitMarketSeries {
+        num_outcomes: u64,
+        first_exp_time: u64,
+        period: u64,
+        resolve_window: u64,
+    },
+
+    /// Permissionless: create and initialize the next contract of a series at the address derived
+    /// from the series pubkey, the contract index and `contract_nonce`
+    /// The vault and outcome mints must be created beforehand as for InitOmegaContract
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` market_series_acc
+    ///   1. `[writable]` omega_contract_acc - uncreated account at the derived address
+    ///   2. `[writable, signer]` payer_acc - pays the rent of omega_contract_acc
+    ///   3. `[]` Quote currency mint
+    ///   4. `[]` quote_vault - Quote currency SPL token account owned by Omega program
+    ///   5. `[]` signer_acc - The account pointed to by signer key
+    ///   6. `[]` system_program_acc
+    ///   7. `[]` Rent sysvar account
+    ///   8. `[]` outcome0 mint account
+    ///
+    ///   Repeat 8 for each outcome.
+    ///   Total accounts: 8 + num_outcomes
+    CreateSeriesContract {
+        contract_nonce: u64,
+        signer_nonce: u64,
+    },
+
+    /// Initialize an empty registry for the contracts of one creator
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` registry_acc
+    ///   1. `[signer]` creator_acc
+    ///   2. `[]` Rent sysvar account
+    InitRegistry,
+
+    /// Designated oracle sets the category of its contract in the registry
+    /// This also refreshes the status of the entry from the contract
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` registry_acc
+    ///   1. `[]` omega_contract_acc
+    ///   2. `[signer]` oracle_acc - pubkey of oracle
+    UpdateRegistryEntry {
+        category: u64
+    },
+
+    /// Create the ProgramConfig singleton with the signer as admin
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` program_config_acc - uncreated account at the address derived from PROGRAM_CONFIG_SEED
+    ///   1. `[writable, signer]` admin_acc - pays the rent of program_config_acc
+    ///   2. `[]` system_program_acc
+    ///   3. `[]` Rent sysvar account
+    ///   4. `[]` treasury_acc - owner of the quote token accounts forfeited creator bonds are paid to
+    ///   5. `[]` quote_mint0 - quote mint allowed as collateral
+    ///
+    ///   Repeat 5 for each of the `num_quote_mints` allowed quote mints, then
+    ///   Repeat `[]` creator for each allowed creator
+    InitProgramConfig {
+        timelock: u64,
+        fee_bps: u64,
+        restrict_creators: u64,
+        num_quote_mints: u64,
+        creator_bond: u64,
+    },
+
+    /// Admin proposes new settings, which replace any pending proposal and can be applied
+    /// once the current timelock has passed
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` program_config_acc
+    ///   1. `[signer]` admin_acc
+    ///   2. `[]` new_admin_acc
+    ///   3. `[]` clock_acc - sysvar Clock
+    ///   4. `[]` treasury_acc - owner of the quote token accounts forfeited creator bonds are paid to
+    ///   5. `[]` quote_mint0 - quote mint allowed as collateral
+    ///
+    ///   Repeat 5 for each of the `num_quote_mints` allowed quote mints, then
+    ///   Repeat `[]` creator for each allowed creator
+    ProposeConfig {
+        timelock: u64,
+        fee_bps: u64,
+        restrict_creators: u64,
+        num_quote_mints: u64,
+        creator_bond: u64,
+    },
+
+    /// Permissionless: apply the pending settings after the timelock
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` program_config_acc
+    ///   1. `[]` clock_acc - sysvar Clock
+    ApplyConfig,
+
+    /// Designated oracle lets anyone resolve this binary contract from a price account
+    /// Outcome 0 wins if `price comparison threshold` holds and outcome 1 otherwise
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` omega_contract_acc
+    ///   1. `[signer]` oracle_acc - pubkey of oracle
+    ///   2. `[]` price_acc - account laid out as described by `source`
+    ///   3. `[]` price_program_acc - program owning price_acc, e.g. the Pyth program of the cluster
+    InitPriceResolution {
+        source: u64,
+        comparison: u64,
+        threshold: i64,
+        max_staleness: u64,
+    },
+
+    /// Permissionless: resolve the contract from its price account
+    /// This will fail if time < expiration time, the price account changed owner or the price was
+    /// not published within max_staleness seconds of the expiration time
+    /// The oracle picked the price account, so the resolution counts for its reputation
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` omega_contract_acc
+    ///   1. `[]` price_acc
+    ///   2. `[]` clock_acc - sysvar Clock
+    ///   3. `[]` vault_acc - omega's quote currency vault
+    ///   4. `[]` outcome0_mint_acc - outcome0 mint account, to check the vault against
+    ///
+    ///   Repeat 4 for each outcome.
+    ///
+    ///   Optionally, to record the resolution in the oracle's reputation right away:
+    ///   4 + num_outcomes. `[writable]` oracle_reputation_acc
+    ResolveFromPrice,
+
+    /// Permissionless: resolve the contract with an attestation the oracle signed off-chain
+    /// The instruction right before this one must be an ed25519 program instruction verifying the
+    /// oracle's signature over attestation::attestation_message(contract, winner, timestamp)
+    /// This will fail if time < expiration time or the attestation was made before it
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` omega_contract_acc
+    ///   1. `[]` instructions_acc - sysvar Instructions
+    ///   2. `[]` clock_acc - sysvar Clock
+    ///   3. `[]` vault_acc - omega's quote currency vault
+    ///   4. `[]` outcome0_mint_acc - outcome0 mint account, to check the vault against
+    ///
+    ///   Repeat 4 for each outcome.
+    ///   Total accounts: 4 + num_outcomes
+    ResolveWithAttestation {
+        winner: u64,
+        timestamp: i64,
+    },
+
+    /// Designated oracle commits to commitment_hash(winner, salt) instead of resolving right away
+    /// This closes trading on the order books. If the winner is not revealed within `reveal_window`
+    /// seconds the contract auto expires
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` omega_contract_acc
+    ///   1. `[signer]` oracle_acc - pubkey of oracle
+    ///   2. `[]` clock_acc - sysvar Clock
+    CommitResolution {
+        commitment: [u8; 32],
+        reveal_window: u64,
+    },
+
+    /// Resolve a committed contract. Anyone who knows the salt can reveal the winner
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` omega_contract_acc
+    ///   1. `[]` winner_acc - mint pubkey of winning outcome
+    ///   2. `[]` clock_acc - sysvar Clock
+    ///   3. `[]` vault_acc - omega's quote currency vault
+    ///   4. `[]` outcome0_mint_acc - outcome0 mint account, to check the vault against
+    ///
+    ///   Repeat 4 for each outcome.
+    ///   Total accounts: 4 + num_outcomes
+    RevealResolution {
+        salt: [u8; 32],
+    },
+
+    /// Designated oracle posts a bond in the quote mint before the contract expires
+    /// The bond is released after the dispute window if the resolution stands, and slashed to the
+    /// holders of the correct outcome if it is overturned or the contract auto expires
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` omega_contract_acc
+    ///   1. `[signer]` oracle_acc - pubkey of oracle
+    ///   2. `[writable]` oracle_quote_acc - quote token account of oracle
+    ///   3. `[writable]` escrow_acc - quote token account owned by omega_signer_acc
+    ///   4. `[]` spl_token_program_acc
+    ///   5. `[]` clock_acc - sysvar Clock
+    InitOracleBond {
+        amount: u64,
+        dispute_window: u64,
+    },
+
+    /// Return the bond to the oracle once the dispute window after resolution has passed
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` omega_contract_acc
+    ///   1. `[writable]` escrow_acc
+    ///   2. `[writable]` oracle_quote_acc - quote token account of oracle
+    ///   3. `[]` spl_token_program_acc
+    ///   4. `[]` omega_signer_acc
+    ///   5. `[]` clock_acc - sysvar Clock
+    ReleaseOracleBond,
+
+    /// Move the bond into the vault after the contract auto expired without a winner.
+    /// It is split over all outcome tokens when they are redeemed, so no more can be issued
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` omega_contract_acc
+    ///   1. `[writable]` escrow_acc
+    ///   2. `[writable]` vault_acc
+    ///   3. `[]` spl_token_program_acc
+    ///   4. `[]` omega_signer_acc
+    ///   5. `[]` clock_acc - sysvar Clock
+    ///   6. `[]` outcome0_mint_acc - outcome0 mint account
+    ///
+    ///   Repeat 6 for each outcome.
+    ///   Total accounts: 6 + num_outcomes
+    SlashOracleBond,
+
+    /// Program config admin replaces the winner during the dispute window and slashes the bond
+    /// to the holders of the new winner
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` omega_contract_acc
+    ///   1. `[]` program_config_acc
+    ///   2. `[signer]` admin_acc
+    ///   3. `[]` winner_acc - mint of the correct outcome
+    ///   4. `[writable]` escrow_acc
+    ///   5. `[writable]` vault_acc
+    ///   6. `[]` spl_token_program_acc
+    ///   7. `[]` omega_signer_acc
+    ///   8. `[]` clock_acc - sysvar Clock
+    OverturnResolution,
+
+    /// Fund a reward for the oracle before the contract expires. `amount` quote tokens are moved
+    /// into the vault. The reward is `amount`, or `fee_bps` of open interest capped at `amount`,
+    /// and shrinks linearly from exp_time until it is 0 at `deadline`. It is paid by Resolve.
+    /// A `fee_bps` of 0 keeps the program config's default rate the contract was made with
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` omega_contract_acc
+    ///   1. `[signer]` funder_acc
+    ///   2. `[writable]` funder_quote_acc
+    ///   3. `[writable]` vault_acc
+    ///   4. `[]` spl_token_program_acc
+    ///   5. `[]` clock_acc - sysvar Clock
+    InitOracleFee {
+        amount: u64,
+        fee_bps: u64,
+        deadline: u64,
+    },
+
+    /// Return the part of the oracle fee that was not paid out to the funder once the contract
+    /// is resolved or auto expired
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` omega_contract_acc
+    ///   1. `[writable]` funder_quote_acc - quote token account of funder
+    ///   2. `[writable]` vault_acc
+    ///   3. `[]` spl_token_program_acc
+    ///   4. `[]` omega_signer_acc
+    ///   5. `[]` clock_acc - sysvar Clock
+    ///   6. `[]` outcome0_mint_acc - outcome0 mint account, to check the vault against
+    ///
+    ///   Repeat 6 for each outcome.
+    ///   Total accounts: 6 + num_outcomes
+    WithdrawOracleFee,
+
+    /// Program config admin resolves a contract the oracle did not resolve in time, up to
+    /// LATE_RESOLUTION_WINDOW seconds after auto_exp_time. Fails once anything was paid out at the
+    /// auto expiry rate. A posted oracle bond is slashed to the holders of the winner
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` omega_contract_acc
+    ///   1. `[]` program_config_acc
+    ///   2. `[signer]` admin_acc
+    ///   3. `[]` winner_acc - mint pubkey of winning outcome
+    ///   4. `[]` clock_acc - sysvar Clock
+    ///
+    ///   Only if the oracle bond was posted and not slashed yet:
+    ///   5. `[writable]` escrow_acc
+    ///   6. `[writable]` vault_acc
+    ///   7. `[]` spl_token_program_acc
+    ///   8. `[]` omega_signer_acc
+    ResolveLate,
+
+    /// Designated oracle names a backup oracle before exp_time. If the oracle goes longer than
+    /// `heartbeat_period` seconds without an OracleHeartbeat, the backup gets the resolution rights
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` omega_contract_acc
+    ///   1. `[signer]` oracle_acc - pubkey of oracle
+    ///   2. `[]` backup_oracle_acc - pubkey of backup oracle
+    ///   3. `[]` clock_acc - sysvar Clock
+    InitOracleLiveness {
+        heartbeat_period: u64,
+    },
+
+    /// Designated oracle records that it is still alive
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` omega_contract_acc
+    ///   1. `[signer]` oracle_acc - pubkey of oracle
+    ///   2. `[]` clock_acc - sysvar Clock
+    OracleHeartbeat,
+
+    /// Create the OracleReputation of an oracle. Anyone can pay for it
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` oracle_reputation_acc - address from find_oracle_reputation_address
+    ///   1. `[]` oracle_acc
+    ///   2. `[writable, signer]` payer_acc
+    ///   3. `[]` system_program_acc
+    ///   4. `[]` rent_acc - sysvar Rent
+    InitOracleReputation,
+
+    /// Permissionless: count resolutions, lost disputes and missed deadlines of a contract in the
+    /// reputation of the oracle responsible for them. Each is only counted once
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` omega_contract_acc
+    ///   1. `[writable]` oracle_reputation_acc
+    ///   2. `[]` clock_acc - sysvar Clock
+    RecordOracleReputation,
+
+    /// Designated oracle hands resolution to the holders of a governance mint before exp_time.
+    /// Holders vote during `voting_period` seconds after exp_time, then anyone can FinalizeVote.
+    /// `tie_break` is a TieBreak, `tie_break_outcome` is only used by TieBreak::Designated
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` omega_contract_acc
+    ///   1. `[signer]` oracle_acc - pubkey of oracle
+    ///   2. `[]` gov_mint_acc - mint of the governance token
+    ///   3. `[]` escrow_acc - governance token account owned by omega_signer_acc
+    ///   4. `[]` clock_acc - sysvar Clock
+    InitVoteResolution {
+        voting_period: u64,
+        quorum: u64,
+        tie_break: u64,
+        tie_break_outcome: u64,
+    },
+
+    /// Escrow `amount` governance tokens as votes for `outcome` during the voting period.
+    /// A voter can add to their vote but not change the outcome
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` omega_contract_acc
+    ///   1. `[writable]` vote_record_acc - address from find_vote_record_address
+    ///   2. `[writable, signer]` voter_acc - pays for the vote record
+    ///   3. `[writable]` voter_gov_acc - governance token account of voter
+    ///   4. `[writable]` escrow_acc
+    ///   5. `[]` spl_token_program_acc
+    ///   6. `[]` system_program_acc
+    ///   7. `[]` rent_acc - sysvar Rent
+    ///   8. `[]` clock_acc - sysvar Clock
+    CastVote {
+        outcome: u64,
+        amount: u64,
+    },
+
+    /// Permissionless: resolve the contract with the vote tally once the voting period is over.
+    /// Fails if the quorum is not met or the tie break leaves no winner
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` omega_contract_acc
+    ///   1. `[]` clock_acc - sysvar Clock
+    ///   2. `[]` vault_acc - omega's quote currency vault
+    ///   3. `[]` outcome0_mint_acc - outcome0 mint account, to check the vault against
+    ///
+    ///   Repeat 3 for each outcome.
+    ///   Total accounts: 3 + num_outcomes
+    FinalizeVote,
+
+    /// Return the escrowed governance tokens to the voter once the voting period is over,
+    /// and close the vote record
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[]` omega_contract_acc
+    ///   1. `[writable]` vote_record_acc
+    ///   2. `[writable, signer]` voter_acc - receives the rent of the vote record
+    ///   3. `[writable]` voter_gov_acc - governance token account of voter
+    ///   4. `[writable]` escrow_acc
+    ///   5. `[]` spl_token_program_acc
+    ///   6. `[]` omega_signer_acc
+    ///   7. `[]` clock_acc - sysvar Clock
+    WithdrawVote,
+
+    /// Permissionless: return the creator bond once the contract has a winner and the creator
+    /// dispute window has passed
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` omega_contract_acc
+    ///   1. `[writable]` escrow_acc
+    ///   2. `[writable]` creator_quote_acc - quote token account of creator
+    ///   3. `[]` spl_token_program_acc
+    ///   4. `[]` omega_signer_acc
+    ///   5. `[]` clock_acc - sysvar Clock
+    ReleaseCreatorBond,
+
+    /// Pay the creator bond to the treasury. Anyone can do so once the contract is resolved invalid,
+    /// i.e. it auto expired and can no longer be resolved late. The program config admin can do so
+    /// when the contract is ambiguous, after it resolved and until the creator dispute window has passed
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` omega_contract_acc
+    ///   1. `[]` program_config_acc
+    ///   2. `[signer]` admin_acc - only needs to sign if the contract is not resolved invalid
+    ///   3. `[writable]` escrow_acc
+    ///   4. `[writable]` treasury_quote_acc - quote token account of the treasury
+    ///   5. `[]` spl_token_program_acc
+    ///   6. `[]` omega_signer_acc
+    ///   7. `[]` clock_acc - sysvar Clock
+    ForfeitCreatorBond,
+
+    /// Program config admin sets the merkle root of the pubkeys allowed to IssueSet.
+    /// A zero root lets anyone issue sets
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` omega_contract_acc
+    ///   1. `[]` program_config_acc
+    ///   2. `[signer]` admin_acc
+    SetAllowList {
+        root: [u8; 32],
+    },
+
+    /// Program config admin caps the sets outstanding on a contract and the sets each user can issue.
+    /// Zero removes a cap. Lowering a cap below what was already issued only blocks further issuance
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` omega_contract_acc
+    ///   1. `[]` program_config_acc
+    ///   2. `[signer]` admin_acc
+    SetIssuanceCaps {
+        max_supply: u64,
+        wallet_cap: u64,
+    },
+
+    /// Creator closes a resolved or auto expired contract once every outcome token was redeemed
+    /// and the vault is empty. The contract is removed from the registry and its rent goes to the creator
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` omega_contract_acc
+    ///   1. `[writable]` registry_acc - Registry the contract was listed in
+    ///   2. `[writable, signer]` creator_acc - creator of the registry
+    ///   3. `[]` vault_acc
+    ///   4. `[]` clock_acc - sysvar Clock
+    CloseContract,
+}
+
+impl OmegaInstruction {
+    /// First four bytes of instruction data is the index of the instruction (e.g. 0 -> InitOmegaContract)
+    /// Remaining data is the actual instruction contents
+    pub fn unpack(input: &[u8]) -> Option<Self> {
+        let (&discrim, data) = array_refs![input, 4; ..;];
+        let discrim = u32::from_le_bytes(discrim);
+
+        Some(match discrim {
+            0 => {
+                let data = array_ref![data, 0, 24];
+                let (exp_time, auto_exp_time, signer_nonce) = array_refs![data, 8, 8, 8];
+                OmegaInstruction::InitOmegaContract {
+                    exp_time: u64::from_le_bytes(*exp_time),
+                    auto_exp_time: u64::from_le_bytes(*auto_exp_time),
+                    signer_nonce: u64::from_le_bytes(*signer_nonce),
+                }
+            }
+            1 => {
+                let quantity = array_ref![data, 0, 8];
+                OmegaInstruction::IssueSet {
+                    quantity: u64::from_le_bytes(*quantity)
+                }
+            }
+            2 => {
+                let quantity = array_ref![data, 0, 8];
+                OmegaInstruction::RedeemSet {
+                    quantity: u64::from_le_bytes(*quantity)
+                }
+            }
+            3 => {
+                let quantity = array_ref![data, 0, 8];
+                OmegaInstruction::RedeemWinner {
+                    quantity: u64::from_le_bytes(*quantity)
+                }
+            }
+            4 => {
+                // Resolve used to take no data
+                let evidence_hash = if data.len() >= 32 { *array_ref![data, 0, 32] } else { [0u8; 32] };
+                OmegaInstruction::Resolve { evidence_hash }
+            }
+            5 => {
+                let liquidity = array_ref![data, 0, 8];
+                OmegaInstruction::InitLmsr {
+                    liquidity: u64::from_le_bytes(*liquidity)
+                }
+            }
+            6 => {
+                let data = array_ref![data, 0, 24];
+                let (outcome, quantity, max_cost) = array_refs![data, 8, 8, 8];
+                OmegaInstruction::LmsrBuy {
+                    outcome: u64::from_le_bytes(*outcome),
+                    quantity: u64::from_le_bytes(*quantity),
+                    max_cost: u64::from_le_bytes(*max_cost),
+                }
+            }
+            7 => {
+                let data = array_ref![data, 0, 24];
+                let (outcome, quantity, min_proceeds) = array_refs![data, 8, 8, 8];
+                OmegaInstruction::LmsrSell {
+                    outcome: u64::from_le_bytes(*outcome),
+                    quantity: u64::from_le_bytes(*quantity),
+                    min_proceeds: u64::from_le_bytes(*min_proceeds),
+                }
+            }
+            8 => {
+                OmegaInstruction::LmsrWithdraw
+            }
+            9 => {
+                OmegaInstruction::InitOrderBook
+            }
+            10 => {
+                let data = array_ref![data, 0, 24];
+                let (side, price, quantity) = array_refs![data, 8, 8, 8];
+                OmegaInstruction::PlaceOrder {
+                    side: u64::from_le_bytes(*side),
+                    price: u64::from_le_bytes(*price),
+                    quantity: u64::from_le_bytes(*quantity),
+                }
+            }
+            11 => {
+                let order_id = array_ref![data, 0, 8];
+                OmegaInstruction::CancelOrder {
+                    order_id: u64::from_le_bytes(*order_id)
+                }
+            }
+            12 => {
+                let order_id = array_ref![data, 0, 8];
+                OmegaInstruction::SettleOrder {
+                    order_id: u64::from_le_bytes(*order_id)
+                }
+            }
+            13 => {
+                let limit = array_ref![data, 0, 8];
+                OmegaInstruction::MatchOrders {
+                    limit: u64::from_le_bytes(*limit)
+                }
+            }
+            14 => {
+                let data = array_ref![data, 0, 16];
+                let (side, limit) = array_refs![data, 8, 8];
+                OmegaInstruction::MatchSets {
+                    side: u64::from_le_bytes(*side),
+                    limit: u64::from_le_bytes(*limit),
+                }
+            }
+            15 => {
+                let fee_bps = array_ref![data, 0, 8];
+                OmegaInstruction::InitParimutuel {
+                    fee_bps: u64::from_le_bytes(*fee_bps)
+                }
+            }
+            16 => {
+                let data = array_ref![data, 0, 16];
+                let (outcome, quantity) = array_refs![data, 8, 8];
+                OmegaInstruction::Stake {
+                    outcome: u64::from_le_bytes(*outcome),
+                    quantity: u64::from_le_bytes(*quantity),
+                }
+            }
+            17 => {
+                OmegaInstruction::CollectParimutuelFee
+            }
+            18 => {
+                let data = array_ref![data, 0, 24];
+                let (outcome, quantity, max_cost) = array_refs![data, 8, 8, 8];
+                OmegaInstruction::BuyOutcome {
+                    outcome: u64::from_le_bytes(*outcome),
+                    quantity: u64::from_le_bytes(*quantity),
+                    max_cost: u64::from_le_bytes(*max_cost),
+                }
+            }
+            19 => {
+                let data = array_ref![data, 0, 24];
+                let (outcome, quantity, min_proceeds) = array_refs![data, 8, 8, 8];
+                OmegaInstruction::SellOutcome {
+                    outcome: u64::from_le_bytes(*outcome),
+                    quantity: u64::from_le_bytes(*quantity),
+                    min_proceeds: u64::from_le_bytes(*min_proceeds),
+                }
+            }
+            20 => {
+                let parent_outcome = array_ref![data, 0, 8];
+                OmegaInstruction::InitConditional {
+                    parent_outcome: u64::from_le_bytes(*parent_outcome)
+                }
+            }
+            21 => {
+                let quantity = array_ref![data, 0, 8];
+                OmegaInstruction::IssueConditionalSet {
+                    quantity: u64::from_le_bytes(*quantity)
+                }
+            }
+            22 => {
+                let quantity = array_ref![data, 0, 8];
+                OmegaInstruction::RedeemConditionalSet {
+                    quantity: u64::from_le_bytes(*quantity)
+                }
+            }
+            23 => {
+                let quantity = array_ref![data, 0, 8];
+                OmegaInstruction::RedeemConditionalWinner {
+                    quantity: u64::from_le_bytes(*quantity)
+                }
+            }
+            24 => {
+                let data = array_ref![data, 0, 8 * MAX_OUTCOMES];
+                let (num_breakpoints, breakpoints_data) = array_refs![data, 8; ..;];
+                let mut breakpoints = [0i64; MAX_OUTCOMES - 1];
+                for (i, breakpoint) in breakpoints.iter_mut().enumerate() {
+                    *breakpoint = i64::from_le_bytes(*array_ref![breakpoints_data, 8 * i, 8]);
+                }
+                OmegaInstruction::InitBucketGroup {
+                    num_breakpoints: u64::from_le_bytes(*num_breakpoints),
+                    breakpoints
+                }
+            }
+            25 => {
+                let value = array_ref![data, 0, 8];
+                OmegaInstruction::ResolveValue {
+                    value: i64::from_le_bytes(*value)
+                }
+            }
+            26 => {
+                let data = array_ref![data, 0, 32];
+                let (num_outcomes, first_exp_time, period, resolve_window) = array_refs![data, 8, 8, 8, 8];
+                OmegaInstruction::InitMarketSeries {
+                    num_outcomes: u64::from_le_bytes(*num_outcomes),
+                    first_exp_time: u64::from_le_bytes(*first_exp_time),
+                    period: u64::from_le_bytes(*period),
+                    resolve_window: u64::from_le_bytes(*resolve_window),
+                }
+            }
+            27 => {
+                let data = array_ref![data, 0, 16];
+                let (contract_nonce, signer_nonce) = array_refs![data, 8, 8];
+                OmegaInstruction::CreateSeriesContract {
+                    contract_nonce: u64::from_le_bytes(*contract_nonce),
+                    signer_nonce: u64::from_le_bytes(*signer_nonce),
+                }
+            }
+            28 => {
+                OmegaInstruction::InitRegistry
+            }
+            29 => {
+                let category = array_ref![data, 0, 8];
+                OmegaInstruction::UpdateRegistryEntry {
+                    category: u64::from_le_bytes(*category)
+                }
+            }
+            30 | 31 => {
+                let data = array_ref![data, 0, 40];
+                let (timelock, fee_bps, restrict_creators, num_quote_mints, creator_bond) =
+                    array_refs![data, 8, 8, 8, 8, 8];
+                let timelock = u64::from_le_bytes(*timelock);
+                let fee_bps = u64::from_le_bytes(*fee_bps);
+                let restrict_creators = u64::from_le_bytes(*restrict_creators);
+                let num_quote_mints = u64::from_le_bytes(*num_quote_mints);
+                let creator_bond = u64::from_le_bytes(*creator_bond);
+                if discrim == 30 {
+                    OmegaInstruction::InitProgramConfig { timelock, fee_bps, restrict_creators, num_quote_mints, creator_bond }
+                } else {
+                    OmegaInstruction::ProposeConfig { timelock, fee_bps, restrict_creators, num_quote_mints, creator_bond }
+                }
+            }
+            32 => {
+                OmegaInstruction::ApplyConfig
+            }
+            33 => {
+                let data = array_ref![data, 0, 32];
+                let (source, comparison, threshold, max_staleness) = array_refs![data, 8, 8, 8, 8];
+                OmegaInstruction::InitPriceResolution {
+                    source: u64::from_le_bytes(*source),
+                    comparison: u64::from_le_bytes(*comparison),
+                    threshold: i64::from_le_bytes(*threshold),
+                    max_staleness: u64::from_le_bytes(*max_staleness),
+                }
+            }
+            34 => {
+                OmegaInstruction::ResolveFromPrice
+            }
+            35 => {
+                let data = array_ref![data, 0, 16];
+                let (winner, timestamp) = array_refs![data, 8, 8];
+                OmegaInstruction::ResolveWithAttestation {
+                    winner: u64::from_le_bytes(*winner),
+                    timestamp: i64::from_le_bytes(*timestamp),
+                }
+            }
+            36 => {
+                let data = array_ref![data, 0, 40];
+                let (commitment, reveal_window) = array_refs![data, 32, 8];
+                OmegaInstruction::CommitResolution {
+                    commitment: *commitment,
+                    reveal_window: u64::from_le_bytes(*reveal_window),
+                }
+            }
+            37 => {
+                let salt = array_ref![data, 0, 32];
+                OmegaInstruction::RevealResolution {
+                    salt: *salt
+                }
+            }
+            38 => {
+                let data = array_ref![data, 0, 16];
+                let (amount, dispute_window) = array_refs![data, 8, 8];
+                OmegaInstruction::InitOracleBond {
+                    amount: u64::from_le_bytes(*amount),
+                    dispute_window: u64::from_le_bytes(*dispute_window),
+                }
+            }
+            39 => {
+                OmegaInstruction::ReleaseOracleBond
+            }
+            40 => {
+                OmegaInstruction::SlashOracleBond
+            }
+            41 => {
+                OmegaInstruction::OverturnResolution
+            }
+            42 => {
+                let data = array_ref![data, 0, 24];
+                let (amount, fee_bps, deadline) = array_refs![data, 8, 8, 8];
+                OmegaInstruction::InitOracleFee {
+                    amount: u64::from_le_bytes(*amount),
+                    fee_bps: u64::from_le_bytes(*fee_bps),
+                    deadline: u64::from_le_bytes(*deadline),
+                }
+            }
+            43 => {
+                OmegaInstruction::WithdrawOracleFee
+            }
+            44 => {
+                OmegaInstruction::ResolveLate
+            }
+            45 => {
+                let heartbeat_period = array_ref![data, 0, 8];
+                OmegaInstruction::InitOracleLiveness {
+                    heartbeat_period: u64::from_le_bytes(*heartbeat_period)
+                }
+            }
+            46 => {
+                OmegaInstruction::OracleHeartbeat
+            }
+            47 => {
+                OmegaInstruction::InitOracleReputation
+            }
+            48 => {
+                OmegaInstruction::RecordOracleReputation
+            }
+            49 => {
+                let data = array_ref![data, 0, 32];
+                let (voting_period, quorum, tie_break, tie_break_outcome) = array_refs![data, 8, 8, 8, 8];
+                OmegaInstruction::InitVoteResolution {
+                    voting_period: u64::from_le_bytes(*voting_period),
+                    quorum: u64::from_le_bytes(*quorum),
+                    tie_break: u64::from_le_bytes(*tie_break),
+                    tie_break_outcome: u64::from_le_bytes(*tie_break_outcome),
+                }
+            }
+            50 => {
+                let data = array_ref![data, 0, 16];
+                let (outcome, amount) = array_refs![data, 8, 8];
+                OmegaInstruction::CastVote {
+                    outcome: u64::from_le_bytes(*outcome),
+                    amount: u64::from_le_bytes(*amount),
+                }
+            }
+            51 => {
+                OmegaInstruction::FinalizeVote
+            }
+            52 => {
+                OmegaInstruction::WithdrawVote
+            }
+            53 => {
+                OmegaInstruction::ReleaseCreatorBond
+            }
+            54 => {
+                OmegaInstruction::ForfeitCreatorBond
+            }
+            55 => {
+                let root = array_ref![data, 0, 32];
+                OmegaInstruction::SetAllowList {
+                    root: *root
+                }
+            }
+            56 => {
+                let data = array_ref![data, 0, 16];
+                let (max_supply, wallet_cap) = array_refs![data, 8, 8];
+                OmegaInstruction::SetIssuanceCaps {
+                    max_supply: u64::from_le_bytes(*max_supply),
+                    wallet_cap: u64::from_le_bytes(*wallet_cap),
+                }
+            }
+            57 => {
+                OmegaInstruction::CloseContract
+            }
+            _ => { return None; }
+        })
+    }
+    pub fn pack(&self) -> Vec<u8> {
+        bincode::serialize(self).unwrap()
+    }
+
+}
+
+
+/// The outcome_pks are public keys for the SPL token mint of each outcome
+/// Make sure the token has 0 supply and the authority is the key generated by gen_vault_signer_key
+/// using the signer nonce
+pub fn init_omega_contract(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    oracle_pk: &Pubkey,
+    quote_mint_pk: &Pubkey,
+    vault_pk: &Pubkey,
+    signer_pk: &Pubkey,
+    creator_pk: &Pubkey,
+    registry_pk: &Pubkey,
+    outcome_pks: &[Pubkey],
+    exp_time: u64,
+    auto_exp_time: u64,
+    signer_nonce: u64,
+    details_str: &str
+) -> Result<Instruction, ProgramError> {
+
+    let (program_config_pk, _) = find_program_config_address(program_id);
+    let mut accounts = vec![
+        AccountMeta::new(*omega_contract_pk, false),
+        AccountMeta::new_readonly(*oracle_pk, false),
+        AccountMeta::new_readonly(*quote_mint_pk, false),
+        AccountMeta::new_readonly(*vault_pk, false),
+        AccountMeta::new_readonly(*signer_pk, false),
+        AccountMeta::new_readonly(solana_program::sysvar::rent::ID, false),
+        AccountMeta::new_readonly(program_config_pk, false),
+        AccountMeta::new_readonly(*creator_pk, true),
+        AccountMeta::new(*registry_pk, false),
+        AccountMeta::new_readonly(solana_program::sysvar::clock::ID, false),
+    ];
+
+    for pk in outcome_pks {
+        accounts.push(AccountMeta::new(*pk, false));
+    }
+
+    let instr = OmegaInstruction::InitOmegaContract {
+        exp_time,
+        auto_exp_time,
+        signer_nonce
+    };
+
+    let details = details_str.as_bytes();
+    let mut data = instr.pack();
+    data.extend_from_slice(details);
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+pub fn issue_set(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    user_pk: &Pubkey,
+    user_quote_pk: &Pubkey,
+    vault_pk: &Pubkey,
+    signer_pk: &Pubkey,
+    outcome_pks: &[(Pubkey, Pubkey)],  // (mint, user_acc)
+    quantity: u64
+) -> Result<Instruction, ProgramError> {
+
+    let mut accounts = vec![
+        AccountMeta::new(*omega_contract_pk, false),
+        AccountMeta::new_readonly(*user_pk, true),
+        AccountMeta::new(*user_quote_pk, false),
+        AccountMeta::new(*vault_pk, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(*signer_pk, false),
+    ];
+
+    for (outcome_mint_pk, outcome_user_pk) in outcome_pks {
+        accounts.push(AccountMeta::new(*outcome_mint_pk, false));
+        accounts.push(AccountMeta::new(*outcome_user_pk, false));
+    }
+    accounts.push(AccountMeta::new_readonly(solana_program::sysvar::clock::ID, false));
+
+    let instr = OmegaInstruction::IssueSet { quantity };
+    let data = instr.pack();
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+pub fn find_issuance_record_address(program_id: &Pubkey, omega_contract_pk: &Pubkey, user_pk: &Pubkey) -> (Pubkey, u64) {
+    for nonce in 0..256u64 {
+        let seeds = [ISSUANCE_RECORD_SEED, omega_contract_pk.as_ref(), user_pk.as_ref(), &nonce.to_le_bytes()];
+        if let Ok(pk) = Pubkey::create_program_address(&seeds, program_id) {
+            return (pk, nonce);
+        }
+    }
+    panic!("Could not generate issuance record address");
+}
+
+/// Append the proof from `allow_list::merkle_proof` to IssueSet, LmsrBuy, Stake or BuyOutcome
+/// of a contract with an allow-list
+pub fn with_allow_list_proof(mut instruction: Instruction, proof: &[[u8; 32]]) -> Instruction {
+    for sibling in proof {
+        instruction.data.extend_from_slice(sibling);
+    }
+    instruction
+}
+
+/// Append the user's IssuanceRecord of the contract to IssueSet, LmsrBuy, Stake or BuyOutcome, or twice
+/// (parent first) to IssueConditionalSet
+pub fn with_issuance_record(
+    mut instruction: Instruction,
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    user_pk: &Pubkey
+) -> Instruction {
+    // the user pays for the record the first time
+    for account in instruction.accounts.iter_mut().filter(|account| account.pubkey == *user_pk) {
+        account.is_writable = true;
+    }
+    let (issuance_record_pk, _) = find_issuance_record_address(program_id, omega_contract_pk, user_pk);
+    instruction.accounts.push(AccountMeta::new(issuance_record_pk, false));
+    instruction.accounts.push(AccountMeta::new_readonly(solana_program::system_program::ID, false));
+    instruction.accounts.push(AccountMeta::new_readonly(solana_program::sysvar::rent::ID, false));
+    instruction
+}
+
+/// Append the proofs for the parent and the conditional contract to IssueConditionalSet,
+/// empty for a contract without an allow-list
+pub fn with_conditional_allow_list_proofs(
+    mut instruction: Instruction,
+    parent_proof: &[[u8; 32]],
+    proof: &[[u8; 32]]
+) -> Instruction {
+    instruction.data.extend_from_slice(&(parent_proof.len() as u64).to_le_bytes());
+    with_allow_list_proof(with_allow_list_proof(instruction, parent_proof), proof)
+}
+
+
+pub fn redeem_set(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    user_pk: &Pubkey,
+    user_quote_pk: &Pubkey,
+    vault_pk: &Pubkey,
+    signer_pk: &Pubkey,
+    outcome_pks: &[(Pubkey, Pubkey)],  // (mint, user_acc)
+    quantity: u64
+) -> Result<Instruction, ProgramError> {
+
+    let mut accounts = vec![
+        AccountMeta::new(*omega_contract_pk, false),
+        AccountMeta::new_readonly(*user_pk, true),
+        AccountMeta::new(*user_quote_pk, false),
+        AccountMeta::new(*vault_pk, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(*signer_pk, false),
+    ];
+
+    for (outcome_mint_pk, outcome_user_pk) in outcome_pks {
+        accounts.push(AccountMeta::new(*outcome_mint_pk, false));
+        accounts.push(AccountMeta::new(*outcome_user_pk, false));
+    }
+
+    accounts.push(AccountMeta::new_readonly(solana_program::sysvar::clock::ID, false));
+
+    let instr = OmegaInstruction::RedeemSet { quantity };
+    let data = instr.pack();
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+pub fn redeem_winner(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    user_pk: &Pubkey,
+    user_quote_pk: &Pubkey,
+    vault_pk: &Pubkey,
+    signer_pk: &Pubkey,
+    winner_mint_pk: &Pubkey,
+    winner_user_pk: &Pubkey,
+    quantity: u64
+) -> Result<Instruction, ProgramError> {
+
+    let accounts = vec![
+        AccountMeta::new(*omega_contract_pk, false),
+        AccountMeta::new_readonly(*user_pk, true),
+        AccountMeta::new(*user_quote_pk, false),
+        AccountMeta::new(*vault_pk, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(*signer_pk, false),
+        AccountMeta::new(*winner_mint_pk, false),
+        AccountMeta::new(*winner_user_pk, false),
+        AccountMeta::new_readonly(solana_program::sysvar::clock::ID, false)
+    ];
+
+    let instr = OmegaInstruction::RedeemWinner { quantity };
+    let data = instr.pack();
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+
+/// Every outcome mint in order, for the instructions that check the vault against the mints' supply
+fn outcome_mint_accounts(outcome_mint_pks: &[Pubkey]) -> Vec<AccountMeta> {
+    outcome_mint_pks.iter().map(|pk| AccountMeta::new_readonly(*pk, false)).collect()
+}
+
+/// The vault followed by every outcome mint, for the instructions that don't take the vault otherwise
+fn solvency_accounts(vault_pk: &Pubkey, outcome_mint_pks: &[Pubkey]) -> Vec<AccountMeta> {
+    let mut accounts = vec![AccountMeta::new_readonly(*vault_pk, false)];
+    accounts.extend(outcome_mint_accounts(outcome_mint_pks));
+    accounts
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn resolve(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    oracle_pk: &Pubkey,
+    winner_pk: &Pubkey,
+    vault_pk: &Pubkey,
+    outcome_mint_pks: &[Pubkey],
+    evidence_uri: &str,
+    evidence_hash: [u8; 32]
+) -> Result<Instruction, ProgramError> {
+
+    let mut accounts = vec![
+        AccountMeta::new(*omega_contract_pk, false),
+        AccountMeta::new_readonly(*oracle_pk, true),
+        AccountMeta::new_readonly(*winner_pk, false),
+        AccountMeta::new_readonly(solana_program::sysvar::clock::ID, false),
+        AccountMeta::new(*vault_pk, false),
+    ];
+    accounts.extend(outcome_mint_accounts(outcome_mint_pks));
+
+    let instr = OmegaInstruction::Resolve { evidence_hash };
+    let mut data = instr.pack();
+    data.extend_from_slice(evidence_uri.as_bytes());
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+/// Append the accounts Resolve needs to pay the oracle fee
+pub fn with_oracle_fee(mut instruction: Instruction, oracle_quote_pk: &Pubkey, omega_signer_pk: &Pubkey) -> Instruction {
+    instruction.accounts.push(AccountMeta::new(*oracle_quote_pk, false));
+    instruction.accounts.push(AccountMeta::new_readonly(spl_token::id(), false));
+    instruction.accounts.push(AccountMeta::new_readonly(*omega_signer_pk, false));
+    instruction
+}
+
+/// Append the oracle's reputation to Resolve or ResolveFromPrice, after any oracle fee accounts,
+/// so the resolution is recorded without a separate RecordOracleReputation
+pub fn with_oracle_reputation(mut instruction: Instruction, oracle_pk: &Pubkey) -> Instruction {
+    let (oracle_reputation_pk, _) = find_oracle_reputation_address(&instruction.program_id, oracle_pk);
+    instruction.accounts.push(AccountMeta::new(oracle_reputation_pk, false));
+    instruction
+}
+
+pub fn init_lmsr(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    creator_pk: &Pubkey,
+    creator_quote_pk: &Pubkey,
+    vault_pk: &Pubkey,
+    liquidity: u64
+) -> Result<Instruction, ProgramError> {
+
+    let accounts = vec![
+        AccountMeta::new(*omega_contract_pk, false),
+        AccountMeta::new_readonly(*creator_pk, true),
+        AccountMeta::new(*creator_quote_pk, false),
+        AccountMeta::new(*vault_pk, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+
+    let instr = OmegaInstruction::InitLmsr { liquidity };
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn lmsr_trade_accounts(
+    omega_contract_pk: &Pubkey,
+    user_pk: &Pubkey,
+    user_quote_pk: &Pubkey,
+    vault_pk: &Pubkey,
+    signer_pk: &Pubkey,
+    outcome_mint_pk: &Pubkey,
+    outcome_user_pk: &Pubkey,
+    outcome_mint_pks: &[Pubkey]
+) -> Vec<AccountMeta> {
+    let mut accounts = vec![
+        AccountMeta::new(*omega_contract_pk, false),
+        AccountMeta::new_readonly(*user_pk, true),
+        AccountMeta::new(*user_quote_pk, false),
+        AccountMeta::new(*vault_pk, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(*signer_pk, false),
+        AccountMeta::new(*outcome_mint_pk, false),
+        AccountMeta::new(*outcome_user_pk, false),
+        AccountMeta::new_readonly(solana_program::sysvar::clock::ID, false)
+    ];
+    accounts.extend(outcome_mint_accounts(outcome_mint_pks));
+    accounts
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn lmsr_buy(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    user_pk: &Pubkey,
+    user_quote_pk: &Pubkey,
+    vault_pk: &Pubkey,
+    signer_pk: &Pubkey,
+    outcome_mint_pk: &Pubkey,
+    outcome_user_pk: &Pubkey,
+    outcome_mint_pks: &[Pubkey],
+    outcome: u64,
+    quantity: u64,
+    max_cost: u64
+) -> Result<Instruction, ProgramError> {
+
+    let accounts = lmsr_trade_accounts(
+        omega_contract_pk, user_pk, user_quote_pk, vault_pk, signer_pk, outcome_mint_pk, outcome_user_pk,
+        outcome_mint_pks
+    );
+
+    let instr = OmegaInstruction::LmsrBuy { outcome, quantity, max_cost };
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn lmsr_sell(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    user_pk: &Pubkey,
+    user_quote_pk: &Pubkey,
+    vault_pk: &Pubkey,
+    signer_pk: &Pubkey,
+    outcome_mint_pk: &Pubkey,
+    outcome_user_pk: &Pubkey,
+    outcome_mint_pks: &[Pubkey],
+    outcome: u64,
+    quantity: u64,
+    min_proceeds: u64
+) -> Result<Instruction, ProgramError> {
+
+    let accounts = lmsr_trade_accounts(
+        omega_contract_pk, user_pk, user_quote_pk, vault_pk, signer_pk, outcome_mint_pk, outcome_user_pk,
+        outcome_mint_pks
+    );
+
+    let instr = OmegaInstruction::LmsrSell { outcome, quantity, min_proceeds };
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+pub fn lmsr_withdraw(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    creator_pk: &Pubkey,
+    creator_quote_pk: &Pubkey,
+    vault_pk: &Pubkey,
+    signer_pk: &Pubkey,
+    outcome_mint_pks: &[Pubkey]
+) -> Result<Instruction, ProgramError> {
+
+    let mut accounts = vec![
+        AccountMeta::new(*omega_contract_pk, false),
+        AccountMeta::new_readonly(*creator_pk, true),
+        AccountMeta::new(*creator_quote_pk, false),
+        AccountMeta::new(*vault_pk, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(*signer_pk, false),
+        AccountMeta::new_readonly(solana_program::sysvar::clock::ID, false),
+    ];
+
+    for pk in outcome_mint_pks {
+        accounts.push(AccountMeta::new_readonly(*pk, false));
+    }
+
+    let instr = OmegaInstruction::LmsrWithdraw;
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+pub fn init_order_book(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    order_book_pk: &Pubkey,
+    outcome_mint_pk: &Pubkey,
+    quote_escrow_pk: &Pubkey,
+    outcome_escrow_pk: &Pubkey
+) -> Result<Instruction, ProgramError> {
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*omega_contract_pk, false),
+        AccountMeta::new(*order_book_pk, false),
+        AccountMeta::new_readonly(*outcome_mint_pk, false),
+        AccountMeta::new_readonly(*quote_escrow_pk, false),
+        AccountMeta::new_readonly(*outcome_escrow_pk, false),
+        AccountMeta::new_readonly(solana_program::sysvar::rent::ID, false),
+    ];
+
+    let instr = OmegaInstruction::InitOrderBook;
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn place_order(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    order_book_pk: &Pubkey,
+    owner_pk: &Pubkey,
+    owner_src_pk: &Pubkey,
+    escrow_pk: &Pubkey,
+    vault_pk: &Pubkey,
+    outcome_mint_pks: &[Pubkey],
+    side: Side,
+    price: u64,
+    quantity: u64
+) -> Result<Instruction, ProgramError> {
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*omega_contract_pk, false),
+        AccountMeta::new(*order_book_pk, false),
+        AccountMeta::new_readonly(*owner_pk, true),
+        AccountMeta::new(*owner_src_pk, false),
+        AccountMeta::new(*escrow_pk, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(solana_program::sysvar::clock::ID, false),
+    ];
+    accounts.extend(solvency_accounts(vault_pk, outcome_mint_pks));
+
+    let instr = OmegaInstruction::PlaceOrder { side: side.into(), price, quantity };
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn settle_order_accounts(
+    omega_contract_pk: &Pubkey,
+    order_book_pk: &Pubkey,
+    owner_pk: &Pubkey,
+    owner_quote_pk: &Pubkey,
+    owner_outcome_pk: &Pubkey,
+    quote_escrow_pk: &Pubkey,
+    outcome_escrow_pk: &Pubkey,
+    signer_pk: &Pubkey,
+    vault_pk: &Pubkey,
+    outcome_mint_pks: &[Pubkey]
+) -> Vec<AccountMeta> {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*omega_contract_pk, false),
+        AccountMeta::new(*order_book_pk, false),
+        AccountMeta::new_readonly(*owner_pk, true),
+        AccountMeta::new(*owner_quote_pk, false),
+        AccountMeta::new(*owner_outcome_pk, false),
+        AccountMeta::new(*quote_escrow_pk, false),
+        AccountMeta::new(*outcome_escrow_pk, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(*signer_pk, false),
+    ];
+    accounts.extend(solvency_accounts(vault_pk, outcome_mint_pks));
+    accounts
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn cancel_order(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    order_book_pk: &Pubkey,
+    owner_pk: &Pubkey,
+    owner_quote_pk: &Pubkey,
+    owner_outcome_pk: &Pubkey,
+    quote_escrow_pk: &Pubkey,
+    outcome_escrow_pk: &Pubkey,
+    signer_pk: &Pubkey,
+    vault_pk: &Pubkey,
+    outcome_mint_pks: &[Pubkey],
+    order_id: u64
+) -> Result<Instruction, ProgramError> {
+
+    let accounts = settle_order_accounts(
+        omega_contract_pk, order_book_pk, owner_pk, owner_quote_pk, owner_outcome_pk,
+        quote_escrow_pk, outcome_escrow_pk, signer_pk, vault_pk, outcome_mint_pks
+    );
+
+    let instr = OmegaInstruction::CancelOrder { order_id };
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn settle_order(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    order_book_pk: &Pubkey,
+    owner_pk: &Pubkey,
+    owner_quote_pk: &Pubkey,
+    owner_outcome_pk: &Pubkey,
+    quote_escrow_pk: &Pubkey,
+    outcome_escrow_pk: &Pubkey,
+    signer_pk: &Pubkey,
+    vault_pk: &Pubkey,
+    outcome_mint_pks: &[Pubkey],
+    order_id: u64
+) -> Result<Instruction, ProgramError> {
+
+    let accounts = settle_order_accounts(
+        omega_contract_pk, order_book_pk, owner_pk, owner_quote_pk, owner_outcome_pk,
+        quote_escrow_pk, outcome_escrow_pk, signer_pk, vault_pk, outcome_mint_pks
+    );
+
+    let instr = OmegaInstruction::SettleOrder { order_id };
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+pub fn match_orders(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    order_book_pk: &Pubkey,
+    limit: u64
+) -> Result<Instruction, ProgramError> {
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*omega_contract_pk, false),
+        AccountMeta::new(*order_book_pk, false),
+    ];
+
+    let instr = OmegaInstruction::MatchOrders { limit };
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+/// The book_pks are (order_book, quote_escrow, outcome_escrow, outcome_mint) for each outcome in order
+pub fn match_sets(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    vault_pk: &Pubkey,
+    signer_pk: &Pubkey,
+    book_pks: &[(Pubkey, Pubkey, Pubkey, Pubkey)],
+    side: Side,
+    limit: u64
+) -> Result<Instruction, ProgramError> {
+
+    let mut accounts = vec![
+        AccountMeta::new(*omega_contract_pk, false),
+        AccountMeta::new(*vault_pk, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(*signer_pk, false),
+        AccountMeta::new_readonly(solana_program::sysvar::clock::ID, false),
+    ];
+
+    for (order_book_pk, quote_escrow_pk, outcome_escrow_pk, outcome_mint_pk) in book_pks {
+        accounts.push(AccountMeta::new(*order_book_pk, false));
+        accounts.push(AccountMeta::new(*quote_escrow_pk, false));
+        accounts.push(AccountMeta::new(*outcome_escrow_pk, false));
+        accounts.push(AccountMeta::new(*outcome_mint_pk, false));
+    }
+
+    let instr = OmegaInstruction::MatchSets { side: side.into(), limit };
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+pub fn init_parimutuel(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    oracle_pk: &Pubkey,
+    fee_pk: &Pubkey,
+    outcome_mint_pks: &[Pubkey],
+    fee_bps: u64
+) -> Result<Instruction, ProgramError> {
+
+    let mut accounts = vec![
+        AccountMeta::new(*omega_contract_pk, false),
+        AccountMeta::new_readonly(*oracle_pk, true),
+        AccountMeta::new_readonly(*fee_pk, false),
+    ];
+
+    for pk in outcome_mint_pks {
+        accounts.push(AccountMeta::new_readonly(*pk, false));
+    }
+
+    let instr = OmegaInstruction::InitParimutuel { fee_bps };
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn stake(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    user_pk: &Pubkey,
+    user_quote_pk: &Pubkey,
+    vault_pk: &Pubkey,
+    signer_pk: &Pubkey,
+    outcome_mint_pk: &Pubkey,
+    outcome_user_pk: &Pubkey,
+    outcome_mint_pks: &[Pubkey],
+    outcome: u64,
+    quantity: u64
+) -> Result<Instruction, ProgramError> {
+
+    let accounts = lmsr_trade_accounts(
+        omega_contract_pk, user_pk, user_quote_pk, vault_pk, signer_pk, outcome_mint_pk, outcome_user_pk,
+        outcome_mint_pks
+    );
+
+    let instr = OmegaInstruction::Stake { outcome, quantity };
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+pub fn collect_parimutuel_fee(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    fee_pk: &Pubkey,
+    vault_pk: &Pubkey,
+    signer_pk: &Pubkey,
+    winner_mint_pk: &Pubkey
+) -> Result<Instruction, ProgramError> {
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*omega_contract_pk, false),
+        AccountMeta::new(*fee_pk, false),
+        AccountMeta::new(*vault_pk, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(*signer_pk, false),
+        AccountMeta::new_readonly(*winner_mint_pk, false),
+        AccountMeta::new_readonly(solana_program::sysvar::clock::ID, false),
+    ];
+
+    let instr = OmegaInstruction::CollectParimutuelFee;
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn swap_outcome_accounts(
+    omega_contract_pk: &Pubkey,
+    user_pk: &Pubkey,
+    user_quote_pk: &Pubkey,
+    vault_pk: &Pubkey,
+    signer_pk: &Pubkey,
+    outcome_mint_pk: &Pubkey,
+    outcome_user_pk: &Pubkey,
+    book_pks: &[(Pubkey, Pubkey, Pubkey, Pubkey)],
+) -> Vec<AccountMeta> {
+    let mut accounts = vec![
+        AccountMeta::new(*omega_contract_pk, false),
+        AccountMeta::new_readonly(*user_pk, true),
+        AccountMeta::new(*user_quote_pk, false),
+        AccountMeta::new(*vault_pk, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(*signer_pk, false),
+        AccountMeta::new(*outcome_mint_pk, false),
+        AccountMeta::new(*outcome_user_pk, false),
+        AccountMeta::new_readonly(solana_program::sysvar::clock::ID, false),
+    ];
+
+    for (order_book_pk, quote_escrow_pk, outcome_escrow_pk, other_mint_pk) in book_pks {
+        accounts.push(AccountMeta::new(*order_book_pk, false));
+        accounts.push(AccountMeta::new(*quote_escrow_pk, false));
+        accounts.push(AccountMeta::new(*outcome_escrow_pk, false));
+        accounts.push(AccountMeta::new(*other_mint_pk, false));
+    }
+    accounts
+}
+
+/// The book_pks are (order_book, quote_escrow, outcome_escrow, outcome_mint) for every other outcome in order
+#[allow(clippy::too_many_arguments)]
+pub fn buy_outcome(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    user_pk: &Pubkey,
+    user_quote_pk: &Pubkey,
+    vault_pk: &Pubkey,
+    signer_pk: &Pubkey,
+    outcome_mint_pk: &Pubkey,
+    outcome_user_pk: &Pubkey,
+    book_pks: &[(Pubkey, Pubkey, Pubkey, Pubkey)],
+    outcome: u64,
+    quantity: u64,
+    max_cost: u64
+) -> Result<Instruction, ProgramError> {
+
+    let accounts = swap_outcome_accounts(
+        omega_contract_pk, user_pk, user_quote_pk, vault_pk, signer_pk, outcome_mint_pk, outcome_user_pk, book_pks
+    );
+
+    let instr = OmegaInstruction::BuyOutcome { outcome, quantity, max_cost };
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+/// The book_pks are (order_book, quote_escrow, outcome_escrow, outcome_mint) for every other outcome in order
+#[allow(clippy::too_many_arguments)]
+pub fn sell_outcome(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    user_pk: &Pubkey,
+    user_quote_pk: &Pubkey,
+    vault_pk: &Pubkey,
+    signer_pk: &Pubkey,
+    outcome_mint_pk: &Pubkey,
+    outcome_user_pk: &Pubkey,
+    book_pks: &[(Pubkey, Pubkey, Pubkey, Pubkey)],
+    outcome: u64,
+    quantity: u64,
+    min_proceeds: u64
+) -> Result<Instruction, ProgramError> {
+
+    let accounts = swap_outcome_accounts(
+        omega_contract_pk, user_pk, user_quote_pk, vault_pk, signer_pk, outcome_mint_pk, outcome_user_pk, book_pks
+    );
+
+    let instr = OmegaInstruction::SellOutcome { outcome, quantity, min_proceeds };
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+pub fn init_conditional(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    oracle_pk: &Pubkey,
+    parent_contract_pk: &Pubkey,
+    parent_outcome: u64
+) -> Result<Instruction, ProgramError> {
+
+    let accounts = vec![
+        AccountMeta::new(*omega_contract_pk, false),
+        AccountMeta::new_readonly(*oracle_pk, true),
+        AccountMeta::new_readonly(*parent_contract_pk, false),
+    ];
+
+    let instr = OmegaInstruction::InitConditional { parent_outcome };
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn conditional_set_accounts(
+    omega_contract_pk: &Pubkey,
+    parent_contract_pk: &Pubkey,
+    user_pk: &Pubkey,
+    user_quote_pk: &Pubkey,
+    parent_vault_pk: &Pubkey,
+    vault_pk: &Pubkey,
+    parent_signer_pk: &Pubkey,
+    signer_pk: &Pubkey,
+    parent_outcome_pks: &[(Pubkey, Pubkey)],
+    outcome_pks: &[(Pubkey, Pubkey)]
+) -> Vec<AccountMeta> {
+    let mut accounts = vec![
+        AccountMeta::new(*omega_contract_pk, false),
+        AccountMeta::new(*parent_contract_pk, false),
+        AccountMeta::new_readonly(*user_pk, true),
+        AccountMeta::new(*user_quote_pk, false),
+        AccountMeta::new(*parent_vault_pk, false),
+        AccountMeta::new(*vault_pk, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(*parent_signer_pk, false),
+        AccountMeta::new_readonly(*signer_pk, false),
+        AccountMeta::new_readonly(solana_program::sysvar::clock::ID, false),
+    ];
+
+    for (outcome_mint_pk, outcome_user_pk) in parent_outcome_pks.iter().chain(outcome_pks) {
+        accounts.push(AccountMeta::new(*outcome_mint_pk, false));
         accounts.push(AccountMeta::new(*outcome_user_pk, false));
     }
+    accounts
+}
+
+/// The parent_outcome_pks are (mint, user_acc) for each parent outcome, where user_acc is the conditional
+/// contract's vault for the outcome backing it. The outcome_pks are (mint, user_acc) for each conditional outcome
+#[allow(clippy::too_many_arguments)]
+pub fn issue_conditional_set(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    parent_contract_pk: &Pubkey,
+    user_pk: &Pubkey,
+    user_quote_pk: &Pubkey,
+    parent_vault_pk: &Pubkey,
+    vault_pk: &Pubkey,
+    parent_signer_pk: &Pubkey,
+    signer_pk: &Pubkey,
+    parent_outcome_pks: &[(Pubkey, Pubkey)],
+    outcome_pks: &[(Pubkey, Pubkey)],
+    quantity: u64
+) -> Result<Instruction, ProgramError> {
+
+    let accounts = conditional_set_accounts(
+        omega_contract_pk, parent_contract_pk, user_pk, user_quote_pk, parent_vault_pk, vault_pk,
+        parent_signer_pk, signer_pk, parent_outcome_pks, outcome_pks
+    );
+
+    let instr = OmegaInstruction::IssueConditionalSet { quantity };
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn redeem_conditional_set(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    parent_contract_pk: &Pubkey,
+    user_pk: &Pubkey,
+    user_quote_pk: &Pubkey,
+    parent_vault_pk: &Pubkey,
+    vault_pk: &Pubkey,
+    parent_signer_pk: &Pubkey,
+    signer_pk: &Pubkey,
+    parent_outcome_pks: &[(Pubkey, Pubkey)],
+    outcome_pks: &[(Pubkey, Pubkey)],
+    quantity: u64
+) -> Result<Instruction, ProgramError> {
+
+    let accounts = conditional_set_accounts(
+        omega_contract_pk, parent_contract_pk, user_pk, user_quote_pk, parent_vault_pk, vault_pk,
+        parent_signer_pk, signer_pk, parent_outcome_pks, outcome_pks
+    );
+
+    let instr = OmegaInstruction::RedeemConditionalSet { quantity };
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn redeem_conditional_winner(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    parent_contract_pk: &Pubkey,
+    user_pk: &Pubkey,
+    user_quote_pk: &Pubkey,
+    parent_vault_pk: &Pubkey,
+    vault_pk: &Pubkey,
+    parent_signer_pk: &Pubkey,
+    signer_pk: &Pubkey,
+    winner_mint_pk: &Pubkey,
+    winner_user_pk: &Pubkey,
+    collateral_mint_pk: &Pubkey,
+    parent_mint_pks: &[Pubkey],
+    quantity: u64
+) -> Result<Instruction, ProgramError> {
+
+    let mut accounts = vec![
+        AccountMeta::new(*omega_contract_pk, false),
+        AccountMeta::new(*parent_contract_pk, false),
+        AccountMeta::new_readonly(*user_pk, true),
+        AccountMeta::new(*user_quote_pk, false),
+        AccountMeta::new(*parent_vault_pk, false),
+        AccountMeta::new(*vault_pk, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(*parent_signer_pk, false),
+        AccountMeta::new_readonly(*signer_pk, false),
+        AccountMeta::new(*winner_mint_pk, false),
+        AccountMeta::new(*winner_user_pk, false),
+        AccountMeta::new(*collateral_mint_pk, false),
+        AccountMeta::new_readonly(solana_program::sysvar::clock::ID, false)
+    ];
+    accounts.extend(outcome_mint_accounts(parent_mint_pks));
+
+    let instr = OmegaInstruction::RedeemConditionalWinner { quantity };
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+pub fn init_bucket_group(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    oracle_pk: &Pubkey,
+    breakpoints: &[i64]
+) -> Result<Instruction, ProgramError> {
+
+    let accounts = vec![
+        AccountMeta::new(*omega_contract_pk, false),
+        AccountMeta::new_readonly(*oracle_pk, true),
+    ];
+
+    if breakpoints.len() >= MAX_OUTCOMES {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let mut breakpoints_buf = [0i64; MAX_OUTCOMES - 1];
+    breakpoints_buf[..breakpoints.len()].copy_from_slice(breakpoints);
+
+    let instr = OmegaInstruction::InitBucketGroup {
+        num_breakpoints: breakpoints.len() as u64,
+        breakpoints: breakpoints_buf
+    };
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+pub fn resolve_value(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    oracle_pk: &Pubkey,
+    vault_pk: &Pubkey,
+    outcome_mint_pks: &[Pubkey],
+    value: i64
+) -> Result<Instruction, ProgramError> {
+
+    let mut accounts = vec![
+        AccountMeta::new(*omega_contract_pk, false),
+        AccountMeta::new_readonly(*oracle_pk, true),
+        AccountMeta::new_readonly(solana_program::sysvar::clock::ID, false),
+    ];
+    accounts.extend(solvency_accounts(vault_pk, outcome_mint_pks));
+
+    let instr = OmegaInstruction::ResolveValue { value };
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn init_market_series(
+    program_id: &Pubkey,
+    market_series_pk: &Pubkey,
+    oracle_pk: &Pubkey,
+    quote_mint_pk: &Pubkey,
+    creator_pk: &Pubkey,
+    num_outcomes: u64,
+    first_exp_time: u64,
+    period: u64,
+    resolve_window: u64,
+    details_template: &str
+) -> Result<Instruction, ProgramError> {
+
+    let (program_config_pk, _) = find_program_config_address(program_id);
+    let accounts = vec![
+        AccountMeta::new(*market_series_pk, false),
+        AccountMeta::new_readonly(*oracle_pk, false),
+        AccountMeta::new_readonly(*quote_mint_pk, false),
+        AccountMeta::new_readonly(solana_program::sysvar::rent::ID, false),
+        AccountMeta::new_readonly(program_config_pk, false),
+        AccountMeta::new_readonly(*creator_pk, true),
+    ];
+
+    let instr = OmegaInstruction::InitMarketSeries {
+        num_outcomes,
+        first_exp_time,
+        period,
+        resolve_window
+    };
+    let mut data = instr.pack();
+    data.extend_from_slice(details_template.as_bytes());
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+/// Address of contract `index` of a series and the nonce used to derive it
+pub fn find_series_contract_address(program_id: &Pubkey, market_series_pk: &Pubkey, index: u64) -> (Pubkey, u64) {
+    for nonce in 0..256u64 {
+        let seeds = [market_series_pk.as_ref(), &index.to_le_bytes(), &nonce.to_le_bytes()];
+        if let Ok(pk) = Pubkey::create_program_address(&seeds, program_id) {
+            return (pk, nonce);
+        }
+    }
+    panic!("Could not generate series contract address");
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_series_contract(
+    program_id: &Pubkey,
+    market_series_pk: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    payer_pk: &Pubkey,
+    quote_mint_pk: &Pubkey,
+    vault_pk: &Pubkey,
+    signer_pk: &Pubkey,
+    outcome_pks: &[Pubkey],
+    contract_nonce: u64,
+    signer_nonce: u64
+) -> Result<Instruction, ProgramError> {
+
+    let mut accounts = vec![
+        AccountMeta::new(*market_series_pk, false),
+        AccountMeta::new(*omega_contract_pk, false),
+        AccountMeta::new(*payer_pk, true),
+        AccountMeta::new_readonly(*quote_mint_pk, false),
+        AccountMeta::new_readonly(*vault_pk, false),
+        AccountMeta::new_readonly(*signer_pk, false),
+        AccountMeta::new_readonly(solana_program::system_program::ID, false),
+        AccountMeta::new_readonly(solana_program::sysvar::rent::ID, false),
+    ];
+    for pk in outcome_pks {
+        accounts.push(AccountMeta::new_readonly(*pk, false));
+    }
+
+    let instr = OmegaInstruction::CreateSeriesContract { contract_nonce, signer_nonce };
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+pub fn init_registry(
+    program_id: &Pubkey,
+    registry_pk: &Pubkey,
+    creator_pk: &Pubkey
+) -> Result<Instruction, ProgramError> {
+
+    let accounts = vec![
+        AccountMeta::new(*registry_pk, false),
+        AccountMeta::new_readonly(*creator_pk, true),
+        AccountMeta::new_readonly(solana_program::sysvar::rent::ID, false),
+    ];
+
+    let instr = OmegaInstruction::InitRegistry;
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+/// Pay the creator bond required by the program config
+pub fn with_creator_bond(mut instruction: Instruction, creator_quote_pk: &Pubkey, escrow_pk: &Pubkey) -> Instruction {
+    instruction.accounts.push(AccountMeta::new(*creator_quote_pk, false));
+    instruction.accounts.push(AccountMeta::new(*escrow_pk, false));
+    instruction.accounts.push(AccountMeta::new_readonly(spl_token::id(), false));
+    instruction
+}
+
+pub fn update_registry_entry(
+    program_id: &Pubkey,
+    registry_pk: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    oracle_pk: &Pubkey,
+    category: u64
+) -> Result<Instruction, ProgramError> {
+
+    let accounts = vec![
+        AccountMeta::new(*registry_pk, false),
+        AccountMeta::new_readonly(*omega_contract_pk, false),
+        AccountMeta::new_readonly(*oracle_pk, true),
+    ];
+
+    let instr = OmegaInstruction::UpdateRegistryEntry { category };
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+pub fn close_contract(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    registry_pk: &Pubkey,
+    creator_pk: &Pubkey,
+    vault_pk: &Pubkey
+) -> Result<Instruction, ProgramError> {
+
+    let accounts = vec![
+        AccountMeta::new(*omega_contract_pk, false),
+        AccountMeta::new(*registry_pk, false),
+        AccountMeta::new(*creator_pk, true),
+        AccountMeta::new_readonly(*vault_pk, false),
+        AccountMeta::new_readonly(solana_program::sysvar::clock::ID, false),
+    ];
+
+    let instr = OmegaInstruction::CloseContract;
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+/// Address of the ProgramConfig singleton and the nonce used to derive it
+pub fn find_program_config_address(program_id: &Pubkey) -> (Pubkey, u64) {
+    for nonce in 0..256u64 {
+        if let Ok(pk) = Pubkey::create_program_address(&[PROGRAM_CONFIG_SEED, &nonce.to_le_bytes()], program_id) {
+            return (pk, nonce);
+        }
+    }
+    panic!("Could not generate program config address");
+}
+
+fn config_settings_accounts(treasury_pk: &Pubkey, quote_mint_pks: &[Pubkey], creator_pks: &[Pubkey]) -> Vec<AccountMeta> {
+    std::iter::once(treasury_pk).chain(quote_mint_pks.iter()).chain(creator_pks.iter())
+        .map(|pk| AccountMeta::new_readonly(*pk, false))
+        .collect()
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn init_program_config(
+    program_id: &Pubkey,
+    admin_pk: &Pubkey,
+    timelock: u64,
+    fee_bps: u64,
+    creator_bond: u64,
+    treasury_pk: &Pubkey,
+    quote_mint_pks: &[Pubkey],
+    creator_pks: Option<&[Pubkey]>
+) -> Result<Instruction, ProgramError> {
+
+    let (program_config_pk, _) = find_program_config_address(program_id);
+    let mut accounts = vec![
+        AccountMeta::new(program_config_pk, false),
+        AccountMeta::new(*admin_pk, true),
+        AccountMeta::new_readonly(solana_program::system_program::ID, false),
+        AccountMeta::new_readonly(solana_program::sysvar::rent::ID, false),
+    ];
+    accounts.extend(config_settings_accounts(treasury_pk, quote_mint_pks, creator_pks.unwrap_or(&[])));
+
+    let instr = OmegaInstruction::InitProgramConfig {
+        timelock,
+        fee_bps,
+        restrict_creators: creator_pks.is_some() as u64,
+        num_quote_mints: quote_mint_pks.len() as u64,
+        creator_bond
+    };
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+/// Pass `creator_pks` to only allow those creators to make markets
+#[allow(clippy::too_many_arguments)]
+pub fn propose_config(
+    program_id: &Pubkey,
+    admin_pk: &Pubkey,
+    new_admin_pk: &Pubkey,
+    timelock: u64,
+    fee_bps: u64,
+    creator_bond: u64,
+    treasury_pk: &Pubkey,
+    quote_mint_pks: &[Pubkey],
+    creator_pks: Option<&[Pubkey]>
+) -> Result<Instruction, ProgramError> {
+
+    let (program_config_pk, _) = find_program_config_address(program_id);
+    let mut accounts = vec![
+        AccountMeta::new(program_config_pk, false),
+        AccountMeta::new_readonly(*admin_pk, true),
+        AccountMeta::new_readonly(*new_admin_pk, false),
+        AccountMeta::new_readonly(solana_program::sysvar::clock::ID, false),
+    ];
+    accounts.extend(config_settings_accounts(treasury_pk, quote_mint_pks, creator_pks.unwrap_or(&[])));
+
+    let instr = OmegaInstruction::ProposeConfig {
+        timelock,
+        fee_bps,
+        restrict_creators: creator_pks.is_some() as u64,
+        num_quote_mints: quote_mint_pks.len() as u64,
+        creator_bond
+    };
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+pub fn apply_config(program_id: &Pubkey) -> Result<Instruction, ProgramError> {
+
+    let (program_config_pk, _) = find_program_config_address(program_id);
+    let accounts = vec![
+        AccountMeta::new(program_config_pk, false),
+        AccountMeta::new_readonly(solana_program::sysvar::clock::ID, false),
+    ];
+
+    let instr = OmegaInstruction::ApplyConfig;
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn init_price_resolution(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    oracle_pk: &Pubkey,
+    price_pk: &Pubkey,
+    price_program_pk: &Pubkey,
+    source: PriceSourceKind,
+    comparison: Comparison,
+    threshold: i64,
+    max_staleness: u64
+) -> Result<Instruction, ProgramError> {
+
+    let accounts = vec![
+        AccountMeta::new(*omega_contract_pk, false),
+        AccountMeta::new_readonly(*oracle_pk, true),
+        AccountMeta::new_readonly(*price_pk, false),
+        AccountMeta::new_readonly(*price_program_pk, false),
+    ];
+
+    let instr = OmegaInstruction::InitPriceResolution {
+        source: source.into(),
+        comparison: comparison.into(),
+        threshold,
+        max_staleness
+    };
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+pub fn resolve_from_price(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    price_pk: &Pubkey,
+    vault_pk: &Pubkey,
+    outcome_mint_pks: &[Pubkey]
+) -> Result<Instruction, ProgramError> {
+
+    let mut accounts = vec![
+        AccountMeta::new(*omega_contract_pk, false),
+        AccountMeta::new_readonly(*price_pk, false),
+        AccountMeta::new_readonly(solana_program::sysvar::clock::ID, false),
+    ];
+    accounts.extend(solvency_accounts(vault_pk, outcome_mint_pks));
+
+    let instr = OmegaInstruction::ResolveFromPrice;
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+/// Returns the ed25519 verification instruction followed by the ResolveWithAttestation instruction,
+/// which have to be sent in this order in one transaction
+#[allow(clippy::too_many_arguments)]
+pub fn resolve_with_attestation(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    oracle_pk: &Pubkey,
+    vault_pk: &Pubkey,
+    outcome_mint_pks: &[Pubkey],
+    winner: u64,
+    timestamp: i64,
+    signature: &[u8; 64]
+) -> Result<Vec<Instruction>, ProgramError> {
+
+    let message = attestation_message(omega_contract_pk, winner, timestamp);
+    let verify_instruction = Instruction {
+        program_id: ed25519_program::ID,
+        accounts: vec![],
+        data: ed25519_instruction_data(oracle_pk, signature, &message)
+    };
+
+    let mut accounts = vec![
+        AccountMeta::new(*omega_contract_pk, false),
+        AccountMeta::new_readonly(solana_program::sysvar::instructions::ID, false),
+        AccountMeta::new_readonly(solana_program::sysvar::clock::ID, false),
+    ];
+    accounts.extend(solvency_accounts(vault_pk, outcome_mint_pks));
+
+    let instr = OmegaInstruction::ResolveWithAttestation { winner, timestamp };
+    let data = instr.pack();
+    Ok(vec![
+        verify_instruction,
+        Instruction {
+            program_id: *program_id,
+            accounts,
+            data
+        }
+    ])
+}
+
+/// Hash the oracle commits to with CommitResolution
+pub fn commitment_hash(winner_pk: &Pubkey, salt: &[u8; 32]) -> [u8; 32] {
+    solana_program::hash::hashv(&[winner_pk.as_ref(), salt]).to_bytes()
+}
+
+pub fn commit_resolution(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    oracle_pk: &Pubkey,
+    commitment: [u8; 32],
+    reveal_window: u64
+) -> Result<Instruction, ProgramError> {
+
+    let accounts = vec![
+        AccountMeta::new(*omega_contract_pk, false),
+        AccountMeta::new_readonly(*oracle_pk, true),
+        AccountMeta::new_readonly(solana_program::sysvar::clock::ID, false),
+    ];
+
+    let instr = OmegaInstruction::CommitResolution { commitment, reveal_window };
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+pub fn reveal_resolution(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    winner_pk: &Pubkey,
+    vault_pk: &Pubkey,
+    outcome_mint_pks: &[Pubkey],
+    salt: [u8; 32]
+) -> Result<Instruction, ProgramError> {
+
+    let mut accounts = vec![
+        AccountMeta::new(*omega_contract_pk, false),
+        AccountMeta::new_readonly(*winner_pk, false),
+        AccountMeta::new_readonly(solana_program::sysvar::clock::ID, false),
+    ];
+    accounts.extend(solvency_accounts(vault_pk, outcome_mint_pks));
+
+    let instr = OmegaInstruction::RevealResolution { salt };
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+pub fn init_oracle_bond(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    oracle_pk: &Pubkey,
+    oracle_quote_pk: &Pubkey,
+    escrow_pk: &Pubkey,
+    amount: u64,
+    dispute_window: u64
+) -> Result<Instruction, ProgramError> {
+
+    let accounts = vec![
+        AccountMeta::new(*omega_contract_pk, false),
+        AccountMeta::new_readonly(*oracle_pk, true),
+        AccountMeta::new(*oracle_quote_pk, false),
+        AccountMeta::new(*escrow_pk, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(solana_program::sysvar::clock::ID, false),
+    ];
+
+    let instr = OmegaInstruction::InitOracleBond { amount, dispute_window };
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+pub fn release_oracle_bond(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    escrow_pk: &Pubkey,
+    oracle_quote_pk: &Pubkey,
+    omega_signer_pk: &Pubkey
+) -> Result<Instruction, ProgramError> {
+
+    let accounts = vec![
+        AccountMeta::new(*omega_contract_pk, false),
+        AccountMeta::new(*escrow_pk, false),
+        AccountMeta::new(*oracle_quote_pk, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(*omega_signer_pk, false),
+        AccountMeta::new_readonly(solana_program::sysvar::clock::ID, false),
+    ];
+
+    let instr = OmegaInstruction::ReleaseOracleBond;
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+pub fn slash_oracle_bond(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    escrow_pk: &Pubkey,
+    vault_pk: &Pubkey,
+    omega_signer_pk: &Pubkey,
+    outcome_pks: &[Pubkey]
+) -> Result<Instruction, ProgramError> {
+
+    let mut accounts = vec![
+        AccountMeta::new(*omega_contract_pk, false),
+        AccountMeta::new(*escrow_pk, false),
+        AccountMeta::new(*vault_pk, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(*omega_signer_pk, false),
+        AccountMeta::new_readonly(solana_program::sysvar::clock::ID, false),
+    ];
+    accounts.extend(outcome_pks.iter().map(|pk| AccountMeta::new_readonly(*pk, false)));
+
+    let instr = OmegaInstruction::SlashOracleBond;
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+pub fn overturn_resolution(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    admin_pk: &Pubkey,
+    winner_pk: &Pubkey,
+    escrow_pk: &Pubkey,
+    vault_pk: &Pubkey,
+    omega_signer_pk: &Pubkey
+) -> Result<Instruction, ProgramError> {
+    let (program_config_pk, _) = find_program_config_address(program_id);
+
+    let accounts = vec![
+        AccountMeta::new(*omega_contract_pk, false),
+        AccountMeta::new_readonly(program_config_pk, false),
+        AccountMeta::new_readonly(*admin_pk, true),
+        AccountMeta::new_readonly(*winner_pk, false),
+        AccountMeta::new(*escrow_pk, false),
+        AccountMeta::new(*vault_pk, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(*omega_signer_pk, false),
+        AccountMeta::new_readonly(solana_program::sysvar::clock::ID, false),
+    ];
+
+    let instr = OmegaInstruction::OverturnResolution;
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
 
-    let instr = OmegaInstruction::RedeemSet { quantity };
-    let data = instr.pack();
+#[allow(clippy::too_many_arguments)]
+pub fn init_oracle_fee(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    funder_pk: &Pubkey,
+    funder_quote_pk: &Pubkey,
+    vault_pk: &Pubkey,
+    amount: u64,
+    fee_bps: u64,
+    deadline: u64
+) -> Result<Instruction, ProgramError> {
 
+    let accounts = vec![
+        AccountMeta::new(*omega_contract_pk, false),
+        AccountMeta::new_readonly(*funder_pk, true),
+        AccountMeta::new(*funder_quote_pk, false),
+        AccountMeta::new(*vault_pk, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(solana_program::sysvar::clock::ID, false),
+    ];
+
+    let instr = OmegaInstruction::InitOracleFee { amount, fee_bps, deadline };
+    let data = instr.pack();
     Ok(Instruction {
         program_id: *program_id,
         accounts,
@@ -260,33 +2957,60 @@ pub fn redeem_set(
     })
 }
 
-pub fn redeem_winner(
+pub fn withdraw_oracle_fee(
     program_id: &Pubkey,
     omega_contract_pk: &Pubkey,
-    user_pk: &Pubkey,
-    user_quote_pk: &Pubkey,
+    funder_quote_pk: &Pubkey,
     vault_pk: &Pubkey,
-    signer_pk: &Pubkey,
-    winner_mint_pk: &Pubkey,
-    winner_user_pk: &Pubkey,
-    quantity: u64
+    omega_signer_pk: &Pubkey,
+    outcome_mint_pks: &[Pubkey]
 ) -> Result<Instruction, ProgramError> {
 
-    let accounts = vec![
-        AccountMeta::new_readonly(*omega_contract_pk, false),
-        AccountMeta::new_readonly(*user_pk, true),
-        AccountMeta::new(*user_quote_pk, false),
+    let mut accounts = vec![
+        AccountMeta::new(*omega_contract_pk, false),
+        AccountMeta::new(*funder_quote_pk, false),
         AccountMeta::new(*vault_pk, false),
         AccountMeta::new_readonly(spl_token::id(), false),
-        AccountMeta::new_readonly(*signer_pk, false),
-        AccountMeta::new(*winner_mint_pk, false),
-        AccountMeta::new(*winner_user_pk, false),
-        AccountMeta::new_readonly(solana_program::sysvar::clock::ID, false)
+        AccountMeta::new_readonly(*omega_signer_pk, false),
+        AccountMeta::new_readonly(solana_program::sysvar::clock::ID, false),
     ];
+    accounts.extend(outcome_mint_accounts(outcome_mint_pks));
 
-    let instr = OmegaInstruction::RedeemWinner { quantity };
+    let instr = OmegaInstruction::WithdrawOracleFee;
     let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+/// Pass `bond` as (escrow, vault, omega signer) if the oracle bond is posted and not slashed yet
+pub fn resolve_late(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    admin_pk: &Pubkey,
+    winner_pk: &Pubkey,
+    bond: Option<(&Pubkey, &Pubkey, &Pubkey)>
+) -> Result<Instruction, ProgramError> {
+    let (program_config_pk, _) = find_program_config_address(program_id);
+
+    let mut accounts = vec![
+        AccountMeta::new(*omega_contract_pk, false),
+        AccountMeta::new_readonly(program_config_pk, false),
+        AccountMeta::new_readonly(*admin_pk, true),
+        AccountMeta::new_readonly(*winner_pk, false),
+        AccountMeta::new_readonly(solana_program::sysvar::clock::ID, false),
+    ];
+    if let Some((escrow_pk, vault_pk, omega_signer_pk)) = bond {
+        accounts.push(AccountMeta::new(*escrow_pk, false));
+        accounts.push(AccountMeta::new(*vault_pk, false));
+        accounts.push(AccountMeta::new_readonly(spl_token::id(), false));
+        accounts.push(AccountMeta::new_readonly(*omega_signer_pk, false));
+    }
 
+    let instr = OmegaInstruction::ResolveLate;
+    let data = instr.pack();
     Ok(Instruction {
         program_id: *program_id,
         accounts,
@@ -294,26 +3018,339 @@ pub fn redeem_winner(
     })
 }
 
+pub fn init_oracle_liveness(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    oracle_pk: &Pubkey,
+    backup_oracle_pk: &Pubkey,
+    heartbeat_period: u64
+) -> Result<Instruction, ProgramError> {
+
+    let accounts = vec![
+        AccountMeta::new(*omega_contract_pk, false),
+        AccountMeta::new_readonly(*oracle_pk, true),
+        AccountMeta::new_readonly(*backup_oracle_pk, false),
+        AccountMeta::new_readonly(solana_program::sysvar::clock::ID, false),
+    ];
 
-pub fn resolve(
+    let instr = OmegaInstruction::InitOracleLiveness { heartbeat_period };
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+pub fn oracle_heartbeat(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    oracle_pk: &Pubkey
+) -> Result<Instruction, ProgramError> {
+
+    let accounts = vec![
+        AccountMeta::new(*omega_contract_pk, false),
+        AccountMeta::new_readonly(*oracle_pk, true),
+        AccountMeta::new_readonly(solana_program::sysvar::clock::ID, false),
+    ];
+
+    let instr = OmegaInstruction::OracleHeartbeat;
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+pub fn find_oracle_reputation_address(program_id: &Pubkey, oracle_pk: &Pubkey) -> (Pubkey, u64) {
+    for nonce in 0..256u64 {
+        let seeds = [ORACLE_REPUTATION_SEED, oracle_pk.as_ref(), &nonce.to_le_bytes()];
+        if let Ok(pk) = Pubkey::create_program_address(&seeds, program_id) {
+            return (pk, nonce);
+        }
+    }
+    panic!("Could not generate oracle reputation address");
+}
+
+pub fn init_oracle_reputation(
+    program_id: &Pubkey,
+    oracle_pk: &Pubkey,
+    payer_pk: &Pubkey
+) -> Result<Instruction, ProgramError> {
+    let (oracle_reputation_pk, _) = find_oracle_reputation_address(program_id, oracle_pk);
+
+    let accounts = vec![
+        AccountMeta::new(oracle_reputation_pk, false),
+        AccountMeta::new_readonly(*oracle_pk, false),
+        AccountMeta::new(*payer_pk, true),
+        AccountMeta::new_readonly(solana_program::system_program::ID, false),
+        AccountMeta::new_readonly(solana_program::sysvar::rent::ID, false),
+    ];
+
+    let instr = OmegaInstruction::InitOracleReputation;
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+pub fn record_oracle_reputation(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    oracle_pk: &Pubkey
+) -> Result<Instruction, ProgramError> {
+    let (oracle_reputation_pk, _) = find_oracle_reputation_address(program_id, oracle_pk);
+
+    let accounts = vec![
+        AccountMeta::new(*omega_contract_pk, false),
+        AccountMeta::new(oracle_reputation_pk, false),
+        AccountMeta::new_readonly(solana_program::sysvar::clock::ID, false),
+    ];
+
+    let instr = OmegaInstruction::RecordOracleReputation;
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn init_vote_resolution(
     program_id: &Pubkey,
     omega_contract_pk: &Pubkey,
     oracle_pk: &Pubkey,
-    winner_pk: &Pubkey
+    gov_mint_pk: &Pubkey,
+    escrow_pk: &Pubkey,
+    voting_period: u64,
+    quorum: u64,
+    tie_break: TieBreak,
+    tie_break_outcome: u64
 ) -> Result<Instruction, ProgramError> {
 
     let accounts = vec![
         AccountMeta::new(*omega_contract_pk, false),
         AccountMeta::new_readonly(*oracle_pk, true),
-        AccountMeta::new_readonly(*winner_pk, false),
+        AccountMeta::new_readonly(*gov_mint_pk, false),
+        AccountMeta::new_readonly(*escrow_pk, false),
         AccountMeta::new_readonly(solana_program::sysvar::clock::ID, false),
     ];
 
-    let instr = OmegaInstruction::Resolve;
+    let instr = OmegaInstruction::InitVoteResolution {
+        voting_period,
+        quorum,
+        tie_break: tie_break.into(),
+        tie_break_outcome
+    };
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+pub fn find_vote_record_address(program_id: &Pubkey, omega_contract_pk: &Pubkey, voter_pk: &Pubkey) -> (Pubkey, u64) {
+    for nonce in 0..256u64 {
+        let seeds = [VOTE_RECORD_SEED, omega_contract_pk.as_ref(), voter_pk.as_ref(), &nonce.to_le_bytes()];
+        if let Ok(pk) = Pubkey::create_program_address(&seeds, program_id) {
+            return (pk, nonce);
+        }
+    }
+    panic!("Could not generate vote record address");
+}
+
+pub fn cast_vote(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    voter_pk: &Pubkey,
+    voter_gov_pk: &Pubkey,
+    escrow_pk: &Pubkey,
+    outcome: u64,
+    amount: u64
+) -> Result<Instruction, ProgramError> {
+    let (vote_record_pk, _) = find_vote_record_address(program_id, omega_contract_pk, voter_pk);
+
+    let accounts = vec![
+        AccountMeta::new(*omega_contract_pk, false),
+        AccountMeta::new(vote_record_pk, false),
+        AccountMeta::new(*voter_pk, true),
+        AccountMeta::new(*voter_gov_pk, false),
+        AccountMeta::new(*escrow_pk, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(solana_program::system_program::ID, false),
+        AccountMeta::new_readonly(solana_program::sysvar::rent::ID, false),
+        AccountMeta::new_readonly(solana_program::sysvar::clock::ID, false),
+    ];
+
+    let instr = OmegaInstruction::CastVote { outcome, amount };
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+pub fn finalize_vote(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    vault_pk: &Pubkey,
+    outcome_mint_pks: &[Pubkey]
+) -> Result<Instruction, ProgramError> {
+
+    let mut accounts = vec![
+        AccountMeta::new(*omega_contract_pk, false),
+        AccountMeta::new_readonly(solana_program::sysvar::clock::ID, false),
+    ];
+    accounts.extend(solvency_accounts(vault_pk, outcome_mint_pks));
+
+    let instr = OmegaInstruction::FinalizeVote;
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+pub fn withdraw_vote(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    voter_pk: &Pubkey,
+    voter_gov_pk: &Pubkey,
+    escrow_pk: &Pubkey,
+    omega_signer_pk: &Pubkey
+) -> Result<Instruction, ProgramError> {
+    let (vote_record_pk, _) = find_vote_record_address(program_id, omega_contract_pk, voter_pk);
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*omega_contract_pk, false),
+        AccountMeta::new(vote_record_pk, false),
+        AccountMeta::new(*voter_pk, true),
+        AccountMeta::new(*voter_gov_pk, false),
+        AccountMeta::new(*escrow_pk, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(*omega_signer_pk, false),
+        AccountMeta::new_readonly(solana_program::sysvar::clock::ID, false),
+    ];
+
+    let instr = OmegaInstruction::WithdrawVote;
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+pub fn release_creator_bond(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    escrow_pk: &Pubkey,
+    creator_quote_pk: &Pubkey,
+    omega_signer_pk: &Pubkey
+) -> Result<Instruction, ProgramError> {
+
+    let accounts = vec![
+        AccountMeta::new(*omega_contract_pk, false),
+        AccountMeta::new(*escrow_pk, false),
+        AccountMeta::new(*creator_quote_pk, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(*omega_signer_pk, false),
+        AccountMeta::new_readonly(solana_program::sysvar::clock::ID, false),
+    ];
+
+    let instr = OmegaInstruction::ReleaseCreatorBond;
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+/// Pass `admin_pk` unless the contract is resolved invalid
+pub fn forfeit_creator_bond(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    admin_pk: Option<&Pubkey>,
+    escrow_pk: &Pubkey,
+    treasury_quote_pk: &Pubkey,
+    omega_signer_pk: &Pubkey
+) -> Result<Instruction, ProgramError> {
+    let (program_config_pk, _) = find_program_config_address(program_id);
+
+    let accounts = vec![
+        AccountMeta::new(*omega_contract_pk, false),
+        AccountMeta::new_readonly(program_config_pk, false),
+        match admin_pk {
+            Some(admin_pk) => AccountMeta::new_readonly(*admin_pk, true),
+            None => AccountMeta::new_readonly(solana_program::system_program::ID, false)
+        },
+        AccountMeta::new(*escrow_pk, false),
+        AccountMeta::new(*treasury_quote_pk, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(*omega_signer_pk, false),
+        AccountMeta::new_readonly(solana_program::sysvar::clock::ID, false),
+    ];
+
+    let instr = OmegaInstruction::ForfeitCreatorBond;
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+pub fn set_allow_list(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    admin_pk: &Pubkey,
+    root: [u8; 32]
+) -> Result<Instruction, ProgramError> {
+    let (program_config_pk, _) = find_program_config_address(program_id);
+
+    let accounts = vec![
+        AccountMeta::new(*omega_contract_pk, false),
+        AccountMeta::new_readonly(program_config_pk, false),
+        AccountMeta::new_readonly(*admin_pk, true),
+    ];
+
+    let instr = OmegaInstruction::SetAllowList { root };
+    let data = instr.pack();
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data
+    })
+}
+
+pub fn set_issuance_caps(
+    program_id: &Pubkey,
+    omega_contract_pk: &Pubkey,
+    admin_pk: &Pubkey,
+    max_supply: u64,
+    wallet_cap: u64
+) -> Result<Instruction, ProgramError> {
+    let (program_config_pk, _) = find_program_config_address(program_id);
+
+    let accounts = vec![
+        AccountMeta::new(*omega_contract_pk, false),
+        AccountMeta::new_readonly(program_config_pk, false),
+        AccountMeta::new_readonly(*admin_pk, true),
+    ];
+
+    let instr = OmegaInstruction::SetIssuanceCaps { max_supply, wallet_cap };
     let data = instr.pack();
     Ok(Instruction {
         program_id: *program_id,
         accounts,
         data
     })
-}
\ No newline at end of file
+}
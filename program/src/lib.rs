@@ -4,6 +4,11 @@ pub mod error;
 pub mod processor;
 pub mod state;
 pub mod instruction;
+pub mod attestation;
+pub mod allow_list;
+pub mod lmsr;
+pub mod math;
+pub mod price;
 
 
 use solana_program::{
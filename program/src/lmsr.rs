@@ -0,0 +1,136 @@
+//! Logarithmic market scoring rule (LMSR) used by contracts that run their own market maker.
+//!
+//! The cost of the outstanding quantities q is C(q) = b * ln(sum(exp(q_i / b))), and a trade from
+//! q to q' costs C(q') - C(q). The market maker can lose at most C(0) = b * ln(num_outcomes).
+//!
+//! Only basic float arithmetic is used here because libm functions are not available on-chain.
+
+use std::f64::consts::LN_2;
+
+/// Smallest exponent for which `exp` still returns a normal f64
+const MIN_EXP: i64 = -1022;
+
+/// exp(x) computed as 2^k * exp(r) with |r| <= ln(2) / 2
+fn exp(x: f64) -> f64 {
+    let k = (x / LN_2 + if x < 0.0 { -0.5 } else { 0.5 }) as i64;
+    if k < MIN_EXP {
+        return 0.0;
+    }
+    let r = x - (k as f64) * LN_2;
+
+    let mut term = 1.0;
+    let mut sum = 1.0;
+    for i in 1..20 {
+        term *= r / (i as f64);
+        sum += term;
+    }
+
+    let scale = f64::from_bits(((k + 1023) as u64) << 52);
+    sum * scale
+}
+
+/// ln(x) for normal, positive x computed as k * ln(2) + 2 * atanh((m - 1) / (m + 1))
+/// where x = 2^k * m and m is in [1, 2)
+fn ln(x: f64) -> f64 {
+    let bits = x.to_bits();
+    let k = ((bits >> 52) & 0x7ff) as i64 - 1023;
+    let m = f64::from_bits((bits & ((1u64 << 52) - 1)) | (1023u64 << 52));
+
+    let z = (m - 1.0) / (m + 1.0);
+    let z2 = z * z;
+    let mut term = z;
+    let mut sum = 0.0;
+    for i in 0..20 {
+        sum += term / ((2 * i + 1) as f64);
+        term *= z2;
+    }
+
+    (k as f64) * LN_2 + 2.0 * sum
+}
+
+fn ceil(x: f64) -> u64 {
+    let t = x as u64;
+    if (t as f64) < x { t + 1 } else { t }
+}
+
+fn floor(x: f64) -> u64 {
+    x as u64
+}
+
+/// Value of the cost function C(q) in quote lots
+pub fn cost(liquidity: u64, quantities: &[u64]) -> f64 {
+    let b = liquidity as f64;
+    let max = quantities.iter().copied().max().unwrap_or(0) as f64;
+    let sum: f64 = quantities.iter().map(|&q| exp((q as f64 - max) / b)).sum();
+    max + b * ln(sum)
+}
+
+/// Instantaneous price of each outcome. These always sum to 1
+pub fn prices(liquidity: u64, quantities: &[u64]) -> Vec<f64> {
+    let b = liquidity as f64;
+    let max = quantities.iter().copied().max().unwrap_or(0) as f64;
+    let weights: Vec<f64> = quantities.iter().map(|&q| exp((q as f64 - max) / b)).collect();
+    let sum: f64 = weights.iter().sum();
+    weights.iter().map(|w| w / sum).collect()
+}
+
+/// Quote tokens the creator has to deposit to cover the worst case loss of the market maker
+pub fn max_loss(liquidity: u64, num_outcomes: usize) -> u64 {
+    ceil(liquidity as f64 * ln(num_outcomes as f64))
+}
+
+/// Quote tokens the user pays to buy `quantity` of `outcome`, rounded up
+pub fn buy_cost(liquidity: u64, quantities: &[u64], outcome: usize, quantity: u64) -> Option<u64> {
+    let mut after = quantities.to_vec();
+    after[outcome] = after[outcome].checked_add(quantity)?;
+    Some(ceil(cost(liquidity, &after) - cost(liquidity, quantities)))
+}
+
+/// Quote tokens the user receives for selling `quantity` of `outcome`, rounded down
+pub fn sell_proceeds(liquidity: u64, quantities: &[u64], outcome: usize, quantity: u64) -> Option<u64> {
+    let mut after = quantities.to_vec();
+    after[outcome] = after[outcome].checked_sub(quantity)?;
+    Some(floor(cost(liquidity, quantities) - cost(liquidity, &after)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exp_ln() {
+        for &x in &[-700.0, -20.5, -1.0, -0.3, 0.0, 0.7, 3.0, 41.25] {
+            assert!((exp(x) - x.exp()).abs() <= 1e-13 * x.exp());
+        }
+        for &x in &[1.0, 1.5, 2.0, 3.0, 7.99, 8.0, 1e9] {
+            assert!((ln(x) - x.ln()).abs() <= 1e-13 * x.ln().abs().max(1.0));
+        }
+    }
+
+    #[test]
+    fn test_cost_bounds() {
+        let b = 1_000_000;
+        assert_eq!(max_loss(b, 2), 693_148);
+        assert!((cost(b, &[0, 0]) - 693_147.18).abs() < 0.01);
+
+        // the cost function always covers the largest payout
+        let quantities = [5_000_000, 200_000, 0];
+        assert!(cost(b, &quantities) >= 5_000_000.0);
+
+        let p = prices(b, &quantities);
+        assert!((p.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!(p[0] > p[1] && p[1] > p[2]);
+    }
+
+    #[test]
+    fn test_round_trip_is_not_profitable() {
+        let b = 1_000_000;
+        let mut quantities = [0u64; 3];
+        let paid = buy_cost(b, &quantities, 1, 750_000).unwrap();
+        quantities[1] += 750_000;
+        let received = sell_proceeds(b, &quantities, 1, 750_000).unwrap();
+        assert!(received <= paid);
+        assert!(sell_proceeds(b, &quantities, 0, 1).is_none());
+        assert!(buy_cost(b, &quantities, 1, u64::MAX).is_none());
+    }
+}
@@ -0,0 +1,78 @@
+//! Checked arithmetic on token amounts, counters and timestamps. Anything that would wrap or panic
+//! fails the instruction with `MathOverflow` instead.
+
+use std::convert::TryFrom;
+
+use solana_program::clock::Clock;
+
+use crate::error::{OmegaErrorCode, OmegaResult};
+
+pub fn add(a: u64, b: u64) -> OmegaResult<u64> {
+    Ok(a.checked_add(b).ok_or(OmegaErrorCode::MathOverflow)?)
+}
+
+pub fn sub(a: u64, b: u64) -> OmegaResult<u64> {
+    Ok(a.checked_sub(b).ok_or(OmegaErrorCode::MathOverflow)?)
+}
+
+pub fn mul(a: u64, b: u64) -> OmegaResult<u64> {
+    Ok(a.checked_mul(b).ok_or(OmegaErrorCode::MathOverflow)?)
+}
+
+/// Rounded down. Dividing by zero is an error too
+pub fn div(a: u64, b: u64) -> OmegaResult<u64> {
+    Ok(a.checked_div(b).ok_or(OmegaErrorCode::MathOverflow)?)
+}
+
+/// a * b / c rounded down, with the product in 128 bits so that only the result has to fit in a u64
+pub fn mul_div(a: u64, b: u64, c: u64) -> OmegaResult<u64> {
+    let result = (a as u128 * b as u128).checked_div(c as u128).ok_or(OmegaErrorCode::MathOverflow)?;
+    Ok(u64::try_from(result).map_err(|_| OmegaErrorCode::MathOverflow)?)
+}
+
+pub fn sum(amounts: &[u64]) -> OmegaResult<u64> {
+    amounts.iter().try_fold(0, |total, &amount| add(total, amount))
+}
+
+/// Seconds since 1970. A timestamp before 1970 is an error rather than a huge u64
+pub fn timestamp(unix_timestamp: i64) -> OmegaResult<u64> {
+    Ok(u64::try_from(unix_timestamp).map_err(|_| OmegaErrorCode::MathOverflow)?)
+}
+
+pub fn unix_time(clock: &Clock) -> OmegaResult<u64> {
+    timestamp(clock.unix_timestamp)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::OmegaError;
+
+    use super::*;
+
+    fn overflow<T>(result: OmegaResult<T>) -> bool {
+        matches!(result, Err(OmegaError::ErrorCode(OmegaErrorCode::MathOverflow)))
+    }
+
+    #[test]
+    fn test_u64_boundaries() {
+        assert_eq!(add(u64::MAX - 1, 1).unwrap(), u64::MAX);
+        assert!(overflow(add(u64::MAX, 1)));
+        assert_eq!(sub(1, 1).unwrap(), 0);
+        assert!(overflow(sub(0, 1)));
+        assert_eq!(mul(u64::MAX, 1).unwrap(), u64::MAX);
+        assert!(overflow(mul(u64::MAX / 2 + 1, 2)));
+        assert_eq!(div(u64::MAX, 3).unwrap(), u64::MAX / 3);
+        assert!(overflow(div(1, 0)));
+        assert_eq!(sum(&[u64::MAX - 2, 1, 1]).unwrap(), u64::MAX);
+        assert!(overflow(sum(&[u64::MAX, 1])));
+
+        // the product may exceed a u64 as long as the result does not
+        assert_eq!(mul_div(u64::MAX, u64::MAX, u64::MAX).unwrap(), u64::MAX);
+        assert_eq!(mul_div(u64::MAX, 9_999, 10_000).unwrap(), ((u64::MAX as u128) * 9_999 / 10_000) as u64);
+        assert!(overflow(mul_div(u64::MAX, 2, 1)));
+        assert!(overflow(mul_div(1, 1, 0)));
+
+        assert_eq!(unix_time(&Clock { unix_timestamp: i64::MAX, ..Clock::default() }).unwrap(), i64::MAX as u64);
+        assert!(overflow(unix_time(&Clock { unix_timestamp: -1, ..Clock::default() })));
+    }
+}
@@ -0,0 +1,117 @@
+//! Price accounts that contracts can be resolved from.
+//!
+//! Each supported account layout implements `PriceSource` and gets a `PriceSourceKind`, which is
+//! what contracts store to know how to read their price account. Contracts also store the program
+//! that owns the price account, since only that program can write it.
+
+use std::mem::size_of;
+
+use bytemuck::{from_bytes, Pod, Zeroable};
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+
+use crate::error::{OmegaErrorCode, OmegaResult, SourceFileId};
+use crate::math;
+
+declare_check_assert_macros!(SourceFileId::Price);
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Price {
+    pub price: i64,
+    pub timestamp: u64,  // publish time in seconds since 1970
+}
+
+pub trait PriceSource {
+    fn read_price(data: &[u8]) -> OmegaResult<Price>;
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[repr(u64)]
+pub enum PriceSourceKind {
+    Simple = 0,
+    Pyth = 1,
+}
+
+impl PriceSourceKind {
+    pub fn read_price(self, data: &[u8]) -> OmegaResult<Price> {
+        match self {
+            PriceSourceKind::Simple => SimplePrice::read_price(data),
+            PriceSourceKind::Pyth => PythPrice::read_price(data),
+        }
+    }
+}
+
+/// Bare price and publish time at the start of the account
+/// Used by the tests and by any publisher that writes this layout
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct SimplePrice {
+    pub price: i64,
+    pub timestamp: u64,
+}
+
+unsafe impl Zeroable for SimplePrice {}
+unsafe impl Pod for SimplePrice {}
+
+impl PriceSource for SimplePrice {
+    fn read_price(data: &[u8]) -> OmegaResult<Price> {
+        check_assert!(data.len() >= size_of::<SimplePrice>())?;
+        let simple_price: &SimplePrice = from_bytes(&data[..size_of::<SimplePrice>()]);
+        Ok(Price { price: simple_price.price, timestamp: simple_price.timestamp })
+    }
+}
+
+pub const PYTH_MAGIC: u32 = 0xa1b2_c3d4;
+pub const PYTH_VERSION: u32 = 2;
+pub const PYTH_PRICE_ACCOUNT: u32 = 3;
+pub const PYTH_STATUS_TRADING: u32 = 1;
+
+/// Price account of the Pyth network, version 2. Prices are in units of 10^expo
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct PythPrice {
+    pub magic: u32,
+    pub ver: u32,
+    pub atype: u32,  // PYTH_PRICE_ACCOUNT
+    pub size: u32,
+    pub ptype: u32,
+    pub expo: i32,
+    pub num: u32,
+    pub num_qt: u32,
+    pub last_slot: u64,
+    pub valid_slot: u64,
+    pub ema_price: [i64; 3],
+    pub ema_conf: [i64; 3],
+    pub timestamp: i64,  // publish time of the aggregate price
+    pub min_pub: u8,
+    pub drv2: u8,
+    pub drv3: u16,
+    pub drv4: u32,
+    pub prod: [u8; 32],
+    pub next: [u8; 32],
+    pub prev_slot: u64,
+    pub prev_price: i64,
+    pub prev_conf: u64,
+    pub prev_timestamp: i64,
+    pub agg_price: i64,
+    pub agg_conf: u64,
+    pub agg_status: u32,  // only PYTH_STATUS_TRADING prices are valid
+    pub agg_corp_act: u32,
+    pub agg_pub_slot: u64,
+}
+
+unsafe impl Zeroable for PythPrice {}
+unsafe impl Pod for PythPrice {}
+
+impl PriceSource for PythPrice {
+    fn read_price(data: &[u8]) -> OmegaResult<Price> {
+        check_assert!(data.len() >= size_of::<PythPrice>())?;
+        let pyth_price: &PythPrice = from_bytes(&data[..size_of::<PythPrice>()]);
+        check_assert!(pyth_price.magic == PYTH_MAGIC)?;
+        check_assert!(pyth_price.ver == PYTH_VERSION)?;
+        check_assert!(pyth_price.atype == PYTH_PRICE_ACCOUNT)?;
+        if pyth_price.agg_status != PYTH_STATUS_TRADING {
+            return Err(OmegaErrorCode::StalePrice.into());
+        }
+        Ok(Price { price: pyth_price.agg_price, timestamp: math::timestamp(pyth_price.timestamp)? })
+    }
+}
@@ -1,7 +1,10 @@
+use std::cmp::{min, Ordering};
+use std::convert::TryFrom;
 use std::mem::size_of;
 
 use arrayref::{array_ref, array_refs};
-use bytemuck::bytes_of;
+use bytemuck::{bytes_of, Pod, Zeroable};
+use enumflags2::BitFlags;
 use solana_program::account_info::AccountInfo;
 use solana_program::entrypoint::ProgramResult;
 use solana_program::msg;
@@ -13,9 +16,21 @@ use solana_program::sysvar::rent::Rent;
 use solana_program::sysvar::Sysvar;
 use spl_token::state::{Account, Mint};
 
+use crate::allow_list::{split_proofs, verify_proof};
+use crate::attestation::{attestation_message, ed25519_program, parse_ed25519_instruction};
 use crate::error::{OmegaError, OmegaErrorCode, OmegaResult, SourceFileId};
-use crate::instruction::OmegaInstruction;
-use crate::state::{AccountFlag, DETAILS_BUFFER_LEN, Loadable, MAX_OUTCOMES, OmegaContract};
+use crate::instruction::{commitment_hash, find_issuance_record_address, find_oracle_reputation_address,
+                         find_program_config_address, find_vote_record_address, OmegaInstruction};
+use crate::lmsr;
+use crate::math::{self, unix_time};
+use crate::price::PriceSourceKind;
+use crate::state::{AccountFlag, Activity, BondStatus, Comparison, ConfigSettings, ContractMode, ContractStatus,
+                   CREATOR_DISPUTE_WINDOW, CreatorBond, DETAILS_BUFFER_LEN, EVIDENCE_URI_LEN, ISSUANCE_RECORD_SEED,
+                   IssuanceRecord, LATE_RESOLUTION_WINDOW, Loadable, MarketSeries, MAX_BPS, MAX_CREATORS, MAX_OUTCOMES, MAX_QUOTE_MINTS,
+                   OmegaContract, OracleBond, OracleEvent, OracleFee, OracleLiveness, OracleReputation,
+                   ORACLE_REPUTATION_SEED, Order, OrderBook, PRICE_SCALE, PriceResolution, PROGRAM_CONFIG_SEED, ProgramConfig,
+                   Registry, RegistryEntry, Side, TieBreak, VOTE_RECORD_SEED, VoteRecord,
+                   VoteResolution};
 
 pub struct Processor {}
 
@@ -31,8 +46,9 @@ impl Processor {
         signer_nonce: u64,
         details: &[u8]
     ) -> OmegaResult<()> {
-        const NUM_FIXED: usize = 6;
-        check_assert!(accounts.len() >= NUM_FIXED + 2 && accounts.len() <= NUM_FIXED + MAX_OUTCOMES)?;
+        const NUM_FIXED: usize = 10;
+        const NUM_BOND: usize = 3;
+        check_assert!(accounts.len() >= NUM_FIXED + 2 && accounts.len() <= NUM_FIXED + MAX_OUTCOMES + NUM_BOND)?;
 
         let (fixed_accs, outcome_accs) = array_refs![accounts, NUM_FIXED; ..;];
         let [
@@ -41,49 +57,92 @@ impl Processor {
             quote_mint_acc,
             vault_acc,
             signer_acc,
-            rent_acc
+            rent_acc,
+            program_config_acc,
+            creator_acc,
+            registry_acc,
+            clock_acc
         ] = fixed_accs;
 
         let rent = Rent::from_account_info(rent_acc)?;
 
         check_assert!(omega_contract_acc.owner == program_id)?;
         check_assert!(rent.is_exempt(omega_contract_acc.lamports(), size_of::<OmegaContract>()))?;
-        check_assert!(details.len() <= DETAILS_BUFFER_LEN)?;
+        let settings = check_program_config(program_id, program_config_acc, creator_acc, quote_mint_acc.key)?;
+        let creator_bond = settings.creator_bond;
 
-        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
+        // the accounts paying the creator bond come last
+        let (outcome_accs, bond_accs) = if creator_bond > 0 {
+            check_assert!(outcome_accs.len() >= 2 + NUM_BOND)?;
+            outcome_accs.split_at(outcome_accs.len() - NUM_BOND)
+        } else {
+            (outcome_accs, &outcome_accs[outcome_accs.len()..])
+        };
+        check_assert!(outcome_accs.len() <= MAX_OUTCOMES)?;
 
-        check_assert!(omega_contract.account_flags == 0)?;
-        check_assert!(auto_exp_time >= exp_time)?;
-        let signer_key = gen_signer_key(signer_nonce, omega_contract_acc.key, program_id)?;
-        check_assert!(signer_key == *signer_acc.key)?;
-        omega_contract.account_flags = (AccountFlag::Initialized | AccountFlag::OmegaContract).bits();
-        omega_contract.oracle = *oracle_acc.key;
-        omega_contract.quote_mint = *quote_mint_acc.key;
-        omega_contract.exp_time = exp_time;
-        omega_contract.auto_exp_time = auto_exp_time;
-        omega_contract.vault = *vault_acc.key;
-        omega_contract.signer_key = *signer_acc.key;
-        omega_contract.signer_nonce = signer_nonce;
-        omega_contract.winner = Pubkey::default();
-        omega_contract.num_outcomes = outcome_accs.len();
+        init_contract(
+            program_id,
+            omega_contract_acc,
+            oracle_acc.key,
+            quote_mint_acc,
+            vault_acc,
+            signer_acc,
+            outcome_accs,
+            exp_time,
+            auto_exp_time,
+            signer_nonce,
+            details
+        )?;
+        OmegaContract::load_mut(omega_contract_acc)?.oracle_fee.fee_bps = settings.fee_bps;
 
-        let details_buf = &mut omega_contract.details[..details.len()];
-        details_buf.copy_from_slice(details);
+        // only the creator can list contracts in its registry
+        let clock = solana_program::clock::Clock::from_account_info(clock_acc)?;
+        let mut registry = Registry::load_mut(registry_acc)?;
+        check_assert!(registry.account_flags == (AccountFlag::Initialized | AccountFlag::Registry).bits())?;
+        check_assert!(registry_acc.owner == program_id)?;
+        check_assert!(registry.creator == *creator_acc.key)?;
+        check_assert!(creator_acc.is_signer)?;
 
-        let quote_mint = Mint::unpack(&quote_mint_acc.try_borrow_data()?)?;
-        let vault = Account::unpack(&vault_acc.try_borrow_data()?)?;
-        check_assert!(vault.owner == signer_key)?;
-        check_assert!(&vault.mint == quote_mint_acc.key)?;
+        let entry = RegistryEntry {
+            contract: *omega_contract_acc.key,
+            oracle: *oracle_acc.key,
+            category: 0,
+            created_at: unix_time(&clock)?,
+            status: ContractStatus::Open.into()
+        };
+        if !registry.push(entry) {
+            return Err(OmegaErrorCode::RegistryFull.into());
+        }
 
-        for (i, outcome_acc) in outcome_accs.iter().enumerate() {
-            let outcome = Mint::unpack(&outcome_acc.try_borrow_data()?)?;
-            let authority = outcome.mint_authority.ok_or(OmegaErrorCode::InvalidOutcomeMintAuthority)?;
-            check_assert!(*outcome_acc.key != Pubkey::default())?;
-            check_assert!(outcome.is_initialized)?;
-            check_assert!(authority == signer_key)?;
-            check_assert!(outcome.supply == 0)?;
-            check_assert!(outcome.decimals == quote_mint.decimals)?;
-            omega_contract.outcomes[i] = *outcome_acc.key;
+        if let [creator_quote_acc, escrow_acc, spl_token_program_acc] = bond_accs {
+            let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
+            let escrow = Account::unpack(&escrow_acc.try_borrow_data()?)?;
+            check_assert!(escrow.owner == *signer_acc.key)?;
+            check_assert!(escrow.mint == *quote_mint_acc.key)?;
+            check_assert!(*escrow_acc.key != omega_contract.vault)?;
+
+            let deposit_instruction = spl_token::instruction::transfer(
+                spl_token_program_acc.key,
+                creator_quote_acc.key,
+                escrow_acc.key,
+                creator_acc.key,
+                &[],
+                creator_bond
+            )?;
+            let deposit_accs = [
+                creator_quote_acc.clone(),
+                escrow_acc.clone(),
+                creator_acc.clone(),
+                spl_token_program_acc.clone()
+            ];
+            invoke(&deposit_instruction, &deposit_accs)?;
+
+            omega_contract.creator_bond = CreatorBond {
+                creator: *creator_acc.key,
+                escrow: *escrow_acc.key,
+                amount: creator_bond,
+                status: BondStatus::Posted as u64
+            };
         }
 
         Ok(())
@@ -92,10 +151,11 @@ impl Processor {
     fn issue_set(
         program_id: &Pubkey,
         accounts: &[AccountInfo],
-        quantity: u64
+        quantity: u64,
+        allow_list_proof: &[u8]
     ) -> OmegaResult<()> {
         const NUM_FIXED: usize = 6;
-        let (fixed_accs, outcome_accs) = array_refs![accounts, NUM_FIXED; ..;];
+        let (fixed_accs, tail_accs) = array_refs![accounts, NUM_FIXED; ..;];
 
         let [
             omega_contract_acc,
@@ -108,12 +168,27 @@ impl Processor {
 
 
         // Transfer quote tokens from the user's token wallet
-        let omega_contract = OmegaContract::load(omega_contract_acc)?;
+        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
         check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
         check_assert!(omega_contract_acc.owner == program_id)?;
         check_assert!(*vault_acc.key == omega_contract.vault)?;
-        check_assert!(outcome_accs.len() == 2 * omega_contract.num_outcomes)?;
+        check_assert!(tail_accs.len() >= 2 * omega_contract.num_outcomes)?;
+        let (outcome_accs, tail_accs) = tail_accs.split_at(2 * omega_contract.num_outcomes);
+        let (clock_acc, issuance_accs) = split_optional_clock(tail_accs);
         check_assert!(user_acc.is_signer)?;
+        if omega_contract.mode != ContractMode::CompleteSet as u64 {
+            return Err(OmegaErrorCode::InvalidContractMode.into());
+        }
+        check_not_slashed(&omega_contract)?;
+        check_allow_list(&omega_contract, user_acc.key, allow_list_proof)?;
+
+        omega_contract.add_sets(quantity)?;
+        Self::record_optional_issuance(
+            program_id, &mut omega_contract, omega_contract_acc, user_acc, issuance_accs, quantity
+        )?;
+
+        let curr_time = optional_clock_time(&omega_contract, clock_acc)?;
+        omega_contract.stats.record(Activity::SetsIssued, quantity, curr_time)?;
 
         let deposit_instruction = spl_token::instruction::transfer(
             spl_token_program_acc.key,
@@ -149,6 +224,85 @@ impl Processor {
             invoke_signed(&mint_instruction, &mint_accs, &[&signer_seeds])?;
         }
 
+        let supplies = read_outcome_supplies(&omega_contract, outcome_accs.iter().step_by(2))?;
+        check_vault_solvency(&omega_contract, vault_acc, &supplies)?;
+
+        Ok(())
+    }
+
+    /// Add `quantity` to the sets the user issued, creating their IssuanceRecord on the first issuance.
+    /// Returns whether the record was created
+    #[allow(clippy::too_many_arguments)]
+    fn record_issuance<'a>(
+        program_id: &Pubkey,
+        omega_contract_acc: &AccountInfo<'a>,
+        user_acc: &AccountInfo<'a>,
+        issuance_record_acc: &AccountInfo<'a>,
+        system_program_acc: &AccountInfo<'a>,
+        rent_acc: &AccountInfo<'a>,
+        quantity: u64,
+        wallet_cap: u64
+    ) -> OmegaResult<bool> {
+        let (issuance_record_key, nonce) = find_issuance_record_address(program_id, omega_contract_acc.key, user_acc.key);
+        check_assert!(issuance_record_key == *issuance_record_acc.key)?;
+        let created = issuance_record_acc.data_is_empty();
+        if created {
+            check_assert!(*system_program_acc.key == solana_program::system_program::ID)?;
+            let nonce_bytes = nonce.to_le_bytes();
+            let seeds = [ISSUANCE_RECORD_SEED, omega_contract_acc.key.as_ref(), user_acc.key.as_ref(), &nonce_bytes];
+            let rent = Rent::from_account_info(rent_acc)?;
+            create_pda_account::<IssuanceRecord>(
+                program_id, user_acc, issuance_record_acc, system_program_acc, &rent, &seeds
+            )?;
+
+            let mut issuance_record = IssuanceRecord::load_mut(issuance_record_acc)?;
+            issuance_record.account_flags = (AccountFlag::Initialized | AccountFlag::IssuanceRecord).bits();
+            issuance_record.omega_contract = *omega_contract_acc.key;
+            issuance_record.user = *user_acc.key;
+            issuance_record.nonce = nonce;
+        }
+
+        let mut issuance_record = IssuanceRecord::load_mut(issuance_record_acc)?;
+        check_assert!(issuance_record.account_flags == (AccountFlag::Initialized | AccountFlag::IssuanceRecord).bits())?;
+        check_assert!(issuance_record_acc.owner == program_id)?;
+        let issued = math::add(issuance_record.issued, quantity)?;
+        if wallet_cap != 0 && issued > wallet_cap {
+            return Err(OmegaErrorCode::IssuanceCapExceeded.into());
+        }
+        issuance_record.issued = issued;
+
+        Ok(created)
+    }
+
+    /// Count `quantity` sets on the user's IssuanceRecord when the instruction passes the optional
+    /// `[issuance_record, system_program, rent]` accounts. They are required once the contract has a wallet cap
+    fn record_optional_issuance<'a>(
+        program_id: &Pubkey,
+        omega_contract: &mut OmegaContract,
+        omega_contract_acc: &AccountInfo<'a>,
+        user_acc: &AccountInfo<'a>,
+        issuance_accs: &[AccountInfo<'a>],
+        quantity: u64
+    ) -> OmegaResult<()> {
+        if issuance_accs.is_empty() {
+            if omega_contract.wallet_cap != 0 {
+                return Err(OmegaErrorCode::IssuanceCapExceeded.into());
+            }
+            return Ok(());
+        }
+        check_assert!(issuance_accs.len() == 3)?;
+        let [issuance_record_acc, system_program_acc, rent_acc] = array_ref![issuance_accs, 0, 3];
+        let new_issuer = Self::record_issuance(
+            program_id,
+            omega_contract_acc,
+            user_acc,
+            issuance_record_acc,
+            system_program_acc,
+            rent_acc,
+            quantity,
+            omega_contract.wallet_cap
+        )?;
+        omega_contract.stats.unique_issuers = math::add(omega_contract.stats.unique_issuers, new_issuer as u64)?;
         Ok(())
     }
 
@@ -158,7 +312,7 @@ impl Processor {
         quantity: u64
     ) -> OmegaResult<()> {
         const NUM_FIXED: usize = 6;
-        let (fixed_accs, outcome_accs) = array_refs![accounts, NUM_FIXED; ..;];
+        let (fixed_accs, tail_accs) = array_refs![accounts, NUM_FIXED; ..;];
 
         let [
             omega_contract_acc,
@@ -170,12 +324,21 @@ impl Processor {
         ] = fixed_accs;
 
         // Transfer outcome tokens for each outcome
-        let omega_contract = OmegaContract::load(omega_contract_acc)?;
+        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
         check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
         check_assert!(omega_contract_acc.owner == program_id)?;
         check_assert!(*vault_acc.key == omega_contract.vault)?;
-        check_assert!(outcome_accs.len() == 2 * omega_contract.num_outcomes)?;
+        check_assert!(tail_accs.len() >= 2 * omega_contract.num_outcomes)?;
+        let (outcome_accs, tail_accs) = tail_accs.split_at(2 * omega_contract.num_outcomes);
+        let (clock_acc, tail_accs) = split_optional_clock(tail_accs);
+        check_assert!(tail_accs.is_empty())?;
         check_assert!(user_acc.is_signer)?;
+        if omega_contract.mode != ContractMode::CompleteSet as u64 {
+            return Err(OmegaErrorCode::InvalidContractMode.into());
+        }
+        omega_contract.sub_sets(quantity)?;
+        let curr_time = optional_clock_time(&omega_contract, clock_acc)?;
+        omega_contract.stats.record(Activity::SetsRedeemed, quantity, curr_time)?;
 
         for i in 0..omega_contract.num_outcomes {
             let outcome_mint_acc = &outcome_accs[2 * i];
@@ -217,6 +380,9 @@ impl Processor {
         let signer_seeds = gen_signer_seeds(&omega_contract.signer_nonce, omega_contract_acc.key);
         invoke_signed(&withdraw_instruction, &withdraw_accs, &[&signer_seeds])?;
 
+        let supplies = read_outcome_supplies(&omega_contract, outcome_accs.iter().step_by(2))?;
+        check_vault_solvency(&omega_contract, vault_acc, &supplies)?;
+
         Ok(())
     }
 
@@ -233,25 +399,48 @@ impl Processor {
             winner_user_acc,
             clock_acc
         ] = accounts;
-        let omega_contract = OmegaContract::load(omega_contract_acc)?;
+        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
         check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
         check_assert!(omega_contract_acc.owner == program_id)?;
         check_assert!(*vault_acc.key == omega_contract.vault)?;
         check_assert!(user_acc.is_signer)?;
 
         let clock = solana_program::clock::Clock::from_account_info(clock_acc)?;
-        let curr_time = clock.unix_timestamp as u64;
+        let curr_time = unix_time(&clock)?;
 
-        // If it has auto expired with no winner, then let user redeem at 1 / num_outcomes rate
-        let winnings = if omega_contract.winner == Pubkey::default() {
-            check_assert!(curr_time >= omega_contract.auto_exp_time)?;
-            // allow redemptions at 1 / num_outcomes
-            quantity / (omega_contract.num_outcomes as u64)
-            // no need to check winner mint because token program will fail if it's not a mint controlled by omega
-        } else {
-            check_assert!(*winner_mint_acc.key == omega_contract.winner)?;
-            quantity
+        check_bond_settled(&omega_contract, curr_time)?;
+        let bond = omega_contract.bond;
+
+        let winnings = if omega_contract.mode == ContractMode::Parimutuel as u64 {
+            let num_outcomes = omega_contract.num_outcomes;
+            let outcomes = &omega_contract.outcomes[..num_outcomes];
+            let outcome = outcomes.iter().position(|pk| pk == winner_mint_acc.key)
+                .ok_or(OmegaErrorCode::InvalidOutcome)?;
+            let pools = &omega_contract.parimutuel.pools[..num_outcomes];
+
+            // Tickets are refunded if the contract auto expired or nobody staked on the winner
+            match outcomes.iter().position(|pk| *pk == omega_contract.winner) {
+                None => {
+                    check_assert!(curr_time >= omega_contract.auto_exp_time)?;
+                    quantity
+                }
+                Some(winner) if pools[winner] == 0 => quantity,
+                Some(winner) => {
+                    check_assert!(outcome == winner)?;
+                    parimutuel_payout(quantity, pools, winner, omega_contract.parimutuel.fee_bps)?
+                }
+            }
+        }
+        else {
+            winner_payout(&omega_contract, winner_mint_acc.key, quantity, curr_time)?
         };
+        let winnings = math::add(winnings, bond.share(quantity)?)?;
+        let outcome = omega_contract.outcome_index(winner_mint_acc.key)?;
+        omega_contract.sub_supply(outcome, quantity)?;
+        if omega_contract.winner == Pubkey::default() {
+            omega_contract.auto_exp_redemptions = math::add(omega_contract.auto_exp_redemptions, 1)?;
+        }
+        omega_contract.stats.record(Activity::WinnerRedeemed, quantity, curr_time)?;
 
         // Burn the tokens
         let burn_instruction = spl_token::instruction::burn(
@@ -289,156 +478,5966 @@ impl Processor {
         let signer_seeds = gen_signer_seeds(&omega_contract.signer_nonce, omega_contract_acc.key);
         invoke_signed(&withdraw_instruction, &withdraw_accs, &[&signer_seeds])?;
 
+        if let Some(supplies) = resolved_supplies(&omega_contract, winner_mint_acc)? {
+            check_vault_solvency(&omega_contract, vault_acc, &supplies)?;
+        }
+
         Ok(())
     }
 
-    fn resolve(program_id: &Pubkey, accounts: &[AccountInfo]) -> OmegaResult<()> {
-        let accounts = array_ref![accounts, 0, 4];
+    fn resolve(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        evidence_hash: [u8; 32],
+        evidence_uri: &[u8]
+    ) -> OmegaResult<()> {
+        const NUM_FIXED: usize = 5;
+        let (fixed_accs, tail_accs) = array_refs![accounts, NUM_FIXED; ..;];
         let [
             omega_contract_acc,
             oracle_acc,  // signer
             winner_acc,
-            clock_acc
-        ] = accounts;
+            clock_acc,
+            vault_acc
+        ] = fixed_accs;
 
         let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
         check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
         check_assert!(omega_contract_acc.owner == program_id)?;
-        check_assert!(omega_contract.oracle == *oracle_acc.key)?;
-        check_assert!(oracle_acc.is_signer)?;
         let clock = solana_program::clock::Clock::from_account_info(clock_acc)?;
-        let curr_time = clock.unix_timestamp as u64;
+        let curr_time = unix_time(&clock)?;
+        check_assert!(omega_contract.resolving_oracle(curr_time) == *oracle_acc.key)?;
+        check_assert!(oracle_acc.is_signer)?;
+        check_assert!(!omega_contract.vote.is_enabled())?;
+        check_assert!(omega_contract.price_resolution.price_acc == Pubkey::default())?;
 
         check_assert!(omega_contract.exp_time <= curr_time)?;
         check_assert!(curr_time < omega_contract.auto_exp_time)?;
         check_assert!(omega_contract.winner == Pubkey::default())?;
+        check_assert!(omega_contract.commitment == [0u8; 32])?;
+
+        check_assert!(evidence_uri.len() <= EVIDENCE_URI_LEN)?;
 
         let winner = *winner_acc.key;
-        for i in 0..omega_contract.num_outcomes {
-            if winner == omega_contract.outcomes[i] {
-                omega_contract.winner = winner;
-                return Ok(());
-            }
+        if !omega_contract.outcomes[..omega_contract.num_outcomes].contains(&winner) {
+            return Err(OmegaError::ErrorCode(OmegaErrorCode::InvalidWinner));
         }
+        omega_contract.set_oracle_winner(winner, *oracle_acc.key, clock.slot, curr_time);
+        omega_contract.evidence_hash = evidence_hash;
+        omega_contract.evidence_uri_len = evidence_uri.len() as u64;
+        omega_contract.evidence_uri[..evidence_uri.len()].copy_from_slice(evidence_uri);
 
-        Err(OmegaError::ErrorCode(OmegaErrorCode::InvalidWinner))
-    }
+        // the outcome mints come first, then the fee accounts, the oracle reputation last
+        check_assert!(*vault_acc.key == omega_contract.vault)?;
+        let fee_len = if omega_contract.oracle_fee.reserve == 0 { 0 } else { 3 };
+        let num_outcomes = omega_contract.num_outcomes;
+        check_assert!(tail_accs.len() == num_outcomes + fee_len || tail_accs.len() == num_outcomes + fee_len + 1)?;
+        let (outcome_mint_accs, tail_accs) = tail_accs.split_at(num_outcomes);
+        let (fee_accs, reputation_accs) = tail_accs.split_at(fee_len);
+        if let Some(oracle_reputation_acc) = reputation_accs.first() {
+            record_reputation(program_id, &mut omega_contract, oracle_reputation_acc)?;
+        }
 
-    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
-        let instruction = OmegaInstruction::unpack(data).ok_or(ProgramError::InvalidInstructionData)?;
-        match instruction {
-            OmegaInstruction::InitOmegaContract {
-                exp_time, auto_exp_time, signer_nonce,
-            } => {
-                msg!("InitOmegaContract");
-                let details_buffer = &data[16..];
-                Self::init_omega_contract(program_id, accounts, exp_time, auto_exp_time, signer_nonce, details_buffer)?;
-            },
-            OmegaInstruction::IssueSet {
-                quantity
-            } => {
-                msg!("IssueSet");
-                Self::issue_set(program_id, accounts, quantity)?;
-            },
-            OmegaInstruction::RedeemSet {
-                quantity
-            } => {
-                msg!("RedeemSet");
-                Self::redeem_set(program_id, accounts, quantity)?;
-            },
-            OmegaInstruction::RedeemWinner {
-                quantity
-            } => {
-                msg!("RedeemWinner");
-                Self::redeem_winner(program_id, accounts, quantity)?;
-            },
-            OmegaInstruction::Resolve => {
-                msg!("Resolve");
-                Self::resolve(program_id, accounts)?;
-            }
+        if let [oracle_quote_acc, spl_token_program_acc, omega_signer_acc] = fee_accs {
+            Self::pay_oracle_reward(
+                &mut omega_contract, omega_contract_acc, oracle_acc, vault_acc, oracle_quote_acc,
+                spl_token_program_acc, omega_signer_acc, curr_time
+            )?;
         }
 
+        let supplies = read_outcome_supplies(&omega_contract, outcome_mint_accs.iter())?;
+        check_vault_solvency(&omega_contract, vault_acc, &supplies)?;
 
         Ok(())
     }
-}
-
 
-fn gen_signer_seeds<'a>(nonce: &'a u64, contract_pk: &'a Pubkey) -> [&'a [u8]; 2] {
-    [contract_pk.as_ref(), bytes_of(nonce)]
-}
+    /// Pay the oracle reward out of the reserve funded for it
+    #[allow(clippy::too_many_arguments)]
+    fn pay_oracle_reward<'a>(
+        omega_contract: &mut OmegaContract,
+        omega_contract_acc: &AccountInfo<'a>,
+        oracle_acc: &AccountInfo<'a>,
+        vault_acc: &AccountInfo<'a>,
+        oracle_quote_acc: &AccountInfo<'a>,
+        spl_token_program_acc: &AccountInfo<'a>,
+        omega_signer_acc: &AccountInfo<'a>,
+        curr_time: u64
+    ) -> OmegaResult<()> {
+        let oracle_quote = Account::unpack(&oracle_quote_acc.try_borrow_data()?)?;
+        check_assert!(oracle_quote.owner == *oracle_acc.key)?;
 
-fn gen_signer_key(
-    nonce: u64,
-    contract_pk: &Pubkey,
-    program_id: &Pubkey,
-) -> Result<Pubkey, ProgramError> {
-    let seeds = gen_signer_seeds(&nonce, contract_pk);
-    Ok(Pubkey::create_program_address(&seeds, program_id)?)
-}
+        let vault = Account::unpack(&vault_acc.try_borrow_data()?)?;
+        let open_interest = vault.amount.saturating_sub(omega_contract.oracle_fee.reserve);
+        let reward = omega_contract.oracle_fee.reward(omega_contract.exp_time, curr_time, open_interest)?;
+        msg!("oracle reward: {}", reward);
+        if reward == 0 {
+            return Ok(());
+        }
 
+        let reward_instruction = spl_token::instruction::transfer(
+            spl_token_program_acc.key,
+            vault_acc.key,
+            oracle_quote_acc.key,
+            omega_signer_acc.key,
+            &[],
+            reward
+        )?;
+        let reward_accs = [
+            vault_acc.clone(),
+            oracle_quote_acc.clone(),
+            omega_signer_acc.clone(),
+            spl_token_program_acc.clone()
+        ];
+        let signer_seeds = gen_signer_seeds(&omega_contract.signer_nonce, omega_contract_acc.key);
+        invoke_signed(&reward_instruction, &reward_accs, &[&signer_seeds])?;
+        omega_contract.oracle_fee.reserve = math::sub(omega_contract.oracle_fee.reserve, reward)?;
 
-#[cfg(test)]
-mod tests {
-    use std::mem::size_of;
+        Ok(())
+    }
 
-    use bytemuck::Pod;
-    use solana_program::instruction::Instruction;
-    use solana_program::program_error::PrintProgramError;
-    use solana_program::rent::Rent;
-    use solana_sdk::account::{Account, create_account, create_is_signer_account_infos};
+    fn init_bucket_group(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        num_breakpoints: u64,
+        breakpoints: &[i64; MAX_OUTCOMES - 1]
+    ) -> OmegaResult<()> {
+        let accounts = array_ref![accounts, 0, 2];
+        let [
+            omega_contract_acc,
+            oracle_acc
+        ] = accounts;
 
-    use crate::error::OmegaError;
-    use crate::instruction::*;
+        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+        check_assert!(omega_contract.oracle == *oracle_acc.key)?;
+        check_assert!(oracle_acc.is_signer)?;
+        check_assert!(omega_contract.winner == Pubkey::default())?;
+        check_assert!(omega_contract.num_breakpoints == 0)?;
 
-    use super::*;
+        let num_breakpoints = num_breakpoints as usize;
+        if num_breakpoints + 1 != omega_contract.num_outcomes
+            || breakpoints[..num_breakpoints].windows(2).any(|w| w[0] >= w[1]) {
+            return Err(OmegaErrorCode::InvalidBreakpoints.into());
+        }
 
-    fn do_process_instruction(
-        instruction: Instruction,
-        accounts: Vec<&mut Account>,
-    ) -> ProgramResult {
-        let mut meta = instruction
-            .accounts
-            .iter()
-            .zip(accounts)
-            .map(|(account_meta, account)| (&account_meta.pubkey, account_meta.is_signer, account))
-            .collect::<Vec<_>>();
+        omega_contract.num_breakpoints = num_breakpoints as u64;
+        omega_contract.breakpoints = *breakpoints;
 
-        let account_infos = create_is_signer_account_infos(&mut meta);
-        Processor::process(&instruction.program_id, &account_infos, &instruction.data)
+        Ok(())
     }
 
-    fn get_rent_exempt<T: Pod>(owner: &Pubkey) -> Account {
-        let rent = Rent::default();
-        Account::new(rent.minimum_balance(size_of::<T>()), size_of::<T>(), owner)
-    }
+    fn resolve_value(program_id: &Pubkey, accounts: &[AccountInfo], value: i64) -> OmegaResult<()> {
+        const NUM_FIXED: usize = 3;
+        let (fixed_accs, solvency_accs) = array_refs![accounts, NUM_FIXED; ..;];
+        let [
+            omega_contract_acc,
+            oracle_acc,  // signer
+            clock_acc
+        ] = fixed_accs;
 
-    #[test]
-    fn test_init_omega_contract() {
-        let program_id = Pubkey::new_unique();
-        let omega_contract_pk = Pubkey::new_unique();
-        let mut omega_contract_acc = get_rent_exempt::<OmegaContract>(&program_id);
+        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+        if omega_contract.num_breakpoints == 0 {
+            return Err(OmegaErrorCode::InvalidBreakpoints.into());
+        }
+        let clock = solana_program::clock::Clock::from_account_info(clock_acc)?;
+        let curr_time = unix_time(&clock)?;
+        check_assert!(omega_contract.resolving_oracle(curr_time) == *oracle_acc.key)?;
+        check_assert!(oracle_acc.is_signer)?;
+        check_assert!(!omega_contract.vote.is_enabled())?;
+        check_assert!(omega_contract.price_resolution.price_acc == Pubkey::default())?;
 
-        let oracle_pk = Pubkey::new_unique();
-        let mut oracle_acc = Account::default();
+        check_assert!(omega_contract.exp_time <= curr_time)?;
+        check_assert!(curr_time < omega_contract.auto_exp_time)?;
+        check_assert!(omega_contract.winner == Pubkey::default())?;
+        check_assert!(omega_contract.commitment == [0u8; 32])?;
 
-        let quote_mint_pk = Pubkey::new_unique();
-        let mut quote_mint_acc = Account::default();
+        let bucket = omega_contract.bucket(value);
+        let winner = omega_contract.outcomes[bucket];
+        omega_contract.set_oracle_winner(winner, *oracle_acc.key, clock.slot, curr_time);
+        omega_contract.resolved_value = value;
 
-        let vault_pk = Pubkey::new_unique();
-        let mut vault_acc = Account::default();
+        check_solvency_accounts(&omega_contract, solvency_accs)
+    }
 
-        let signer_pk = Pubkey::new_unique();  // doesn't work
+    fn init_lmsr(program_id: &Pubkey, accounts: &[AccountInfo], liquidity: u64) -> OmegaResult<()> {
+        let accounts = array_ref![accounts, 0, 5];
+        let [
+            omega_contract_acc,
+            creator_acc,
+            creator_quote_acc,
+            vault_acc,
+            spl_token_program_acc
+        ] = accounts;
 
-        let instruction = init_omega_contract(
-            &program_id, &omega_contract_pk, &oracle_pk, &quote_mint_pk, &vault_pk, &signer_pk, &[],
-            0, 0, 0, "DO NOT USE THIS CONTRACT"
-        ).unwrap();
+        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+        check_assert!(*vault_acc.key == omega_contract.vault)?;
+        check_assert!(creator_acc.is_signer)?;
+        check_assert!(omega_contract.winner == Pubkey::default())?;
+        check_assert!(omega_contract.lmsr.liquidity == 0)?;
+        check_assert!(liquidity > 0)?;
+        if omega_contract.mode != ContractMode::CompleteSet as u64 {
+            return Err(OmegaErrorCode::InvalidContractMode.into());
+        }
 
-        let accounts = vec![&mut omega_contract_acc, &mut oracle_acc, &mut quote_mint_acc, &mut vault_acc];
-        let result = do_process_instruction(instruction, accounts);
-        assert!(result == Ok(()));
+        let max_loss = lmsr::max_loss(liquidity, omega_contract.num_outcomes);
+        let deposit_instruction = spl_token::instruction::transfer(
+            spl_token_program_acc.key,
+            creator_quote_acc.key,
+            vault_acc.key,
+            creator_acc.key,
+            &[],
+            max_loss
+        )?;
+        let deposit_accs = [
+            creator_quote_acc.clone(),
+            vault_acc.clone(),
+            creator_acc.clone(),
+            spl_token_program_acc.clone()
+        ];
+        invoke(&deposit_instruction, &deposit_accs)?;
+
+        omega_contract.lmsr.creator = *creator_acc.key;
+        omega_contract.lmsr.liquidity = liquidity;
+        omega_contract.lmsr.max_loss = max_loss;
+        omega_contract.lmsr.quantities = [0; MAX_OUTCOMES];
+
+        Ok(())
     }
-}
 
+    fn lmsr_buy(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        outcome: u64,
+        quantity: u64,
+        max_cost: u64,
+        allow_list_proof: &[u8]
+    ) -> OmegaResult<()> {
+        const NUM_FIXED: usize = 9;
+        let (fixed_accs, tail_accs) = array_refs![accounts, NUM_FIXED; ..;];
+        let [
+            omega_contract_acc,
+            user_acc,
+            user_quote_acc,
+            vault_acc,
+            spl_token_program_acc,
+            omega_signer_acc,
+            outcome_mint_acc,
+            outcome_user_acc,
+            clock_acc
+        ] = fixed_accs;
+
+        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+        check_assert!(*vault_acc.key == omega_contract.vault)?;
+        check_assert!(tail_accs.len() >= omega_contract.num_outcomes)?;
+        let (outcome_mint_accs, issuance_accs) = tail_accs.split_at(omega_contract.num_outcomes);
+        check_assert!(user_acc.is_signer)?;
+        check_assert!(omega_contract.lmsr.liquidity != 0)?;
 
+        let clock = solana_program::clock::Clock::from_account_info(clock_acc)?;
+        let curr_time = unix_time(&clock)?;
+        check_assert!(curr_time < omega_contract.exp_time)?;
+        check_assert!(omega_contract.winner == Pubkey::default())?;
+        check_not_slashed(&omega_contract)?;
+        check_allow_list(&omega_contract, user_acc.key, allow_list_proof)?;
+
+        let outcome = outcome as usize;
+        if outcome >= omega_contract.num_outcomes || *outcome_mint_acc.key != omega_contract.outcomes[outcome] {
+            return Err(OmegaErrorCode::InvalidOutcome.into());
+        }
+
+        let num_outcomes = omega_contract.num_outcomes;
+        let quantities = &omega_contract.lmsr.quantities[..num_outcomes];
+        let cost = lmsr::buy_cost(omega_contract.lmsr.liquidity, quantities, outcome, quantity)
+            .ok_or(assertion_error!())?;
+        if cost > max_cost {
+            return Err(OmegaErrorCode::SlippageExceeded.into());
+        }
+        omega_contract.add_supply(outcome, quantity)?;
+        omega_contract.stats.record(Activity::OutcomesIssued, quantity, curr_time)?;
+        Self::record_optional_issuance(
+            program_id, &mut omega_contract, omega_contract_acc, user_acc, issuance_accs, quantity
+        )?;
+
+        let deposit_instruction = spl_token::instruction::transfer(
+            spl_token_program_acc.key,
+            user_quote_acc.key,
+            vault_acc.key,
+            user_acc.key,
+            &[],
+            cost
+        )?;
+        let deposit_accs = [user_quote_acc.clone(), vault_acc.clone(), user_acc.clone(), spl_token_program_acc.clone()];
+        invoke(&deposit_instruction, &deposit_accs)?;
+
+        let mint_instruction = spl_token::instruction::mint_to(
+            spl_token_program_acc.key,
+            outcome_mint_acc.key,
+            outcome_user_acc.key,
+            omega_signer_acc.key,
+            &[],
+            quantity,
+        )?;
+        let mint_accs = [
+            outcome_mint_acc.clone(),
+            outcome_user_acc.clone(),
+            omega_signer_acc.clone(),
+            spl_token_program_acc.clone()
+        ];
+        let signer_seeds = gen_signer_seeds(&omega_contract.signer_nonce, omega_contract_acc.key);
+        invoke_signed(&mint_instruction, &mint_accs, &[&signer_seeds])?;
+
+        omega_contract.lmsr.quantities[outcome] = math::add(omega_contract.lmsr.quantities[outcome], quantity)?;
+
+        let supplies = read_outcome_supplies(&omega_contract, outcome_mint_accs.iter())?;
+        check_vault_solvency(&omega_contract, vault_acc, &supplies)?;
+
+        Ok(())
+    }
+
+    fn lmsr_sell(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        outcome: u64,
+        quantity: u64,
+        min_proceeds: u64
+    ) -> OmegaResult<()> {
+        const NUM_FIXED: usize = 9;
+        let (fixed_accs, outcome_mint_accs) = array_refs![accounts, NUM_FIXED; ..;];
+        let [
+            omega_contract_acc,
+            user_acc,
+            user_quote_acc,
+            vault_acc,
+            spl_token_program_acc,
+            omega_signer_acc,
+            outcome_mint_acc,
+            outcome_user_acc,
+            clock_acc
+        ] = fixed_accs;
+
+        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+        check_assert!(*vault_acc.key == omega_contract.vault)?;
+        check_assert!(user_acc.is_signer)?;
+        check_assert!(omega_contract.lmsr.liquidity != 0)?;
+
+        let clock = solana_program::clock::Clock::from_account_info(clock_acc)?;
+        let curr_time = unix_time(&clock)?;
+        check_assert!(curr_time < omega_contract.exp_time)?;
+        check_assert!(omega_contract.winner == Pubkey::default())?;
+
+        let outcome = outcome as usize;
+        if outcome >= omega_contract.num_outcomes || *outcome_mint_acc.key != omega_contract.outcomes[outcome] {
+            return Err(OmegaErrorCode::InvalidOutcome.into());
+        }
+
+        // The market maker only buys back what it has sold
+        let num_outcomes = omega_contract.num_outcomes;
+        let quantities = &omega_contract.lmsr.quantities[..num_outcomes];
+        let proceeds = lmsr::sell_proceeds(omega_contract.lmsr.liquidity, quantities, outcome, quantity)
+            .ok_or(assertion_error!())?;
+        if proceeds < min_proceeds {
+            return Err(OmegaErrorCode::SlippageExceeded.into());
+        }
+
+        let burn_instruction = spl_token::instruction::burn(
+            spl_token_program_acc.key,
+            outcome_user_acc.key,
+            outcome_mint_acc.key,
+            user_acc.key,
+            &[],
+            quantity,
+        )?;
+        let burn_accs = [
+            outcome_user_acc.clone(),
+            outcome_mint_acc.clone(),
+            user_acc.clone(),
+            spl_token_program_acc.clone()
+        ];
+        invoke(&burn_instruction, &burn_accs)?;
+
+        let withdraw_instruction = spl_token::instruction::transfer(
+            spl_token_program_acc.key,
+            vault_acc.key,
+            user_quote_acc.key,
+            omega_signer_acc.key,
+            &[],
+            proceeds
+        )?;
+        let withdraw_accs = [
+            vault_acc.clone(),
+            user_quote_acc.clone(),
+            omega_signer_acc.clone(),
+            spl_token_program_acc.clone()
+        ];
+        let signer_seeds = gen_signer_seeds(&omega_contract.signer_nonce, omega_contract_acc.key);
+        invoke_signed(&withdraw_instruction, &withdraw_accs, &[&signer_seeds])?;
+
+        omega_contract.lmsr.quantities[outcome] = math::sub(omega_contract.lmsr.quantities[outcome], quantity)?;
+        omega_contract.sub_supply(outcome, quantity)?;
+        omega_contract.stats.record(Activity::OutcomesRedeemed, quantity, curr_time)?;
+
+        let supplies = read_outcome_supplies(&omega_contract, outcome_mint_accs.iter())?;
+        check_vault_solvency(&omega_contract, vault_acc, &supplies)?;
+
+        Ok(())
+    }
+
+    fn lmsr_withdraw(program_id: &Pubkey, accounts: &[AccountInfo]) -> OmegaResult<()> {
+        const NUM_FIXED: usize = 7;
+        let (fixed_accs, outcome_accs) = array_refs![accounts, NUM_FIXED; ..;];
+        let [
+            omega_contract_acc,
+            creator_acc,
+            creator_quote_acc,
+            vault_acc,
+            spl_token_program_acc,
+            omega_signer_acc,
+            clock_acc
+        ] = fixed_accs;
+
+        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+        check_assert!(*vault_acc.key == omega_contract.vault)?;
+        check_assert!(creator_acc.is_signer)?;
+        check_assert!(omega_contract.lmsr.liquidity != 0)?;
+        check_assert!(omega_contract.lmsr.creator == *creator_acc.key)?;
+        check_assert!(outcome_accs.len() == omega_contract.num_outcomes)?;
+
+        let clock = solana_program::clock::Clock::from_account_info(clock_acc)?;
+        let curr_time = unix_time(&clock)?;
+        check_bond_settled(&omega_contract, curr_time)?;
+
+        let mut supplies = [0u64; MAX_OUTCOMES];
+        for (i, outcome_acc) in outcome_accs.iter().enumerate() {
+            check_assert!(*outcome_acc.key == omega_contract.outcomes[i])?;
+            supplies[i] = Mint::unpack(&outcome_acc.try_borrow_data()?)?.supply;
+        }
+
+        // Quote tokens that outcome token holders can still redeem
+        let owed = if omega_contract.winner == Pubkey::default() {
+            check_assert!(curr_time >= omega_contract.auto_exp_time)?;
+            // withdrawing at the auto expiry rate rules out a late resolution
+            omega_contract.auto_exp_redemptions = math::add(omega_contract.auto_exp_redemptions, 1)?;
+            let total = math::sum(&supplies[..omega_contract.num_outcomes])?;
+            math::add(math::div(total, omega_contract.num_outcomes as u64)?, omega_contract.bond.share(total)?)?
+        } else {
+            let i = omega_contract.outcomes.iter().position(|pk| *pk == omega_contract.winner)
+                .ok_or(assertion_error!())?;
+            math::add(supplies[i], omega_contract.bond.share(supplies[i])?)?
+        };
+
+        // the unpaid oracle reward goes back to whoever funded it
+        let vault = Account::unpack(&vault_acc.try_borrow_data()?)?;
+        let surplus = vault.amount.saturating_sub(owed).saturating_sub(omega_contract.oracle_fee.reserve);
+
+        let withdraw_instruction = spl_token::instruction::transfer(
+            spl_token_program_acc.key,
+            vault_acc.key,
+            creator_quote_acc.key,
+            omega_signer_acc.key,
+            &[],
+            surplus
+        )?;
+        let withdraw_accs = [
+            vault_acc.clone(),
+            creator_quote_acc.clone(),
+            omega_signer_acc.clone(),
+            spl_token_program_acc.clone()
+        ];
+        let signer_seeds = gen_signer_seeds(&omega_contract.signer_nonce, omega_contract_acc.key);
+        invoke_signed(&withdraw_instruction, &withdraw_accs, &[&signer_seeds])?;
+
+        check_vault_solvency(&omega_contract, vault_acc, &supplies)?;
+
+        Ok(())
+    }
+
+    fn init_order_book(program_id: &Pubkey, accounts: &[AccountInfo]) -> OmegaResult<()> {
+        let accounts = array_ref![accounts, 0, 6];
+        let [
+            omega_contract_acc,
+            order_book_acc,
+            outcome_mint_acc,
+            quote_escrow_acc,
+            outcome_escrow_acc,
+            rent_acc
+        ] = accounts;
+
+        let omega_contract = OmegaContract::load(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+
+        let rent = Rent::from_account_info(rent_acc)?;
+        check_assert!(order_book_acc.owner == program_id)?;
+        check_assert!(rent.is_exempt(order_book_acc.lamports(), size_of::<OrderBook>()))?;
+
+        let num_outcomes = omega_contract.num_outcomes;
+        if !omega_contract.outcomes[..num_outcomes].contains(outcome_mint_acc.key) {
+            return Err(OmegaErrorCode::InvalidOutcome.into());
+        }
+
+        let quote_escrow = Account::unpack(&quote_escrow_acc.try_borrow_data()?)?;
+        check_assert!(quote_escrow.owner == omega_contract.signer_key)?;
+        check_assert!(quote_escrow.mint == omega_contract.quote_mint)?;
+        check_assert!(*quote_escrow_acc.key != omega_contract.vault)?;
+        let outcome_escrow = Account::unpack(&outcome_escrow_acc.try_borrow_data()?)?;
+        check_assert!(outcome_escrow.owner == omega_contract.signer_key)?;
+        check_assert!(outcome_escrow.mint == *outcome_mint_acc.key)?;
+
+        let mut order_book = OrderBook::load_mut(order_book_acc)?;
+        check_assert!(order_book.account_flags == 0)?;
+        order_book.account_flags = (AccountFlag::Initialized | AccountFlag::OrderBook).bits();
+        order_book.omega_contract = *omega_contract_acc.key;
+        order_book.outcome_mint = *outcome_mint_acc.key;
+        order_book.quote_escrow = *quote_escrow_acc.key;
+        order_book.outcome_escrow = *outcome_escrow_acc.key;
+        order_book.next_order_id = 0;
+
+        Ok(())
+    }
+
+    fn place_order(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        side: u64,
+        price: u64,
+        quantity: u64
+    ) -> OmegaResult<()> {
+        const NUM_FIXED: usize = 7;
+        let (fixed_accs, solvency_accs) = array_refs![accounts, NUM_FIXED; ..;];
+        let [
+            omega_contract_acc,
+            order_book_acc,
+            owner_acc,
+            owner_src_acc,
+            escrow_acc,
+            spl_token_program_acc,
+            clock_acc
+        ] = fixed_accs;
+
+        let side = Side::try_from(side).map_err(|_| OmegaErrorCode::InvalidOrder)?;
+        if price == 0 || price >= PRICE_SCALE || quantity == 0 || !quantity.is_multiple_of(PRICE_SCALE) {
+            return Err(OmegaErrorCode::InvalidOrder.into());
+        }
+
+        let omega_contract = OmegaContract::load(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+        check_assert!(owner_acc.is_signer)?;
+        if omega_contract.mode != ContractMode::CompleteSet as u64 {
+            return Err(OmegaErrorCode::InvalidContractMode.into());
+        }
+
+        let clock = solana_program::clock::Clock::from_account_info(clock_acc)?;
+        let curr_time = unix_time(&clock)?;
+        check_assert!(curr_time < omega_contract.exp_time)?;
+        check_assert!(omega_contract.winner == Pubkey::default())?;
+        if omega_contract.commitment != [0u8; 32] {
+            return Err(OmegaErrorCode::TradingClosed.into());
+        }
+
+        let mut order_book = OrderBook::load_mut(order_book_acc)?;
+        check_order_book(&order_book, order_book_acc, omega_contract_acc, program_id)?;
+
+        let (escrow_key, amount) = match side {
+            Side::Bid => (order_book.quote_escrow, math::mul_div(price, quantity, PRICE_SCALE)?),
+            Side::Ask => (order_book.outcome_escrow, quantity),
+        };
+        check_assert!(*escrow_acc.key == escrow_key)?;
+        let slot = order_book.free_slot().ok_or(OmegaErrorCode::OrderBookFull)?;
+
+        let deposit_instruction = spl_token::instruction::transfer(
+            spl_token_program_acc.key,
+            owner_src_acc.key,
+            escrow_acc.key,
+            owner_acc.key,
+            &[],
+            amount
+        )?;
+        let deposit_accs = [owner_src_acc.clone(), escrow_acc.clone(), owner_acc.clone(), spl_token_program_acc.clone()];
+        invoke(&deposit_instruction, &deposit_accs)?;
+
+        let order_id = order_book.next_order_id;
+        order_book.next_order_id = math::add(order_book.next_order_id, 1)?;
+        order_book.orders[slot] = Order {
+            owner: *owner_acc.key,
+            order_id,
+            side: side.into(),
+            price,
+            quantity,
+            quote_free: 0,
+            outcome_free: 0
+        };
+        msg!("order_id: {}", order_id);
+
+        check_solvency_accounts(&omega_contract, solvency_accs)
+    }
+
+    fn settle_order(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        order_id: u64,
+        cancel: bool
+    ) -> OmegaResult<()> {
+        const NUM_FIXED: usize = 9;
+        let (fixed_accs, solvency_accs) = array_refs![accounts, NUM_FIXED; ..;];
+        let [
+            omega_contract_acc,
+            order_book_acc,
+            owner_acc,
+            owner_quote_acc,
+            owner_outcome_acc,
+            quote_escrow_acc,
+            outcome_escrow_acc,
+            spl_token_program_acc,
+            omega_signer_acc
+        ] = fixed_accs;
+
+        let omega_contract = OmegaContract::load(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+        check_assert!(owner_acc.is_signer)?;
+
+        let mut order_book = OrderBook::load_mut(order_book_acc)?;
+        check_order_book(&order_book, order_book_acc, omega_contract_acc, program_id)?;
+        check_assert!(*quote_escrow_acc.key == order_book.quote_escrow)?;
+        check_assert!(*outcome_escrow_acc.key == order_book.outcome_escrow)?;
+
+        let i = order_book.find(order_id).ok_or(OmegaErrorCode::InvalidOrder)?;
+        let order = &mut order_book.orders[i];
+        check_assert!(order.owner == *owner_acc.key)?;
+
+        if cancel {
+            if order.side == Side::Bid as u64 {
+                order.quote_free = math::add(order.quote_free, math::mul_div(order.price, order.quantity, PRICE_SCALE)?)?;
+            } else {
+                order.outcome_free = math::add(order.outcome_free, order.quantity)?;
+            }
+            order.quantity = 0;
+        }
+
+        let signer_seeds = gen_signer_seeds(&omega_contract.signer_nonce, omega_contract_acc.key);
+        for &(escrow_acc, owner_dst_acc, amount) in [
+            (quote_escrow_acc, owner_quote_acc, order.quote_free),
+            (outcome_escrow_acc, owner_outcome_acc, order.outcome_free)
+        ].iter() {
+            if amount == 0 {
+                continue;
+            }
+            let withdraw_instruction = spl_token::instruction::transfer(
+                spl_token_program_acc.key,
+                escrow_acc.key,
+                owner_dst_acc.key,
+                omega_signer_acc.key,
+                &[],
+                amount
+            )?;
+            let withdraw_accs = [
+                escrow_acc.clone(),
+                owner_dst_acc.clone(),
+                omega_signer_acc.clone(),
+                spl_token_program_acc.clone()
+            ];
+            invoke_signed(&withdraw_instruction, &withdraw_accs, &[&signer_seeds])?;
+        }
+
+        order.quote_free = 0;
+        order.outcome_free = 0;
+        if order.quantity == 0 {
+            *order = Order::zeroed();
+        }
+
+        check_solvency_accounts(&omega_contract, solvency_accs)
+    }
+
+    fn match_orders(program_id: &Pubkey, accounts: &[AccountInfo], limit: u64) -> OmegaResult<()> {
+        let accounts = array_ref![accounts, 0, 2];
+        let [
+            omega_contract_acc,
+            order_book_acc
+        ] = accounts;
+
+        let omega_contract = OmegaContract::load(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+        check_assert!(omega_contract.winner == Pubkey::default())?;
+        if omega_contract.commitment != [0u8; 32] {
+            return Err(OmegaErrorCode::TradingClosed.into());
+        }
+
+        let mut order_book = OrderBook::load_mut(order_book_acc)?;
+        check_order_book(&order_book, order_book_acc, omega_contract_acc, program_id)?;
+
+        for _ in 0..limit {
+            let (bid_i, ask_i) = match (order_book.best(Side::Bid), order_book.best(Side::Ask)) {
+                (Some(bid_i), Some(ask_i)) => (bid_i, ask_i),
+                _ => break
+            };
+            let bid = order_book.orders[bid_i];
+            let ask = order_book.orders[ask_i];
+            if bid.price < ask.price {
+                break;
+            }
+
+            // Both escrows already hold what is traded, so a fill only moves balances between orders
+            let price = if bid.order_id < ask.order_id { bid.price } else { ask.price };
+            let quantity = min(bid.quantity, ask.quantity);
+
+            let bid = &mut order_book.orders[bid_i];
+            bid.quantity = math::sub(bid.quantity, quantity)?;
+            bid.outcome_free = math::add(bid.outcome_free, quantity)?;
+            let refund = math::mul_div(math::sub(bid.price, price)?, quantity, PRICE_SCALE)?;
+            bid.quote_free = math::add(bid.quote_free, refund)?;
+
+            let ask = &mut order_book.orders[ask_i];
+            ask.quantity = math::sub(ask.quantity, quantity)?;
+            ask.quote_free = math::add(ask.quote_free, math::mul_div(price, quantity, PRICE_SCALE)?)?;
+        }
+
+        Ok(())
+    }
+
+    fn match_sets(program_id: &Pubkey, accounts: &[AccountInfo], side: u64, limit: u64) -> OmegaResult<()> {
+        const NUM_FIXED: usize = 5;
+        let (fixed_accs, book_accs) = array_refs![accounts, NUM_FIXED; ..;];
+        let [
+            omega_contract_acc,
+            vault_acc,
+            spl_token_program_acc,
+            omega_signer_acc,
+            clock_acc
+        ] = fixed_accs;
+
+        let side = Side::try_from(side).map_err(|_| OmegaErrorCode::InvalidOrder)?;
+
+        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+        check_assert!(*vault_acc.key == omega_contract.vault)?;
+        check_assert!(omega_contract.winner == Pubkey::default())?;
+        if omega_contract.commitment != [0u8; 32] {
+            return Err(OmegaErrorCode::TradingClosed.into());
+        }
+        if omega_contract.mode != ContractMode::CompleteSet as u64 {
+            return Err(OmegaErrorCode::InvalidContractMode.into());
+        }
+        if side == Side::Bid {
+            check_not_slashed(&omega_contract)?;
+            // the bidders can't prove they are on the allow-list, so they only trade against asks
+            if omega_contract.allow_list_root != [0u8; 32] {
+                return Err(OmegaErrorCode::NotOnAllowList.into());
+            }
+            // nor can their issuance be counted against a wallet cap
+            if omega_contract.wallet_cap != 0 {
+                return Err(OmegaErrorCode::IssuanceCapExceeded.into());
+            }
+        }
+        let num_outcomes = omega_contract.num_outcomes;
+        check_assert!(book_accs.len() == 4 * num_outcomes)?;
+        let clock = solana_program::clock::Clock::from_account_info(clock_acc)?;
+        let curr_time = unix_time(&clock)?;
+
+        let mut order_books = Vec::with_capacity(num_outcomes);
+        for i in 0..num_outcomes {
+            let order_book_acc = &book_accs[4 * i];
+            let order_book = OrderBook::load_mut(order_book_acc)?;
+            check_order_book(&order_book, order_book_acc, omega_contract_acc, program_id)?;
+            check_assert!(order_book.outcome_mint == omega_contract.outcomes[i])?;
+            check_assert!(*book_accs[4 * i + 1].key == order_book.quote_escrow)?;
+            check_assert!(*book_accs[4 * i + 2].key == order_book.outcome_escrow)?;
+            check_assert!(*book_accs[4 * i + 3].key == order_book.outcome_mint)?;
+            order_books.push(order_book);
+        }
+
+        let signer_nonce = omega_contract.signer_nonce;
+        let signer_seeds = gen_signer_seeds(&signer_nonce, omega_contract_acc.key);
+        'matching: for _ in 0..limit {
+            let mut best = [0usize; MAX_OUTCOMES];
+            let mut total_price = 0;
+            let mut quantity = u64::MAX;
+            for i in 0..num_outcomes {
+                best[i] = match order_books[i].best(side) {
+                    Some(j) => j,
+                    None => break 'matching
+                };
+                let order = &order_books[i].orders[best[i]];
+                total_price = math::add(total_price, order.price)?;
+                quantity = min(quantity, order.quantity);
+            }
+
+            let crossed = match side {
+                Side::Bid => total_price >= PRICE_SCALE,
+                Side::Ask => total_price <= PRICE_SCALE,
+            };
+            if !crossed {
+                break;
+            }
+
+            // Most recent orders crossed the books, so they get the price improvement
+            let mut by_age: Vec<usize> = (0..num_outcomes).collect();
+            by_age.sort_by_key(|&i| std::cmp::Reverse(order_books[i].orders[best[i]].order_id));
+            let mut surplus = math::mul_div(
+                total_price.abs_diff(PRICE_SCALE),
+                quantity,
+                PRICE_SCALE
+            )?;
+            match side {
+                Side::Bid => {
+                    omega_contract.add_sets(quantity)?;
+                    omega_contract.stats.record(Activity::SetsIssued, quantity, curr_time)?;
+                }
+                Side::Ask => {
+                    omega_contract.sub_sets(quantity)?;
+                    omega_contract.stats.record(Activity::SetsRedeemed, quantity, curr_time)?;
+                }
+            }
+
+            let mut improvement = [0u64; MAX_OUTCOMES];
+            for &i in by_age.iter() {
+                improvement[i] = match side {
+                    Side::Bid => min(surplus, math::mul_div(order_books[i].orders[best[i]].price, quantity, PRICE_SCALE)?),
+                    Side::Ask => surplus,
+                };
+                surplus = math::sub(surplus, improvement[i])?;
+            }
+
+            for i in 0..num_outcomes {
+                let quote_escrow_acc = &book_accs[4 * i + 1];
+                let outcome_escrow_acc = &book_accs[4 * i + 2];
+                let outcome_mint_acc = &book_accs[4 * i + 3];
+                let order = &mut order_books[i].orders[best[i]];
+                let amount = math::mul_div(order.price, quantity, PRICE_SCALE)?;
+
+                match side {
+                    Side::Bid => {
+                        // Quote escrow pays the vault and new sets are minted into the outcome escrow
+                        let (src_acc, dst_acc, amount) = (quote_escrow_acc, vault_acc, math::sub(amount, improvement[i])?);
+                        let transfer_instruction = spl_token::instruction::transfer(
+                            spl_token_program_acc.key,
+                            src_acc.key,
+                            dst_acc.key,
+                            omega_signer_acc.key,
+                            &[],
+                            amount
+                        )?;
+                        let transfer_accs = [
+                            src_acc.clone(),
+                            dst_acc.clone(),
+                            omega_signer_acc.clone(),
+                            spl_token_program_acc.clone()
+                        ];
+                        invoke_signed(&transfer_instruction, &transfer_accs, &[&signer_seeds])?;
+
+                        let mint_instruction = spl_token::instruction::mint_to(
+                            spl_token_program_acc.key,
+                            outcome_mint_acc.key,
+                            outcome_escrow_acc.key,
+                            omega_signer_acc.key,
+                            &[],
+                            quantity,
+                        )?;
+                        let mint_accs = [
+                            outcome_mint_acc.clone(),
+                            outcome_escrow_acc.clone(),
+                            omega_signer_acc.clone(),
+                            spl_token_program_acc.clone()
+                        ];
+                        invoke_signed(&mint_instruction, &mint_accs, &[&signer_seeds])?;
+
+                        order.outcome_free = math::add(order.outcome_free, quantity)?;
+                        order.quote_free = math::add(order.quote_free, improvement[i])?;
+                    }
+                    Side::Ask => {
+                        // Sets are burned from the outcome escrows and the vault pays the quote escrow
+                        let burn_instruction = spl_token::instruction::burn(
+                            spl_token_program_acc.key,
+                            outcome_escrow_acc.key,
+                            outcome_mint_acc.key,
+                            omega_signer_acc.key,
+                            &[],
+                            quantity,
+                        )?;
+                        let burn_accs = [
+                            outcome_escrow_acc.clone(),
+                            outcome_mint_acc.clone(),
+                            omega_signer_acc.clone(),
+                            spl_token_program_acc.clone()
+                        ];
+                        invoke_signed(&burn_instruction, &burn_accs, &[&signer_seeds])?;
+
+                        let (src_acc, dst_acc, amount) = (vault_acc, quote_escrow_acc, math::add(amount, improvement[i])?);
+                        let transfer_instruction = spl_token::instruction::transfer(
+                            spl_token_program_acc.key,
+                            src_acc.key,
+                            dst_acc.key,
+                            omega_signer_acc.key,
+                            &[],
+                            amount
+                        )?;
+                        let transfer_accs = [
+                            src_acc.clone(),
+                            dst_acc.clone(),
+                            omega_signer_acc.clone(),
+                            spl_token_program_acc.clone()
+                        ];
+                        invoke_signed(&transfer_instruction, &transfer_accs, &[&signer_seeds])?;
+
+                        order.quote_free = math::add(order.quote_free, amount)?;
+                    }
+                }
+                order.quantity = math::sub(order.quantity, quantity)?;
+            }
+        }
+
+        let supplies = read_outcome_supplies(&omega_contract, book_accs.iter().skip(3).step_by(4))?;
+        check_vault_solvency(&omega_contract, vault_acc, &supplies)?;
+
+        Ok(())
+    }
+
+    fn init_parimutuel(program_id: &Pubkey, accounts: &[AccountInfo], fee_bps: u64) -> OmegaResult<()> {
+        const NUM_FIXED: usize = 3;
+        let (fixed_accs, outcome_accs) = array_refs![accounts, NUM_FIXED; ..;];
+        let [
+            omega_contract_acc,
+            oracle_acc,
+            fee_acc
+        ] = fixed_accs;
+
+        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+        check_assert!(omega_contract.oracle == *oracle_acc.key)?;
+        check_assert!(oracle_acc.is_signer)?;
+        check_assert!(omega_contract.winner == Pubkey::default())?;
+        check_assert!(fee_bps <= MAX_BPS)?;
+        if omega_contract.mode != ContractMode::CompleteSet as u64 || omega_contract.lmsr.liquidity != 0 {
+            return Err(OmegaErrorCode::InvalidContractMode.into());
+        }
+
+        let fee = Account::unpack(&fee_acc.try_borrow_data()?)?;
+        check_assert!(fee.mint == omega_contract.quote_mint)?;
+
+        // Pools must start empty, so no outcome token may have been issued yet
+        check_assert!(outcome_accs.len() == omega_contract.num_outcomes)?;
+        for (i, outcome_acc) in outcome_accs.iter().enumerate() {
+            check_assert!(*outcome_acc.key == omega_contract.outcomes[i])?;
+            check_assert!(Mint::unpack(&outcome_acc.try_borrow_data()?)?.supply == 0)?;
+        }
+
+        omega_contract.mode = ContractMode::Parimutuel.into();
+        omega_contract.parimutuel.fee_acc = *fee_acc.key;
+        omega_contract.parimutuel.fee_bps = fee_bps;
+        omega_contract.parimutuel.pools = [0; MAX_OUTCOMES];
+
+        Ok(())
+    }
+
+    fn stake(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        outcome: u64,
+        quantity: u64,
+        allow_list_proof: &[u8]
+    ) -> OmegaResult<()> {
+        const NUM_FIXED: usize = 9;
+        let (fixed_accs, tail_accs) = array_refs![accounts, NUM_FIXED; ..;];
+        let [
+            omega_contract_acc,
+            user_acc,
+            user_quote_acc,
+            vault_acc,
+            spl_token_program_acc,
+            omega_signer_acc,
+            outcome_mint_acc,
+            outcome_user_acc,
+            clock_acc
+        ] = fixed_accs;
+
+        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+        check_assert!(*vault_acc.key == omega_contract.vault)?;
+        check_assert!(tail_accs.len() >= omega_contract.num_outcomes)?;
+        let (outcome_mint_accs, issuance_accs) = tail_accs.split_at(omega_contract.num_outcomes);
+        check_assert!(user_acc.is_signer)?;
+        if omega_contract.mode != ContractMode::Parimutuel as u64 {
+            return Err(OmegaErrorCode::InvalidContractMode.into());
+        }
+
+        let clock = solana_program::clock::Clock::from_account_info(clock_acc)?;
+        let curr_time = unix_time(&clock)?;
+        check_assert!(curr_time < omega_contract.exp_time)?;
+        check_assert!(omega_contract.winner == Pubkey::default())?;
+        check_not_slashed(&omega_contract)?;
+        check_allow_list(&omega_contract, user_acc.key, allow_list_proof)?;
+
+        let outcome = outcome as usize;
+        if outcome >= omega_contract.num_outcomes || *outcome_mint_acc.key != omega_contract.outcomes[outcome] {
+            return Err(OmegaErrorCode::InvalidOutcome.into());
+        }
+        let pool = math::add(omega_contract.parimutuel.pools[outcome], quantity)?;
+        omega_contract.add_supply(outcome, quantity)?;
+        omega_contract.stats.record(Activity::OutcomesIssued, quantity, curr_time)?;
+        Self::record_optional_issuance(
+            program_id, &mut omega_contract, omega_contract_acc, user_acc, issuance_accs, quantity
+        )?;
+
+        let deposit_instruction = spl_token::instruction::transfer(
+            spl_token_program_acc.key,
+            user_quote_acc.key,
+            vault_acc.key,
+            user_acc.key,
+            &[],
+            quantity
+        )?;
+        let deposit_accs = [user_quote_acc.clone(), vault_acc.clone(), user_acc.clone(), spl_token_program_acc.clone()];
+        invoke(&deposit_instruction, &deposit_accs)?;
+
+        let mint_instruction = spl_token::instruction::mint_to(
+            spl_token_program_acc.key,
+            outcome_mint_acc.key,
+            outcome_user_acc.key,
+            omega_signer_acc.key,
+            &[],
+            quantity,
+        )?;
+        let mint_accs = [
+            outcome_mint_acc.clone(),
+            outcome_user_acc.clone(),
+            omega_signer_acc.clone(),
+            spl_token_program_acc.clone()
+        ];
+        let signer_seeds = gen_signer_seeds(&omega_contract.signer_nonce, omega_contract_acc.key);
+        invoke_signed(&mint_instruction, &mint_accs, &[&signer_seeds])?;
+
+        omega_contract.parimutuel.pools[outcome] = pool;
+
+        let supplies = read_outcome_supplies(&omega_contract, outcome_mint_accs.iter())?;
+        check_vault_solvency(&omega_contract, vault_acc, &supplies)?;
+
+        Ok(())
+    }
+
+    fn collect_parimutuel_fee(program_id: &Pubkey, accounts: &[AccountInfo]) -> OmegaResult<()> {
+        let accounts = array_ref![accounts, 0, 7];
+        let [
+            omega_contract_acc,
+            fee_acc,
+            vault_acc,
+            spl_token_program_acc,
+            omega_signer_acc,
+            winner_mint_acc,
+            clock_acc
+        ] = accounts;
+
+        let omega_contract = OmegaContract::load(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+        check_assert!(*vault_acc.key == omega_contract.vault)?;
+        check_assert!(*fee_acc.key == omega_contract.parimutuel.fee_acc)?;
+        if omega_contract.mode != ContractMode::Parimutuel as u64 {
+            return Err(OmegaErrorCode::InvalidContractMode.into());
+        }
+        check_assert!(omega_contract.winner != Pubkey::default())?;
+        check_assert!(*winner_mint_acc.key == omega_contract.winner)?;
+
+        // the fee is whatever the holders are not owed, which depends on the bond
+        let clock = solana_program::clock::Clock::from_account_info(clock_acc)?;
+        check_bond_settled(&omega_contract, unix_time(&clock)?)?;
+
+        let num_outcomes = omega_contract.num_outcomes;
+        let pools = &omega_contract.parimutuel.pools[..num_outcomes];
+        let winner = omega_contract.outcomes[..num_outcomes].iter().position(|pk| *pk == omega_contract.winner)
+            .ok_or(assertion_error!())?;
+        check_assert!(pools[winner] != 0)?;
+
+        // Outstanding winning tickets can redeem at most this much
+        let supply = Mint::unpack(&winner_mint_acc.try_borrow_data()?)?.supply;
+        let owed = math::sum(&[
+            parimutuel_payout(supply, pools, winner, omega_contract.parimutuel.fee_bps)?,
+            omega_contract.bond.share(supply)?,
+            omega_contract.oracle_fee.reserve,
+        ])?;
+        let vault = Account::unpack(&vault_acc.try_borrow_data()?)?;
+        let fee = vault.amount.saturating_sub(owed);
+
+        let withdraw_instruction = spl_token::instruction::transfer(
+            spl_token_program_acc.key,
+            vault_acc.key,
+            fee_acc.key,
+            omega_signer_acc.key,
+            &[],
+            fee
+        )?;
+        let withdraw_accs = [
+            vault_acc.clone(),
+            fee_acc.clone(),
+            omega_signer_acc.clone(),
+            spl_token_program_acc.clone()
+        ];
+        let signer_seeds = gen_signer_seeds(&omega_contract.signer_nonce, omega_contract_acc.key);
+        invoke_signed(&withdraw_instruction, &withdraw_accs, &[&signer_seeds])?;
+
+        if let Some(supplies) = resolved_supplies(&omega_contract, winner_mint_acc)? {
+            check_vault_solvency(&omega_contract, vault_acc, &supplies)?;
+        }
+
+        Ok(())
+    }
+
+    /// Buy (side = Bid) or sell (side = Ask) one outcome by issuing or redeeming sets
+    /// and trading every other outcome against its order book
+    fn swap_outcome(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        side: Side,
+        outcome: u64,
+        quantity: u64,
+        limit: u64,  // max_cost when buying, min_proceeds when selling
+        allow_list_proof: &[u8]
+    ) -> OmegaResult<()> {
+        // with the market maker on, it takes the other side of the trade instead of the order books
+        let omega_contract_acc = accounts.first().ok_or(assertion_error!())?;
+        if OmegaContract::load(omega_contract_acc)?.lmsr.liquidity != 0 {
+            return match side {
+                Side::Bid => Self::lmsr_buy(program_id, accounts, outcome, quantity, limit, allow_list_proof),
+                Side::Ask => Self::lmsr_sell(program_id, accounts, outcome, quantity, limit),
+            };
+        }
+
+        const NUM_FIXED: usize = 9;
+        let (fixed_accs, book_accs) = array_refs![accounts, NUM_FIXED; ..;];
+        let [
+            omega_contract_acc,
+            user_acc,
+            user_quote_acc,
+            vault_acc,
+            spl_token_program_acc,
+            omega_signer_acc,
+            outcome_mint_acc,
+            outcome_user_acc,
+            clock_acc
+        ] = fixed_accs;
+
+        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+        check_assert!(*vault_acc.key == omega_contract.vault)?;
+        check_assert!(user_acc.is_signer)?;
+        check_assert!(omega_contract.winner == Pubkey::default())?;
+        if omega_contract.commitment != [0u8; 32] {
+            return Err(OmegaErrorCode::TradingClosed.into());
+        }
+        if omega_contract.mode != ContractMode::CompleteSet as u64 {
+            return Err(OmegaErrorCode::InvalidContractMode.into());
+        }
+        if quantity == 0 || !quantity.is_multiple_of(PRICE_SCALE) {
+            return Err(OmegaErrorCode::InvalidOrder.into());
+        }
+        if side == Side::Bid {
+            check_not_slashed(&omega_contract)?;
+            check_allow_list(&omega_contract, user_acc.key, allow_list_proof)?;
+        }
+        let clock = solana_program::clock::Clock::from_account_info(clock_acc)?;
+        let curr_time = unix_time(&clock)?;
+
+        let num_outcomes = omega_contract.num_outcomes;
+        let outcome = outcome as usize;
+        if outcome >= num_outcomes || *outcome_mint_acc.key != omega_contract.outcomes[outcome] {
+            return Err(OmegaErrorCode::InvalidOutcome.into());
+        }
+        check_assert!(book_accs.len() >= 4 * (num_outcomes - 1))?;
+        let (book_accs, issuance_accs) = book_accs.split_at(4 * (num_outcomes - 1));
+
+        // Trade the other outcomes first so the bound is checked before any tokens move.
+        // Buying sells them into the bids, selling buys them from the asks
+        let mut amounts = [0u64; MAX_OUTCOMES];
+        let mut total = 0u64;
+        for (k, j) in (0..num_outcomes).filter(|&j| j != outcome).enumerate() {
+            let order_book_acc = &book_accs[4 * k];
+            let mut order_book = OrderBook::load_mut(order_book_acc)?;
+            check_order_book(&order_book, order_book_acc, omega_contract_acc, program_id)?;
+            check_assert!(order_book.outcome_mint == omega_contract.outcomes[j])?;
+            check_assert!(*book_accs[4 * k + 1].key == order_book.quote_escrow)?;
+            check_assert!(*book_accs[4 * k + 2].key == order_book.outcome_escrow)?;
+            check_assert!(*book_accs[4 * k + 3].key == order_book.outcome_mint)?;
+
+            amounts[k] = order_book.take(side, quantity)?.ok_or(OmegaErrorCode::SlippageExceeded)?;
+            total = math::add(total, amounts[k])?;
+        }
+
+        let signer_nonce = omega_contract.signer_nonce;
+        let signer_seeds = gen_signer_seeds(&signer_nonce, omega_contract_acc.key);
+        match side {
+            Side::Bid => {
+                if quantity.saturating_sub(total) > limit {
+                    return Err(OmegaErrorCode::SlippageExceeded.into());
+                }
+                omega_contract.add_sets(quantity)?;
+                omega_contract.stats.record(Activity::SetsIssued, quantity, curr_time)?;
+                Self::record_optional_issuance(
+                    program_id, &mut omega_contract, omega_contract_acc, user_acc, issuance_accs, quantity
+                )?;
+
+                // Issue the sets: the user keeps the outcome being bought, the rest go to the bidders
+                let deposit_instruction = spl_token::instruction::transfer(
+                    spl_token_program_acc.key,
+                    user_quote_acc.key,
+                    vault_acc.key,
+                    user_acc.key,
+                    &[],
+                    quantity
+                )?;
+                let deposit_accs = [user_quote_acc.clone(), vault_acc.clone(), user_acc.clone(), spl_token_program_acc.clone()];
+                invoke(&deposit_instruction, &deposit_accs)?;
+
+                let mint_instruction = spl_token::instruction::mint_to(
+                    spl_token_program_acc.key,
+                    outcome_mint_acc.key,
+                    outcome_user_acc.key,
+                    omega_signer_acc.key,
+                    &[],
+                    quantity,
+                )?;
+                let mint_accs = [
+                    outcome_mint_acc.clone(),
+                    outcome_user_acc.clone(),
+                    omega_signer_acc.clone(),
+                    spl_token_program_acc.clone()
+                ];
+                invoke_signed(&mint_instruction, &mint_accs, &[&signer_seeds])?;
+
+                for k in 0..num_outcomes - 1 {
+                    let quote_escrow_acc = &book_accs[4 * k + 1];
+                    let outcome_escrow_acc = &book_accs[4 * k + 2];
+                    let other_mint_acc = &book_accs[4 * k + 3];
+
+                    let mint_instruction = spl_token::instruction::mint_to(
+                        spl_token_program_acc.key,
+                        other_mint_acc.key,
+                        outcome_escrow_acc.key,
+                        omega_signer_acc.key,
+                        &[],
+                        quantity,
+                    )?;
+                    let mint_accs = [
+                        other_mint_acc.clone(),
+                        outcome_escrow_acc.clone(),
+                        omega_signer_acc.clone(),
+                        spl_token_program_acc.clone()
+                    ];
+                    invoke_signed(&mint_instruction, &mint_accs, &[&signer_seeds])?;
+
+                    let withdraw_instruction = spl_token::instruction::transfer(
+                        spl_token_program_acc.key,
+                        quote_escrow_acc.key,
+                        user_quote_acc.key,
+                        omega_signer_acc.key,
+                        &[],
+                        amounts[k]
+                    )?;
+                    let withdraw_accs = [
+                        quote_escrow_acc.clone(),
+                        user_quote_acc.clone(),
+                        omega_signer_acc.clone(),
+                        spl_token_program_acc.clone()
+                    ];
+                    invoke_signed(&withdraw_instruction, &withdraw_accs, &[&signer_seeds])?;
+                }
+            }
+            Side::Ask => {
+                let proceeds = quantity.checked_sub(total).ok_or(OmegaErrorCode::SlippageExceeded)?;
+                if proceeds < limit {
+                    return Err(OmegaErrorCode::SlippageExceeded.into());
+                }
+                check_assert!(issuance_accs.is_empty())?;
+                omega_contract.sub_sets(quantity)?;
+                omega_contract.stats.record(Activity::SetsRedeemed, quantity, curr_time)?;
+
+                // Redeem the sets: the user's outcome tokens and the ones bought from the asks are burned
+                let burn_instruction = spl_token::instruction::burn(
+                    spl_token_program_acc.key,
+                    outcome_user_acc.key,
+                    outcome_mint_acc.key,
+                    user_acc.key,
+                    &[],
+                    quantity,
+                )?;
+                let burn_accs = [
+                    outcome_user_acc.clone(),
+                    outcome_mint_acc.clone(),
+                    user_acc.clone(),
+                    spl_token_program_acc.clone()
+                ];
+                invoke(&burn_instruction, &burn_accs)?;
+
+                for k in 0..num_outcomes - 1 {
+                    let quote_escrow_acc = &book_accs[4 * k + 1];
+                    let outcome_escrow_acc = &book_accs[4 * k + 2];
+                    let other_mint_acc = &book_accs[4 * k + 3];
+
+                    let burn_instruction = spl_token::instruction::burn(
+                        spl_token_program_acc.key,
+                        outcome_escrow_acc.key,
+                        other_mint_acc.key,
+                        omega_signer_acc.key,
+                        &[],
+                        quantity,
+                    )?;
+                    let burn_accs = [
+                        outcome_escrow_acc.clone(),
+                        other_mint_acc.clone(),
+                        omega_signer_acc.clone(),
+                        spl_token_program_acc.clone()
+                    ];
+                    invoke_signed(&burn_instruction, &burn_accs, &[&signer_seeds])?;
+
+                    let withdraw_instruction = spl_token::instruction::transfer(
+                        spl_token_program_acc.key,
+                        vault_acc.key,
+                        quote_escrow_acc.key,
+                        omega_signer_acc.key,
+                        &[],
+                        amounts[k]
+                    )?;
+                    let withdraw_accs = [
+                        vault_acc.clone(),
+                        quote_escrow_acc.clone(),
+                        omega_signer_acc.clone(),
+                        spl_token_program_acc.clone()
+                    ];
+                    invoke_signed(&withdraw_instruction, &withdraw_accs, &[&signer_seeds])?;
+                }
+
+                let withdraw_instruction = spl_token::instruction::transfer(
+                    spl_token_program_acc.key,
+                    vault_acc.key,
+                    user_quote_acc.key,
+                    omega_signer_acc.key,
+                    &[],
+                    proceeds
+                )?;
+                let withdraw_accs = [
+                    vault_acc.clone(),
+                    user_quote_acc.clone(),
+                    omega_signer_acc.clone(),
+                    spl_token_program_acc.clone()
+                ];
+                invoke_signed(&withdraw_instruction, &withdraw_accs, &[&signer_seeds])?;
+            }
+        }
+
+        // the other outcomes' mints come after their order books, in order
+        let mint_accs = (0..num_outcomes).map(|j| match j.cmp(&outcome) {
+            Ordering::Less => &book_accs[4 * j + 3],
+            Ordering::Equal => outcome_mint_acc,
+            Ordering::Greater => &book_accs[4 * (j - 1) + 3],
+        });
+        let supplies = read_outcome_supplies(&omega_contract, mint_accs)?;
+        check_vault_solvency(&omega_contract, vault_acc, &supplies)?;
+
+        Ok(())
+    }
+
+    fn init_conditional(program_id: &Pubkey, accounts: &[AccountInfo], parent_outcome: u64) -> OmegaResult<()> {
+        let accounts = array_ref![accounts, 0, 3];
+        let [
+            omega_contract_acc,
+            oracle_acc,
+            parent_contract_acc
+        ] = accounts;
+
+        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+        check_assert!(omega_contract.oracle == *oracle_acc.key)?;
+        check_assert!(oracle_acc.is_signer)?;
+        check_assert!(omega_contract.parent == Pubkey::default())?;
+
+        let parent_contract = OmegaContract::load(parent_contract_acc)?;
+        check_assert!(parent_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(parent_contract_acc.owner == program_id)?;
+        if parent_contract.mode != ContractMode::CompleteSet as u64 {
+            return Err(OmegaErrorCode::InvalidContractMode.into());
+        }
+
+        // The collateral has to be the parent's outcome token, and that outcome can't have lost already
+        let parent_outcome = parent_outcome as usize;
+        if parent_outcome >= parent_contract.num_outcomes
+            || omega_contract.quote_mint != parent_contract.outcomes[parent_outcome] {
+            return Err(OmegaErrorCode::InvalidOutcome.into());
+        }
+        check_assert!(parent_contract.winner == Pubkey::default() || parent_contract.winner == omega_contract.quote_mint)?;
+
+        omega_contract.parent = *parent_contract_acc.key;
+        omega_contract.parent_outcome = parent_outcome as u64;
+
+        Ok(())
+    }
+
+    /// Issue (issue = true) or redeem sets of a conditional contract directly from the parent's quote currency
+    fn issue_conditional_set(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        quantity: u64,
+        issue: bool,
+        allow_list_proofs: &[u8]
+    ) -> OmegaResult<()> {
+        const NUM_FIXED: usize = 10;
+        let (fixed_accs, outcome_accs) = array_refs![accounts, NUM_FIXED; ..;];
+        let [
+            omega_contract_acc,
+            parent_contract_acc,
+            user_acc,
+            user_quote_acc,
+            parent_vault_acc,
+            vault_acc,
+            spl_token_program_acc,
+            parent_signer_acc,
+            omega_signer_acc,
+            clock_acc
+        ] = fixed_accs;
+
+        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
+        let mut parent_contract = OmegaContract::load_mut(parent_contract_acc)?;
+        check_conditional(&omega_contract, omega_contract_acc, &parent_contract, parent_contract_acc, program_id)?;
+        check_assert!(*vault_acc.key == omega_contract.vault)?;
+        check_assert!(*parent_vault_acc.key == parent_contract.vault)?;
+        check_assert!(user_acc.is_signer)?;
+        let clock = solana_program::clock::Clock::from_account_info(clock_acc)?;
+        let curr_time = unix_time(&clock)?;
+        if issue {
+            check_not_slashed(&parent_contract)?;
+            check_not_slashed(&omega_contract)?;
+            let (parent_proof, proof) = split_proofs(allow_list_proofs).ok_or(OmegaErrorCode::NotOnAllowList)?;
+            check_allow_list(&parent_contract, user_acc.key, parent_proof)?;
+            check_allow_list(&omega_contract, user_acc.key, proof)?;
+        }
+
+        let parent_outcomes = parent_contract.num_outcomes;
+        let num_outcome_accs = 2 * (parent_outcomes + omega_contract.num_outcomes);
+        check_assert!(outcome_accs.len() >= num_outcome_accs)?;
+        let (outcome_accs, issuance_accs) = outcome_accs.split_at(num_outcome_accs);
+        let (parent_outcome_accs, outcome_accs) = outcome_accs.split_at(2 * parent_outcomes);
+
+        // the parent's issuance records come first, then the conditional contract's
+        if issue {
+            check_assert!(issuance_accs.is_empty() || issuance_accs.len() == 6)?;
+            let (parent_issuance_accs, issuance_accs) = issuance_accs.split_at(issuance_accs.len() / 2);
+            parent_contract.add_sets(quantity)?;
+            Self::record_optional_issuance(
+                program_id, &mut parent_contract, parent_contract_acc, user_acc, parent_issuance_accs, quantity
+            )?;
+            parent_contract.stats.record(Activity::SetsIssued, quantity, curr_time)?;
+            omega_contract.add_sets(quantity)?;
+            Self::record_optional_issuance(
+                program_id, &mut omega_contract, omega_contract_acc, user_acc, issuance_accs, quantity
+            )?;
+            omega_contract.stats.record(Activity::SetsIssued, quantity, curr_time)?;
+        } else {
+            check_assert!(issuance_accs.is_empty())?;
+            parent_contract.sub_sets(quantity)?;
+            parent_contract.stats.record(Activity::SetsRedeemed, quantity, curr_time)?;
+            omega_contract.sub_sets(quantity)?;
+            omega_contract.stats.record(Activity::SetsRedeemed, quantity, curr_time)?;
+        }
+
+        let parent_seeds = gen_signer_seeds(&parent_contract.signer_nonce, parent_contract_acc.key);
+        let signer_seeds = gen_signer_seeds(&omega_contract.signer_nonce, omega_contract_acc.key);
+
+        if issue {
+            let deposit_instruction = spl_token::instruction::transfer(
+                spl_token_program_acc.key,
+                user_quote_acc.key,
+                parent_vault_acc.key,
+                user_acc.key,
+                &[],
+                quantity
+            )?;
+            let deposit_accs = [user_quote_acc.clone(), parent_vault_acc.clone(), user_acc.clone(), spl_token_program_acc.clone()];
+            invoke(&deposit_instruction, &deposit_accs)?;
+        }
+
+        // The parent outcome backing this contract goes to or comes from its vault, the others from the user
+        for i in 0..parent_outcomes {
+            let outcome_mint_acc = &parent_outcome_accs[2 * i];
+            let outcome_user_acc = &parent_outcome_accs[2 * i + 1];
+            check_assert!(*outcome_mint_acc.key == parent_contract.outcomes[i])?;
+            let is_collateral = i as u64 == omega_contract.parent_outcome;
+            if is_collateral {
+                check_assert!(outcome_user_acc.key == vault_acc.key)?;
+            }
+
+            if issue {
+                let mint_instruction = spl_token::instruction::mint_to(
+                    spl_token_program_acc.key,
+                    outcome_mint_acc.key,
+                    outcome_user_acc.key,
+                    parent_signer_acc.key,
+                    &[],
+                    quantity,
+                )?;
+                let mint_accs = [
+                    outcome_mint_acc.clone(),
+                    outcome_user_acc.clone(),
+                    parent_signer_acc.clone(),
+                    spl_token_program_acc.clone()
+                ];
+                invoke_signed(&mint_instruction, &mint_accs, &[&parent_seeds])?;
+            } else {
+                let authority_acc = if is_collateral { omega_signer_acc } else { user_acc };
+                let burn_instruction = spl_token::instruction::burn(
+                    spl_token_program_acc.key,
+                    outcome_user_acc.key,
+                    outcome_mint_acc.key,
+                    authority_acc.key,
+                    &[],
+                    quantity,
+                )?;
+                let burn_accs = [
+                    outcome_user_acc.clone(),
+                    outcome_mint_acc.clone(),
+                    authority_acc.clone(),
+                    spl_token_program_acc.clone()
+                ];
+                invoke_signed(&burn_instruction, &burn_accs, &[&signer_seeds])?;
+            }
+        }
+
+        for i in 0..omega_contract.num_outcomes {
+            let outcome_mint_acc = &outcome_accs[2 * i];
+            let outcome_user_acc = &outcome_accs[2 * i + 1];
+
+            if issue {
+                let mint_instruction = spl_token::instruction::mint_to(
+                    spl_token_program_acc.key,
+                    outcome_mint_acc.key,
+                    outcome_user_acc.key,
+                    omega_signer_acc.key,
+                    &[],
+                    quantity,
+                )?;
+                let mint_accs = [
+                    outcome_mint_acc.clone(),
+                    outcome_user_acc.clone(),
+                    omega_signer_acc.clone(),
+                    spl_token_program_acc.clone()
+                ];
+                invoke_signed(&mint_instruction, &mint_accs, &[&signer_seeds])?;
+            } else {
+                let burn_instruction = spl_token::instruction::burn(
+                    spl_token_program_acc.key,
+                    outcome_user_acc.key,
+                    outcome_mint_acc.key,
+                    user_acc.key,
+                    &[],
+                    quantity,
+                )?;
+                let burn_accs = [
+                    outcome_user_acc.clone(),
+                    outcome_mint_acc.clone(),
+                    user_acc.clone(),
+                    spl_token_program_acc.clone()
+                ];
+                invoke(&burn_instruction, &burn_accs)?;
+            }
+        }
+
+        if !issue {
+            let withdraw_instruction = spl_token::instruction::transfer(
+                spl_token_program_acc.key,
+                parent_vault_acc.key,
+                user_quote_acc.key,
+                parent_signer_acc.key,
+                &[],
+                quantity
+            )?;
+            let withdraw_accs = [
+                parent_vault_acc.clone(),
+                user_quote_acc.clone(),
+                parent_signer_acc.clone(),
+                spl_token_program_acc.clone()
+            ];
+            invoke_signed(&withdraw_instruction, &withdraw_accs, &[&parent_seeds])?;
+        }
+
+        let parent_supplies = read_outcome_supplies(&parent_contract, parent_outcome_accs.iter().step_by(2))?;
+        check_vault_solvency(&parent_contract, parent_vault_acc, &parent_supplies)?;
+        let supplies = read_outcome_supplies(&omega_contract, outcome_accs.iter().step_by(2))?;
+        check_vault_solvency(&omega_contract, vault_acc, &supplies)?;
+
+        Ok(())
+    }
+
+    /// Redeem winning tokens of a conditional contract all the way to the parent's quote currency
+    fn redeem_conditional_winner(program_id: &Pubkey, accounts: &[AccountInfo], quantity: u64) -> OmegaResult<()> {
+        const NUM_FIXED: usize = 13;
+        let (fixed_accs, parent_mint_accs) = array_refs![accounts, NUM_FIXED; ..;];
+        let [
+            omega_contract_acc,
+            parent_contract_acc,
+            user_acc,
+            user_quote_acc,
+            parent_vault_acc,
+            vault_acc,
+            spl_token_program_acc,
+            parent_signer_acc,
+            omega_signer_acc,
+            winner_mint_acc,
+            winner_user_acc,
+            collateral_mint_acc,
+            clock_acc
+        ] = fixed_accs;
+
+        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
+        let mut parent_contract = OmegaContract::load_mut(parent_contract_acc)?;
+        check_conditional(&omega_contract, omega_contract_acc, &parent_contract, parent_contract_acc, program_id)?;
+        check_assert!(*vault_acc.key == omega_contract.vault)?;
+        check_assert!(*parent_vault_acc.key == parent_contract.vault)?;
+        check_assert!(*collateral_mint_acc.key == omega_contract.quote_mint)?;
+        check_assert!(user_acc.is_signer)?;
+
+        let clock = solana_program::clock::Clock::from_account_info(clock_acc)?;
+        let curr_time = unix_time(&clock)?;
+        check_bond_settled(&omega_contract, curr_time)?;
+        check_bond_settled(&parent_contract, curr_time)?;
+
+        // Winnings are paid in the parent's outcome token, which in turn has to be a winner of the parent.
+        // A slashed bond of the conditional contract is posted in that token too
+        let collateral = math::add(
+            winner_payout(&omega_contract, winner_mint_acc.key, quantity, curr_time)?,
+            omega_contract.bond.share(quantity)?
+        )?;
+        let winnings = math::add(
+            winner_payout(&parent_contract, collateral_mint_acc.key, collateral, curr_time)?,
+            parent_contract.bond.share(collateral)?
+        )?;
+        let outcome = omega_contract.outcome_index(winner_mint_acc.key)?;
+        omega_contract.sub_supply(outcome, quantity)?;
+        parent_contract.sub_supply(omega_contract.parent_outcome as usize, collateral)?;
+        omega_contract.stats.record(Activity::WinnerRedeemed, quantity, curr_time)?;
+        parent_contract.stats.record(Activity::WinnerRedeemed, collateral, curr_time)?;
+        if omega_contract.winner == Pubkey::default() {
+            omega_contract.auto_exp_redemptions = math::add(omega_contract.auto_exp_redemptions, 1)?;
+        }
+        if parent_contract.winner == Pubkey::default() {
+            parent_contract.auto_exp_redemptions = math::add(parent_contract.auto_exp_redemptions, 1)?;
+        }
+
+        let burn_instruction = spl_token::instruction::burn(
+            spl_token_program_acc.key,
+            winner_user_acc.key,
+            winner_mint_acc.key,
+            user_acc.key,
+            &[],
+            quantity,
+        )?;
+        let burn_accs = [
+            winner_user_acc.clone(),
+            winner_mint_acc.clone(),
+            user_acc.clone(),
+            spl_token_program_acc.clone()
+        ];
+        invoke(&burn_instruction, &burn_accs)?;
+
+        let signer_seeds = gen_signer_seeds(&omega_contract.signer_nonce, omega_contract_acc.key);
+        let burn_instruction = spl_token::instruction::burn(
+            spl_token_program_acc.key,
+            vault_acc.key,
+            collateral_mint_acc.key,
+            omega_signer_acc.key,
+            &[],
+            collateral,
+        )?;
+        let burn_accs = [
+            vault_acc.clone(),
+            collateral_mint_acc.clone(),
+            omega_signer_acc.clone(),
+            spl_token_program_acc.clone()
+        ];
+        invoke_signed(&burn_instruction, &burn_accs, &[&signer_seeds])?;
+
+        let parent_seeds = gen_signer_seeds(&parent_contract.signer_nonce, parent_contract_acc.key);
+        let withdraw_instruction = spl_token::instruction::transfer(
+            spl_token_program_acc.key,
+            parent_vault_acc.key,
+            user_quote_acc.key,
+            parent_signer_acc.key,
+            &[],
+            winnings
+        )?;
+        let withdraw_accs = [
+            parent_vault_acc.clone(),
+            user_quote_acc.clone(),
+            parent_signer_acc.clone(),
+            spl_token_program_acc.clone()
+        ];
+        invoke_signed(&withdraw_instruction, &withdraw_accs, &[&parent_seeds])?;
+
+        if let Some(supplies) = resolved_supplies(&omega_contract, winner_mint_acc)? {
+            check_vault_solvency(&omega_contract, vault_acc, &supplies)?;
+        }
+        // the parent's winner may be another outcome than the collateral, so it passes all of its mints
+        let parent_supplies = read_outcome_supplies(&parent_contract, parent_mint_accs.iter())?;
+        check_vault_solvency(&parent_contract, parent_vault_acc, &parent_supplies)?;
+
+        Ok(())
+    }
+
+    fn init_market_series(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        num_outcomes: u64,
+        first_exp_time: u64,
+        period: u64,
+        resolve_window: u64,
+        details: &[u8]
+    ) -> OmegaResult<()> {
+        let accounts = array_ref![accounts, 0, 6];
+        let [
+            market_series_acc,
+            oracle_acc,
+            quote_mint_acc,
+            rent_acc,
+            program_config_acc,
+            creator_acc
+        ] = accounts;
+
+        let rent = Rent::from_account_info(rent_acc)?;
+        check_program_config(program_id, program_config_acc, creator_acc, quote_mint_acc.key)?;
+
+        check_assert!(market_series_acc.owner == program_id)?;
+        check_assert!(rent.is_exempt(market_series_acc.lamports(), size_of::<MarketSeries>()))?;
+        check_assert!(details.len() <= DETAILS_BUFFER_LEN)?;
+        check_assert!(num_outcomes >= 2 && num_outcomes <= MAX_OUTCOMES as u64)?;
+        check_assert!(period > 0)?;
+
+        let mut market_series = MarketSeries::load_mut(market_series_acc)?;
+        check_assert!(market_series.account_flags == 0)?;
+
+        market_series.account_flags = (AccountFlag::Initialized | AccountFlag::MarketSeries).bits();
+        market_series.oracle = *oracle_acc.key;
+        market_series.quote_mint = *quote_mint_acc.key;
+        market_series.num_outcomes = num_outcomes;
+        market_series.first_exp_time = first_exp_time;
+        market_series.period = period;
+        market_series.resolve_window = resolve_window;
+        market_series.next_index = 0;
+        market_series.details_len = details.len() as u64;
+        market_series.details[..details.len()].copy_from_slice(details);
+
+        Ok(())
+    }
+
+    fn create_series_contract(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        contract_nonce: u64,
+        signer_nonce: u64
+    ) -> OmegaResult<()> {
+        const NUM_FIXED: usize = 8;
+        let (fixed_accs, outcome_accs) = array_refs![accounts, NUM_FIXED; ..;];
+        let [
+            market_series_acc,
+            omega_contract_acc,
+            payer_acc,
+            quote_mint_acc,
+            vault_acc,
+            signer_acc,
+            system_program_acc,
+            rent_acc
+        ] = fixed_accs;
+
+        let mut market_series = MarketSeries::load_mut(market_series_acc)?;
+        check_assert!(market_series.account_flags == (AccountFlag::Initialized | AccountFlag::MarketSeries).bits())?;
+        check_assert!(market_series_acc.owner == program_id)?;
+        check_assert!(*quote_mint_acc.key == market_series.quote_mint)?;
+        check_assert!(outcome_accs.len() == market_series.num_outcomes as usize)?;
+        check_assert!(*system_program_acc.key == solana_program::system_program::ID)?;
+
+        let index = market_series.next_index;
+        let (exp_time, auto_exp_time) = market_series.exp_times(index).ok_or(assertion_error!())?;
+        let details = market_series.details_for(index, exp_time)?;
+
+        let index_bytes = index.to_le_bytes();
+        let contract_seeds = [market_series_acc.key.as_ref(), &index_bytes, bytes_of(&contract_nonce)];
+        let contract_key = Pubkey::create_program_address(&contract_seeds, program_id).map_err(ProgramError::from)?;
+        check_assert!(contract_key == *omega_contract_acc.key)?;
+
+        let rent = Rent::from_account_info(rent_acc)?;
+        create_pda_account::<OmegaContract>(
+            program_id, payer_acc, omega_contract_acc, system_program_acc, &rent, &contract_seeds
+        )?;
+
+        init_contract(
+            program_id,
+            omega_contract_acc,
+            &market_series.oracle,
+            quote_mint_acc,
+            vault_acc,
+            signer_acc,
+            outcome_accs,
+            exp_time,
+            auto_exp_time,
+            signer_nonce,
+            &details
+        )?;
+        market_series.next_index = math::add(market_series.next_index, 1)?;
+
+        msg!("index: {}", index);
+        Ok(())
+    }
+
+    fn init_registry(program_id: &Pubkey, accounts: &[AccountInfo]) -> OmegaResult<()> {
+        let accounts = array_ref![accounts, 0, 3];
+        let [
+            registry_acc,
+            creator_acc,  // signer
+            rent_acc
+        ] = accounts;
+
+        let rent = Rent::from_account_info(rent_acc)?;
+        check_assert!(registry_acc.owner == program_id)?;
+        check_assert!(rent.is_exempt(registry_acc.lamports(), size_of::<Registry>()))?;
+        check_assert!(creator_acc.is_signer)?;
+
+        let mut registry = Registry::load_mut(registry_acc)?;
+        check_assert!(registry.account_flags == 0)?;
+        registry.account_flags = (AccountFlag::Initialized | AccountFlag::Registry).bits();
+        registry.creator = *creator_acc.key;
+
+        Ok(())
+    }
+
+    fn update_registry_entry(program_id: &Pubkey, accounts: &[AccountInfo], category: u64) -> OmegaResult<()> {
+        let accounts = array_ref![accounts, 0, 3];
+        let [
+            registry_acc,
+            omega_contract_acc,
+            oracle_acc,  // signer
+        ] = accounts;
+
+        let omega_contract = OmegaContract::load(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+        check_assert!(omega_contract.oracle == *oracle_acc.key)?;
+        check_assert!(oracle_acc.is_signer)?;
+
+        let mut registry = Registry::load_mut(registry_acc)?;
+        check_assert!(registry.account_flags == (AccountFlag::Initialized | AccountFlag::Registry).bits())?;
+        check_assert!(registry_acc.owner == program_id)?;
+        let i = registry.find(omega_contract_acc.key).ok_or(assertion_error!())?;
+
+        let status = if omega_contract.winner == Pubkey::default() {
+            ContractStatus::Open
+        } else {
+            ContractStatus::Resolved
+        };
+        registry.entries[i].category = category;
+        registry.entries[i].status = status.into();
+
+        Ok(())
+    }
+
+    fn close_contract(program_id: &Pubkey, accounts: &[AccountInfo]) -> OmegaResult<()> {
+        let accounts = array_ref![accounts, 0, 5];
+        let [
+            omega_contract_acc,
+            registry_acc,
+            creator_acc,  // signer
+            vault_acc,
+            clock_acc
+        ] = accounts;
+
+        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+        check_assert!(*vault_acc.key == omega_contract.vault)?;
+        let clock = solana_program::clock::Clock::from_account_info(clock_acc)?;
+        check_assert!(omega_contract.winner != Pubkey::default() || unix_time(&clock)? >= omega_contract.auto_exp_time)?;
+
+        // nothing may be left to redeem, withdraw or release
+        check_assert!(omega_contract.open_interest() == 0)?;
+        check_assert!(Account::unpack(&vault_acc.try_borrow_data()?)?.amount == 0)?;
+        check_assert!(omega_contract.bond.status != BondStatus::Posted as u64)?;
+        check_assert!(omega_contract.creator_bond.status != BondStatus::Posted as u64)?;
+
+        let mut registry = Registry::load_mut(registry_acc)?;
+        check_assert!(registry.account_flags == (AccountFlag::Initialized | AccountFlag::Registry).bits())?;
+        check_assert!(registry_acc.owner == program_id)?;
+        check_assert!(registry.creator == *creator_acc.key)?;
+        check_assert!(creator_acc.is_signer)?;
+        // the entry shows the contract was made by this creator
+        let i = registry.find(omega_contract_acc.key).ok_or(assertion_error!())?;
+        registry.remove(i);
+
+        // close the contract, giving the rent back to the creator
+        *omega_contract = OmegaContract::zeroed();
+        let lamports = math::add(creator_acc.lamports(), omega_contract_acc.lamports())?;
+        **omega_contract_acc.try_borrow_mut_lamports()? = 0;
+        **creator_acc.try_borrow_mut_lamports()? = lamports;
+
+        Ok(())
+    }
+
+    fn init_program_config(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        timelock: u64,
+        fee_bps: u64,
+        restrict_creators: u64,
+        num_quote_mints: u64,
+        creator_bond: u64
+    ) -> OmegaResult<()> {
+        const NUM_FIXED: usize = 4;
+        let (fixed_accs, settings_accs) = array_refs![accounts, NUM_FIXED; ..;];
+        let [
+            program_config_acc,
+            admin_acc,  // signer
+            system_program_acc,
+            rent_acc
+        ] = fixed_accs;
+
+        check_assert!(admin_acc.is_signer)?;
+        check_assert!(*system_program_acc.key == solana_program::system_program::ID)?;
+        let settings = read_config_settings(
+            admin_acc.key, timelock, fee_bps, restrict_creators, num_quote_mints, creator_bond, settings_accs
+        )?;
+
+        // the first valid nonce, so that there is only one config
+        let (program_config_key, nonce) = find_program_config_address(program_id);
+        check_assert!(program_config_key == *program_config_acc.key)?;
+        let nonce_bytes = nonce.to_le_bytes();
+        let seeds = [PROGRAM_CONFIG_SEED, &nonce_bytes];
+        let rent = Rent::from_account_info(rent_acc)?;
+        create_pda_account::<ProgramConfig>(program_id, admin_acc, program_config_acc, system_program_acc, &rent, &seeds)?;
+
+        let mut program_config = ProgramConfig::load_mut(program_config_acc)?;
+        check_assert!(program_config.account_flags == 0)?;
+        program_config.account_flags = (AccountFlag::Initialized | AccountFlag::ProgramConfig).bits();
+        program_config.nonce = nonce;
+        program_config.settings = settings;
+
+        Ok(())
+    }
+
+    fn propose_config(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        timelock: u64,
+        fee_bps: u64,
+        restrict_creators: u64,
+        num_quote_mints: u64,
+        creator_bond: u64
+    ) -> OmegaResult<()> {
+        const NUM_FIXED: usize = 4;
+        let (fixed_accs, settings_accs) = array_refs![accounts, NUM_FIXED; ..;];
+        let [
+            program_config_acc,
+            admin_acc,  // signer
+            new_admin_acc,
+            clock_acc
+        ] = fixed_accs;
+
+        let mut program_config = ProgramConfig::load_mut(program_config_acc)?;
+        check_assert!(program_config.account_flags == (AccountFlag::Initialized | AccountFlag::ProgramConfig).bits())?;
+        check_assert!(program_config_acc.owner == program_id)?;
+        check_assert!(program_config.settings.admin == *admin_acc.key)?;
+        check_assert!(admin_acc.is_signer)?;
+
+        let clock = solana_program::clock::Clock::from_account_info(clock_acc)?;
+        let curr_time = unix_time(&clock)?;
+
+        program_config.pending = read_config_settings(
+            new_admin_acc.key, timelock, fee_bps, restrict_creators, num_quote_mints, creator_bond, settings_accs
+        )?;
+        program_config.apply_time = math::add(curr_time, program_config.settings.timelock)?;
+        msg!("apply_time: {}", program_config.apply_time);
+
+        Ok(())
+    }
+
+    fn apply_config(program_id: &Pubkey, accounts: &[AccountInfo]) -> OmegaResult<()> {
+        let accounts = array_ref![accounts, 0, 2];
+        let [
+            program_config_acc,
+            clock_acc
+        ] = accounts;
+
+        let mut program_config = ProgramConfig::load_mut(program_config_acc)?;
+        check_assert!(program_config.account_flags == (AccountFlag::Initialized | AccountFlag::ProgramConfig).bits())?;
+        check_assert!(program_config_acc.owner == program_id)?;
+        check_assert!(program_config.apply_time != 0)?;
+
+        let clock = solana_program::clock::Clock::from_account_info(clock_acc)?;
+        let curr_time = unix_time(&clock)?;
+        if curr_time < program_config.apply_time {
+            return Err(OmegaErrorCode::TimelockActive.into());
+        }
+
+        program_config.settings = program_config.pending;
+        program_config.pending = ConfigSettings::zeroed();
+        program_config.apply_time = 0;
+
+        Ok(())
+    }
+
+    fn init_price_resolution(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        source: u64,
+        comparison: u64,
+        threshold: i64,
+        max_staleness: u64
+    ) -> OmegaResult<()> {
+        let accounts = array_ref![accounts, 0, 4];
+        let [
+            omega_contract_acc,
+            oracle_acc,  // signer
+            price_acc,
+            price_program_acc
+        ] = accounts;
+
+        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+        check_assert!(omega_contract.oracle == *oracle_acc.key)?;
+        check_assert!(oracle_acc.is_signer)?;
+        check_assert!(omega_contract.num_outcomes == 2)?;
+        check_assert!(omega_contract.num_breakpoints == 0)?;
+        check_assert!(omega_contract.winner == Pubkey::default())?;
+        check_assert!(!omega_contract.vote.is_enabled())?;
+        check_assert!(omega_contract.price_resolution.price_acc == Pubkey::default())?;
+        check_assert!(*price_acc.key != Pubkey::default())?;
+        check_assert!(price_acc.owner == price_program_acc.key)?;
+        check_assert!(price_program_acc.executable)?;
+        check_assert!(Comparison::try_from(comparison).is_ok())?;
+
+        // make sure the price account can actually be read
+        let source_kind = PriceSourceKind::try_from(source).map_err(|_| assertion_error!())?;
+        source_kind.read_price(&price_acc.try_borrow_data()?)?;
+
+        omega_contract.price_resolution = PriceResolution {
+            price_acc: *price_acc.key,
+            price_program: *price_program_acc.key,
+            source,
+            comparison,
+            threshold,
+            max_staleness
+        };
+
+        Ok(())
+    }
+
+    fn resolve_from_price(program_id: &Pubkey, accounts: &[AccountInfo]) -> OmegaResult<()> {
+        const NUM_FIXED: usize = 3;
+        let (fixed_accs, tail_accs) = array_refs![accounts, NUM_FIXED; ..;];
+        let [
+            omega_contract_acc,
+            price_acc,
+            clock_acc
+        ] = fixed_accs;
+
+        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+        let resolution = omega_contract.price_resolution;
+        check_assert!(resolution.price_acc != Pubkey::default())?;
+        check_assert!(resolution.price_acc == *price_acc.key)?;
+        check_assert!(resolution.price_program == *price_acc.owner)?;
+        check_assert!(tail_accs.len() > omega_contract.num_outcomes)?;
+        let (solvency_accs, reputation_accs) = tail_accs.split_at(omega_contract.num_outcomes + 1);
+
+        let clock = solana_program::clock::Clock::from_account_info(clock_acc)?;
+        let curr_time = unix_time(&clock)?;
+        check_assert!(omega_contract.exp_time <= curr_time)?;
+        check_assert!(curr_time < omega_contract.auto_exp_time)?;
+        check_assert!(omega_contract.winner == Pubkey::default())?;
+        check_assert!(omega_contract.commitment == [0u8; 32])?;
+
+        let source_kind = PriceSourceKind::try_from(resolution.source).map_err(|_| assertion_error!())?;
+        let price = source_kind.read_price(&price_acc.try_borrow_data()?)?;
+        let exp_time = omega_contract.exp_time;
+        if price.timestamp.saturating_add(resolution.max_staleness) < exp_time
+            || price.timestamp > exp_time.saturating_add(resolution.max_staleness) {
+            return Err(OmegaErrorCode::StalePrice.into());
+        }
+
+        let comparison = Comparison::try_from(resolution.comparison).map_err(|_| assertion_error!())?;
+        let winner = if comparison.holds(price.price, resolution.threshold) { 0 } else { 1 };
+        let winner_key = omega_contract.outcomes[winner];
+        // the oracle picked the price account, so the resolution counts for it
+        let oracle = omega_contract.oracle;
+        omega_contract.set_oracle_winner(winner_key, oracle, clock.slot, curr_time);
+        omega_contract.resolved_value = price.price;
+        msg!("price: {} winner: {}", price.price, winner);
+
+        check_assert!(reputation_accs.len() <= 1)?;
+        if let Some(oracle_reputation_acc) = reputation_accs.first() {
+            record_reputation(program_id, &mut omega_contract, oracle_reputation_acc)?;
+        }
+
+        check_solvency_accounts(&omega_contract, solvency_accs)
+    }
+
+    fn resolve_with_attestation(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        winner: u64,
+        timestamp: i64
+    ) -> OmegaResult<()> {
+        const NUM_FIXED: usize = 3;
+        let (fixed_accs, solvency_accs) = array_refs![accounts, NUM_FIXED; ..;];
+        let [
+            omega_contract_acc,
+            instructions_acc,
+            clock_acc
+        ] = fixed_accs;
+
+        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+        check_assert!(*instructions_acc.key == solana_program::sysvar::instructions::ID)?;
+        check_assert!((winner as usize) < omega_contract.num_outcomes)?;
+
+        let clock = solana_program::clock::Clock::from_account_info(clock_acc)?;
+        let curr_time = unix_time(&clock)?;
+        check_assert!(omega_contract.exp_time <= curr_time)?;
+        check_assert!(curr_time < omega_contract.auto_exp_time)?;
+        check_assert!(omega_contract.winner == Pubkey::default())?;
+        check_assert!(omega_contract.commitment == [0u8; 32])?;
+        check_assert!(!omega_contract.vote.is_enabled())?;
+        check_assert!(omega_contract.price_resolution.price_acc == Pubkey::default())?;
+        let attested_time = math::timestamp(timestamp)?;
+        check_assert!(attested_time >= omega_contract.exp_time && attested_time <= curr_time)?;
+
+        let instructions = instructions_acc.try_borrow_data()?;
+        let current_index = solana_program::sysvar::instructions::load_current_index(&instructions);
+        check_assert!(current_index > 0)?;
+        let verify_instruction = solana_program::sysvar::instructions::load_instruction_at(
+            current_index as usize - 1, &instructions
+        ).map_err(|_| assertion_error!())?;
+        check_assert!(verify_instruction.program_id == ed25519_program::ID)?;
+
+        let expected = attestation_message(omega_contract_acc.key, winner, timestamp);
+        match parse_ed25519_instruction(&verify_instruction.data) {
+            Some((signer, message)) if signer == omega_contract.resolving_oracle(curr_time) && message == expected.as_slice() => {}
+            _ => return Err(OmegaErrorCode::InvalidAttestation.into())
+        }
+
+        let winner_key = omega_contract.outcomes[winner as usize];
+        let resolver = omega_contract.resolving_oracle(curr_time);
+        omega_contract.set_oracle_winner(winner_key, resolver, clock.slot, curr_time);
+
+        check_solvency_accounts(&omega_contract, solvency_accs)
+    }
+
+    fn commit_resolution(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        commitment: [u8; 32],
+        reveal_window: u64
+    ) -> OmegaResult<()> {
+        let accounts = array_ref![accounts, 0, 3];
+        let [
+            omega_contract_acc,
+            oracle_acc,  // signer
+            clock_acc
+        ] = accounts;
+
+        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+        let clock = solana_program::clock::Clock::from_account_info(clock_acc)?;
+        let curr_time = unix_time(&clock)?;
+        check_assert!(omega_contract.resolving_oracle(curr_time) == *oracle_acc.key)?;
+        check_assert!(oracle_acc.is_signer)?;
+        check_assert!(!omega_contract.vote.is_enabled())?;
+        check_assert!(omega_contract.price_resolution.price_acc == Pubkey::default())?;
+
+        check_assert!(omega_contract.exp_time <= curr_time)?;
+        check_assert!(curr_time < omega_contract.auto_exp_time)?;
+        check_assert!(omega_contract.winner == Pubkey::default())?;
+        check_assert!(omega_contract.commitment == [0u8; 32])?;
+        check_assert!(commitment != [0u8; 32])?;
+        check_assert!(reveal_window > 0)?;
+
+        // if the oracle doesn't reveal in time, the contract auto expires
+        omega_contract.commitment = commitment;
+        omega_contract.resolver = *oracle_acc.key;
+        omega_contract.auto_exp_time = min(omega_contract.auto_exp_time, curr_time.saturating_add(reveal_window));
+        msg!("reveal deadline: {}", omega_contract.auto_exp_time);
+
+        Ok(())
+    }
+
+    fn reveal_resolution(program_id: &Pubkey, accounts: &[AccountInfo], salt: [u8; 32]) -> OmegaResult<()> {
+        const NUM_FIXED: usize = 3;
+        let (fixed_accs, solvency_accs) = array_refs![accounts, NUM_FIXED; ..;];
+        let [
+            omega_contract_acc,
+            winner_acc,
+            clock_acc
+        ] = fixed_accs;
+
+        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+        let clock = solana_program::clock::Clock::from_account_info(clock_acc)?;
+        let curr_time = unix_time(&clock)?;
+
+        check_assert!(curr_time < omega_contract.auto_exp_time)?;
+        check_assert!(omega_contract.winner == Pubkey::default())?;
+        check_assert!(omega_contract.commitment != [0u8; 32])?;
+        check_assert!(omega_contract.price_resolution.price_acc == Pubkey::default())?;
+
+        let winner = *winner_acc.key;
+        if commitment_hash(&winner, &salt) != omega_contract.commitment {
+            return Err(OmegaErrorCode::InvalidReveal.into());
+        }
+        if !omega_contract.outcomes[..omega_contract.num_outcomes].contains(&winner) {
+            return Err(OmegaErrorCode::InvalidWinner.into());
+        }
+        let resolver = omega_contract.resolver;
+        omega_contract.set_oracle_winner(winner, resolver, clock.slot, curr_time);
+
+        check_solvency_accounts(&omega_contract, solvency_accs)
+    }
+
+    fn resolve_late(program_id: &Pubkey, accounts: &[AccountInfo]) -> OmegaResult<()> {
+        const NUM_FIXED: usize = 5;
+        let (fixed_accs, bond_accs) = array_refs![accounts, NUM_FIXED; ..;];
+        let [
+            omega_contract_acc,
+            program_config_acc,
+            admin_acc,  // signer
+            winner_acc,
+            clock_acc
+        ] = fixed_accs;
+
+        let program_config = ProgramConfig::load(program_config_acc)?;
+        check_assert!(program_config.account_flags == (AccountFlag::Initialized | AccountFlag::ProgramConfig).bits())?;
+        check_assert!(program_config_acc.owner == program_id)?;
+        check_assert!(program_config.settings.admin == *admin_acc.key)?;
+        check_assert!(admin_acc.is_signer)?;
+
+        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+        check_assert!(omega_contract.winner == Pubkey::default())?;
+        let clock = solana_program::clock::Clock::from_account_info(clock_acc)?;
+        let curr_time = unix_time(&clock)?;
+        check_assert!(omega_contract.auto_exp_time <= curr_time)?;
+        check_assert!(curr_time < omega_contract.auto_exp_time.saturating_add(LATE_RESOLUTION_WINDOW))?;
+
+        // once someone has been paid out at the auto expiry rate, the vault can't cover a winner
+        check_assert!(omega_contract.auto_exp_redemptions == 0)?;
+
+        let winner = *winner_acc.key;
+        if !omega_contract.outcomes[..omega_contract.num_outcomes].contains(&winner) {
+            return Err(OmegaErrorCode::InvalidWinner.into());
+        }
+        omega_contract.set_winner(winner, clock.slot, curr_time);
+        omega_contract.oracle_events |= OracleEvent::MissedDeadline as u64;
+
+        // The oracle missed the window, so its bond goes to the holders of the winner
+        let supply = Mint::unpack(&winner_acc.try_borrow_data()?)?.supply;
+        if omega_contract.bond.status == BondStatus::Slashed as u64 {
+            // the bond is in the vault already and no tokens move, so the vault isn't passed to be checked
+            omega_contract.bond.slashed_supply = supply;
+        } else if omega_contract.bond.status == BondStatus::Posted as u64 {
+            check_assert!(bond_accs.len() == 4)?;
+            let [
+                escrow_acc,
+                vault_acc,
+                spl_token_program_acc,
+                omega_signer_acc
+            ] = array_ref![bond_accs, 0, 4];
+            slash_bond(
+                &mut omega_contract, omega_contract_acc.key, escrow_acc, vault_acc,
+                spl_token_program_acc, omega_signer_acc, supply
+            )?;
+            if let Some(supplies) = resolved_supplies(&omega_contract, winner_acc)? {
+                check_vault_solvency(&omega_contract, vault_acc, &supplies)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn init_oracle_liveness(program_id: &Pubkey, accounts: &[AccountInfo], heartbeat_period: u64) -> OmegaResult<()> {
+        let accounts = array_ref![accounts, 0, 4];
+        let [
+            omega_contract_acc,
+            oracle_acc,  // signer
+            backup_oracle_acc,
+            clock_acc
+        ] = accounts;
+
+        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+        check_assert!(omega_contract.oracle == *oracle_acc.key)?;
+        check_assert!(oracle_acc.is_signer)?;
+        check_assert!(omega_contract.liveness.backup_oracle == Pubkey::default())?;
+        check_assert!(*backup_oracle_acc.key != Pubkey::default() && *backup_oracle_acc.key != omega_contract.oracle)?;
+        check_assert!(heartbeat_period > 0)?;
+        let clock = solana_program::clock::Clock::from_account_info(clock_acc)?;
+        let curr_time = unix_time(&clock)?;
+        check_assert!(curr_time < omega_contract.exp_time)?;
+
+        omega_contract.liveness = OracleLiveness {
+            backup_oracle: *backup_oracle_acc.key,
+            heartbeat_period,
+            last_heartbeat: curr_time
+        };
+
+        Ok(())
+    }
+
+    fn oracle_heartbeat(program_id: &Pubkey, accounts: &[AccountInfo]) -> OmegaResult<()> {
+        let accounts = array_ref![accounts, 0, 3];
+        let [
+            omega_contract_acc,
+            oracle_acc,  // signer
+            clock_acc
+        ] = accounts;
+
+        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+        check_assert!(omega_contract.oracle == *oracle_acc.key)?;
+        check_assert!(oracle_acc.is_signer)?;
+        check_assert!(omega_contract.winner == Pubkey::default())?;
+        let clock = solana_program::clock::Clock::from_account_info(clock_acc)?;
+        let curr_time = unix_time(&clock)?;
+
+        // the backup keeps resolution rights once it has them
+        if omega_contract.resolving_oracle(curr_time) != omega_contract.oracle {
+            return Err(OmegaErrorCode::OracleLapsed.into());
+        }
+        omega_contract.liveness.last_heartbeat = curr_time;
+
+        Ok(())
+    }
+
+    fn init_oracle_reputation(program_id: &Pubkey, accounts: &[AccountInfo]) -> OmegaResult<()> {
+        let accounts = array_ref![accounts, 0, 5];
+        let [
+            oracle_reputation_acc,
+            oracle_acc,
+            payer_acc,  // signer
+            system_program_acc,
+            rent_acc
+        ] = accounts;
+
+        check_assert!(payer_acc.is_signer)?;
+        check_assert!(*system_program_acc.key == solana_program::system_program::ID)?;
+
+        // the first valid nonce, so that each oracle has only one reputation
+        let (oracle_reputation_key, nonce) = find_oracle_reputation_address(program_id, oracle_acc.key);
+        check_assert!(oracle_reputation_key == *oracle_reputation_acc.key)?;
+        let nonce_bytes = nonce.to_le_bytes();
+        let seeds = [ORACLE_REPUTATION_SEED, oracle_acc.key.as_ref(), &nonce_bytes];
+        let rent = Rent::from_account_info(rent_acc)?;
+        create_pda_account::<OracleReputation>(
+            program_id, payer_acc, oracle_reputation_acc, system_program_acc, &rent, &seeds
+        )?;
+
+        let mut oracle_reputation = OracleReputation::load_mut(oracle_reputation_acc)?;
+        check_assert!(oracle_reputation.account_flags == 0)?;
+        oracle_reputation.account_flags = (AccountFlag::Initialized | AccountFlag::OracleReputation).bits();
+        oracle_reputation.oracle = *oracle_acc.key;
+        oracle_reputation.nonce = nonce;
+
+        Ok(())
+    }
+
+    /// Permissionless: count the events of a contract that are not in the oracle's reputation yet
+    fn record_oracle_reputation(program_id: &Pubkey, accounts: &[AccountInfo]) -> OmegaResult<()> {
+        let accounts = array_ref![accounts, 0, 3];
+        let [
+            omega_contract_acc,
+            oracle_reputation_acc,
+            clock_acc
+        ] = accounts;
+
+        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+
+        let clock = solana_program::clock::Clock::from_account_info(clock_acc)?;
+        let curr_time = unix_time(&clock)?;
+        if omega_contract.winner == Pubkey::default() && curr_time >= omega_contract.auto_exp_time {
+            omega_contract.oracle_events |= OracleEvent::MissedDeadline as u64;
+        }
+
+        record_reputation(program_id, &mut omega_contract, oracle_reputation_acc)
+    }
+
+    fn init_vote_resolution(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        voting_period: u64,
+        quorum: u64,
+        tie_break: u64,
+        tie_break_outcome: u64
+    ) -> OmegaResult<()> {
+        let accounts = array_ref![accounts, 0, 5];
+        let [
+            omega_contract_acc,
+            oracle_acc,  // signer
+            gov_mint_acc,
+            escrow_acc,
+            clock_acc
+        ] = accounts;
+
+        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+        check_assert!(omega_contract.oracle == *oracle_acc.key)?;
+        check_assert!(oracle_acc.is_signer)?;
+        check_assert!(!omega_contract.vote.is_enabled())?;
+        check_assert!(omega_contract.price_resolution.price_acc == Pubkey::default())?;
+        let clock = solana_program::clock::Clock::from_account_info(clock_acc)?;
+        let curr_time = unix_time(&clock)?;
+        check_assert!(curr_time < omega_contract.exp_time)?;
+
+        // the vote has to be over before the contract auto expires
+        check_assert!(voting_period > 0)?;
+        check_assert!(omega_contract.exp_time.saturating_add(voting_period) < omega_contract.auto_exp_time)?;
+        match TieBreak::try_from(tie_break).map_err(|_| assertion_error!())? {
+            TieBreak::Designated => check_assert!((tie_break_outcome as usize) < omega_contract.num_outcomes)?,
+            _ => check_assert!(tie_break_outcome == 0)?
+        }
+
+        Mint::unpack(&gov_mint_acc.try_borrow_data()?)?;
+        let signer_key = gen_signer_key(omega_contract.signer_nonce, omega_contract_acc.key, program_id)?;
+        let escrow = Account::unpack(&escrow_acc.try_borrow_data()?)?;
+        check_assert!(escrow.owner == signer_key)?;
+        check_assert!(escrow.mint == *gov_mint_acc.key)?;
+        check_assert!(*escrow_acc.key != omega_contract.vault && *escrow_acc.key != omega_contract.bond.escrow)?;
+        check_assert!(*escrow_acc.key != omega_contract.creator_bond.escrow)?;
+
+        omega_contract.vote = VoteResolution {
+            gov_mint: *gov_mint_acc.key,
+            escrow: *escrow_acc.key,
+            voting_period,
+            quorum,
+            tie_break,
+            tie_break_outcome,
+            total_votes: 0,
+            tallies: [0; MAX_OUTCOMES]
+        };
+
+        Ok(())
+    }
+
+    fn cast_vote(program_id: &Pubkey, accounts: &[AccountInfo], outcome: u64, amount: u64) -> OmegaResult<()> {
+        let accounts = array_ref![accounts, 0, 9];
+        let [
+            omega_contract_acc,
+            vote_record_acc,
+            voter_acc,  // signer
+            voter_gov_acc,
+            escrow_acc,
+            spl_token_program_acc,
+            system_program_acc,
+            rent_acc,
+            clock_acc
+        ] = accounts;
+
+        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+        check_assert!(omega_contract.vote.is_enabled())?;
+        check_assert!(*escrow_acc.key == omega_contract.vote.escrow)?;
+        check_assert!(voter_acc.is_signer)?;
+        check_assert!((outcome as usize) < omega_contract.num_outcomes)?;
+        check_assert!(amount > 0)?;
+        let clock = solana_program::clock::Clock::from_account_info(clock_acc)?;
+        let curr_time = unix_time(&clock)?;
+        check_assert!(omega_contract.exp_time <= curr_time)?;
+        check_assert!(curr_time < omega_contract.vote.voting_end(omega_contract.exp_time))?;
+
+        let (vote_record_key, nonce) = find_vote_record_address(program_id, omega_contract_acc.key, voter_acc.key);
+        check_assert!(vote_record_key == *vote_record_acc.key)?;
+        if vote_record_acc.data_is_empty() {
+            check_assert!(*system_program_acc.key == solana_program::system_program::ID)?;
+            let nonce_bytes = nonce.to_le_bytes();
+            let seeds = [VOTE_RECORD_SEED, omega_contract_acc.key.as_ref(), voter_acc.key.as_ref(), &nonce_bytes];
+            let rent = Rent::from_account_info(rent_acc)?;
+            create_pda_account::<VoteRecord>(
+                program_id, voter_acc, vote_record_acc, system_program_acc, &rent, &seeds
+            )?;
+
+            let mut vote_record = VoteRecord::load_mut(vote_record_acc)?;
+            vote_record.account_flags = (AccountFlag::Initialized | AccountFlag::VoteRecord).bits();
+            vote_record.omega_contract = *omega_contract_acc.key;
+            vote_record.voter = *voter_acc.key;
+            vote_record.nonce = nonce;
+            vote_record.outcome = outcome;
+        }
+
+        let mut vote_record = VoteRecord::load_mut(vote_record_acc)?;
+        check_assert!(vote_record.account_flags == (AccountFlag::Initialized | AccountFlag::VoteRecord).bits())?;
+        check_assert!(vote_record_acc.owner == program_id)?;
+        check_assert!(vote_record.outcome == outcome)?;
+
+        let deposit_instruction = spl_token::instruction::transfer(
+            spl_token_program_acc.key,
+            voter_gov_acc.key,
+            escrow_acc.key,
+            voter_acc.key,
+            &[],
+            amount
+        )?;
+        let deposit_accs = [
+            voter_gov_acc.clone(),
+            escrow_acc.clone(),
+            voter_acc.clone(),
+            spl_token_program_acc.clone()
+        ];
+        invoke(&deposit_instruction, &deposit_accs)?;
+
+        vote_record.amount = math::add(vote_record.amount, amount)?;
+        omega_contract.vote.tallies[outcome as usize] = math::add(omega_contract.vote.tallies[outcome as usize], amount)?;
+        omega_contract.vote.total_votes = math::add(omega_contract.vote.total_votes, amount)?;
+
+        Ok(())
+    }
+
+    /// Permissionless: the tally picks the winner once the voting period is over
+    fn finalize_vote(program_id: &Pubkey, accounts: &[AccountInfo]) -> OmegaResult<()> {
+        const NUM_FIXED: usize = 2;
+        let (fixed_accs, solvency_accs) = array_refs![accounts, NUM_FIXED; ..;];
+        let [
+            omega_contract_acc,
+            clock_acc
+        ] = fixed_accs;
+
+        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+        check_assert!(omega_contract.vote.is_enabled())?;
+        check_assert!(omega_contract.winner == Pubkey::default())?;
+        let clock = solana_program::clock::Clock::from_account_info(clock_acc)?;
+        let curr_time = unix_time(&clock)?;
+        check_assert!(omega_contract.vote.voting_end(omega_contract.exp_time) <= curr_time)?;
+        check_assert!(curr_time < omega_contract.auto_exp_time)?;
+
+        msg!("votes: {} of quorum {}", omega_contract.vote.total_votes, omega_contract.vote.quorum);
+        let winner = omega_contract.vote.winner(omega_contract.num_outcomes).ok_or(OmegaErrorCode::VoteInconclusive)?;
+        let winner_key = omega_contract.outcomes[winner];
+        omega_contract.set_winner(winner_key, clock.slot, curr_time);
+
+        check_solvency_accounts(&omega_contract, solvency_accs)
+    }
+
+    fn withdraw_vote(program_id: &Pubkey, accounts: &[AccountInfo]) -> OmegaResult<()> {
+        let accounts = array_ref![accounts, 0, 8];
+        let [
+            omega_contract_acc,
+            vote_record_acc,
+            voter_acc,  // signer
+            voter_gov_acc,
+            escrow_acc,
+            spl_token_program_acc,
+            omega_signer_acc,
+            clock_acc
+        ] = accounts;
+
+        let omega_contract = OmegaContract::load(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+        check_assert!(*escrow_acc.key == omega_contract.vote.escrow)?;
+        let clock = solana_program::clock::Clock::from_account_info(clock_acc)?;
+        let curr_time = unix_time(&clock)?;
+        check_assert!(omega_contract.vote.voting_end(omega_contract.exp_time) <= curr_time)?;
+
+        let mut vote_record = VoteRecord::load_mut(vote_record_acc)?;
+        check_assert!(vote_record.account_flags == (AccountFlag::Initialized | AccountFlag::VoteRecord).bits())?;
+        check_assert!(vote_record_acc.owner == program_id)?;
+        check_assert!(vote_record.omega_contract == *omega_contract_acc.key)?;
+        check_assert!(vote_record.voter == *voter_acc.key)?;
+        check_assert!(voter_acc.is_signer)?;
+
+        let return_instruction = spl_token::instruction::transfer(
+            spl_token_program_acc.key,
+            escrow_acc.key,
+            voter_gov_acc.key,
+            omega_signer_acc.key,
+            &[],
+            vote_record.amount
+        )?;
+        let return_accs = [
+            escrow_acc.clone(),
+            voter_gov_acc.clone(),
+            omega_signer_acc.clone(),
+            spl_token_program_acc.clone()
+        ];
+        let signer_seeds = gen_signer_seeds(&omega_contract.signer_nonce, omega_contract_acc.key);
+        invoke_signed(&return_instruction, &return_accs, &[&signer_seeds])?;
+
+        // close the vote record, giving the rent back to the voter
+        *vote_record = VoteRecord::zeroed();
+        let lamports = math::add(voter_acc.lamports(), vote_record_acc.lamports())?;
+        **vote_record_acc.try_borrow_mut_lamports()? = 0;
+        **voter_acc.try_borrow_mut_lamports()? = lamports;
+
+        Ok(())
+    }
+
+    fn init_oracle_fee(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+        fee_bps: u64,
+        deadline: u64
+    ) -> OmegaResult<()> {
+        let accounts = array_ref![accounts, 0, 6];
+        let [
+            omega_contract_acc,
+            funder_acc,  // signer
+            funder_quote_acc,
+            vault_acc,
+            spl_token_program_acc,
+            clock_acc
+        ] = accounts;
+
+        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+        check_assert!(*vault_acc.key == omega_contract.vault)?;
+        check_assert!(funder_acc.is_signer)?;
+        check_assert!(omega_contract.oracle_fee.funder == Pubkey::default())?;
+        check_assert!(amount > 0)?;
+        check_assert!(fee_bps <= MAX_BPS)?;
+        check_assert!(omega_contract.exp_time < deadline && deadline <= omega_contract.auto_exp_time)?;
+        let clock = solana_program::clock::Clock::from_account_info(clock_acc)?;
+        let curr_time = unix_time(&clock)?;
+        check_assert!(curr_time < omega_contract.exp_time)?;
+
+        let deposit_instruction = spl_token::instruction::transfer(
+            spl_token_program_acc.key,
+            funder_quote_acc.key,
+            vault_acc.key,
+            funder_acc.key,
+            &[],
+            amount
+        )?;
+        let deposit_accs = [
+            funder_quote_acc.clone(),
+            vault_acc.clone(),
+            funder_acc.clone(),
+            spl_token_program_acc.clone()
+        ];
+        invoke(&deposit_instruction, &deposit_accs)?;
+
+        // without an explicit rate the reward follows the program config's default, set at init
+        let fee_bps = if fee_bps == 0 { omega_contract.oracle_fee.fee_bps } else { fee_bps };
+        omega_contract.oracle_fee = OracleFee {
+            funder: *funder_acc.key,
+            amount,
+            fee_bps,
+            deadline,
+            reserve: amount
+        };
+
+        Ok(())
+    }
+
+    fn withdraw_oracle_fee(program_id: &Pubkey, accounts: &[AccountInfo]) -> OmegaResult<()> {
+        const NUM_FIXED: usize = 6;
+        let (fixed_accs, outcome_mint_accs) = array_refs![accounts, NUM_FIXED; ..;];
+        let [
+            omega_contract_acc,
+            funder_quote_acc,
+            vault_acc,
+            spl_token_program_acc,
+            omega_signer_acc,
+            clock_acc
+        ] = fixed_accs;
+
+        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+        check_assert!(*vault_acc.key == omega_contract.vault)?;
+        check_assert!(omega_contract.oracle_fee.reserve > 0)?;
+        if omega_contract.winner == Pubkey::default() {
+            let clock = solana_program::clock::Clock::from_account_info(clock_acc)?;
+            let curr_time = unix_time(&clock)?;
+            check_assert!(curr_time >= omega_contract.auto_exp_time)?;
+        }
+
+        let funder_quote = Account::unpack(&funder_quote_acc.try_borrow_data()?)?;
+        check_assert!(funder_quote.owner == omega_contract.oracle_fee.funder)?;
+
+        let withdraw_instruction = spl_token::instruction::transfer(
+            spl_token_program_acc.key,
+            vault_acc.key,
+            funder_quote_acc.key,
+            omega_signer_acc.key,
+            &[],
+            omega_contract.oracle_fee.reserve
+        )?;
+        let withdraw_accs = [
+            vault_acc.clone(),
+            funder_quote_acc.clone(),
+            omega_signer_acc.clone(),
+            spl_token_program_acc.clone()
+        ];
+        let signer_seeds = gen_signer_seeds(&omega_contract.signer_nonce, omega_contract_acc.key);
+        invoke_signed(&withdraw_instruction, &withdraw_accs, &[&signer_seeds])?;
+        omega_contract.oracle_fee.reserve = 0;
+        let supplies = read_outcome_supplies(&omega_contract, outcome_mint_accs.iter())?;
+        check_vault_solvency(&omega_contract, vault_acc, &supplies)?;
+
+        Ok(())
+    }
+
+    fn init_oracle_bond(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+        dispute_window: u64
+    ) -> OmegaResult<()> {
+        let accounts = array_ref![accounts, 0, 6];
+        let [
+            omega_contract_acc,
+            oracle_acc,  // signer
+            oracle_quote_acc,
+            escrow_acc,
+            spl_token_program_acc,
+            clock_acc
+        ] = accounts;
+
+        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+        check_assert!(omega_contract.oracle == *oracle_acc.key)?;
+        check_assert!(oracle_acc.is_signer)?;
+        check_assert!(omega_contract.bond.status == BondStatus::None as u64)?;
+        check_assert!(amount > 0)?;
+        let clock = solana_program::clock::Clock::from_account_info(clock_acc)?;
+        let curr_time = unix_time(&clock)?;
+        check_assert!(curr_time < omega_contract.exp_time)?;
+
+        let signer_key = gen_signer_key(omega_contract.signer_nonce, omega_contract_acc.key, program_id)?;
+        let escrow = Account::unpack(&escrow_acc.try_borrow_data()?)?;
+        check_assert!(escrow.owner == signer_key)?;
+        check_assert!(escrow.mint == omega_contract.quote_mint)?;
+        check_assert!(*escrow_acc.key != omega_contract.vault && *escrow_acc.key != omega_contract.vote.escrow)?;
+        check_assert!(*escrow_acc.key != omega_contract.creator_bond.escrow)?;
+
+        let deposit_instruction = spl_token::instruction::transfer(
+            spl_token_program_acc.key,
+            oracle_quote_acc.key,
+            escrow_acc.key,
+            oracle_acc.key,
+            &[],
+            amount
+        )?;
+        let deposit_accs = [
+            oracle_quote_acc.clone(),
+            escrow_acc.clone(),
+            oracle_acc.clone(),
+            spl_token_program_acc.clone()
+        ];
+        invoke(&deposit_instruction, &deposit_accs)?;
+
+        omega_contract.bond = OracleBond {
+            escrow: *escrow_acc.key,
+            amount,
+            dispute_window,
+            status: BondStatus::Posted as u64,
+            slashed_supply: 0
+        };
+
+        Ok(())
+    }
+
+    fn release_oracle_bond(program_id: &Pubkey, accounts: &[AccountInfo]) -> OmegaResult<()> {
+        let accounts = array_ref![accounts, 0, 6];
+        let [
+            omega_contract_acc,
+            escrow_acc,
+            oracle_quote_acc,
+            spl_token_program_acc,
+            omega_signer_acc,
+            clock_acc
+        ] = accounts;
+
+        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+        check_assert!(omega_contract.bond.status == BondStatus::Posted as u64)?;
+        check_assert!(*escrow_acc.key == omega_contract.bond.escrow)?;
+        check_assert!(omega_contract.winner != Pubkey::default())?;
+        let clock = solana_program::clock::Clock::from_account_info(clock_acc)?;
+        let curr_time = unix_time(&clock)?;
+        if omega_contract.bond.is_at_stake(omega_contract.resolved_time, curr_time) {
+            return Err(OmegaErrorCode::BondAtStake.into());
+        }
+
+        let oracle_quote = Account::unpack(&oracle_quote_acc.try_borrow_data()?)?;
+        check_assert!(oracle_quote.owner == omega_contract.oracle)?;
+
+        let release_instruction = spl_token::instruction::transfer(
+            spl_token_program_acc.key,
+            escrow_acc.key,
+            oracle_quote_acc.key,
+            omega_signer_acc.key,
+            &[],
+            omega_contract.bond.amount
+        )?;
+        let release_accs = [
+            escrow_acc.clone(),
+            oracle_quote_acc.clone(),
+            omega_signer_acc.clone(),
+            spl_token_program_acc.clone()
+        ];
+        let signer_seeds = gen_signer_seeds(&omega_contract.signer_nonce, omega_contract_acc.key);
+        invoke_signed(&release_instruction, &release_accs, &[&signer_seeds])?;
+
+        omega_contract.bond.status = BondStatus::Released as u64;
+
+        Ok(())
+    }
+
+    fn slash_oracle_bond(program_id: &Pubkey, accounts: &[AccountInfo]) -> OmegaResult<()> {
+        const NUM_FIXED: usize = 6;
+        let (fixed_accs, outcome_accs) = array_refs![accounts, NUM_FIXED; ..;];
+        let [
+            omega_contract_acc,
+            escrow_acc,
+            vault_acc,
+            spl_token_program_acc,
+            omega_signer_acc,
+            clock_acc
+        ] = fixed_accs;
+
+        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+        check_assert!(omega_contract.winner == Pubkey::default())?;
+        let clock = solana_program::clock::Clock::from_account_info(clock_acc)?;
+        let curr_time = unix_time(&clock)?;
+        check_assert!(curr_time >= omega_contract.auto_exp_time)?;
+
+        // every outcome redeems at the auto expiry rate, so the bond is split over all of them
+        let supplies = read_outcome_supplies(&omega_contract, outcome_accs.iter())?;
+        let supply = math::sum(&supplies)?;
+
+        slash_bond(
+            &mut omega_contract, omega_contract_acc.key, escrow_acc, vault_acc,
+            spl_token_program_acc, omega_signer_acc, supply
+        )?;
+        check_vault_solvency(&omega_contract, vault_acc, &supplies)
+    }
+
+    fn overturn_resolution(program_id: &Pubkey, accounts: &[AccountInfo]) -> OmegaResult<()> {
+        let accounts = array_ref![accounts, 0, 9];
+        let [
+            omega_contract_acc,
+            program_config_acc,
+            admin_acc,  // signer
+            winner_acc,
+            escrow_acc,
+            vault_acc,
+            spl_token_program_acc,
+            omega_signer_acc,
+            clock_acc
+        ] = accounts;
+
+        let program_config = ProgramConfig::load(program_config_acc)?;
+        check_assert!(program_config.account_flags == (AccountFlag::Initialized | AccountFlag::ProgramConfig).bits())?;
+        check_assert!(program_config_acc.owner == program_id)?;
+        check_assert!(program_config.settings.admin == *admin_acc.key)?;
+        check_assert!(admin_acc.is_signer)?;
+
+        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+        check_assert!(omega_contract.winner != Pubkey::default())?;
+        let clock = solana_program::clock::Clock::from_account_info(clock_acc)?;
+        let curr_time = unix_time(&clock)?;
+
+        // without a bond, or after the dispute window, holders may already have redeemed
+        check_assert!(omega_contract.bond.is_at_stake(omega_contract.resolved_time, curr_time))?;
+
+        let winner = *winner_acc.key;
+        if winner == omega_contract.winner || !omega_contract.outcomes[..omega_contract.num_outcomes].contains(&winner) {
+            return Err(OmegaErrorCode::InvalidWinner.into());
+        }
+        let supply = Mint::unpack(&winner_acc.try_borrow_data()?)?.supply;
+        omega_contract.set_winner(winner, clock.slot, curr_time);
+        omega_contract.oracle_events |= OracleEvent::DisputeLost as u64;
+
+        slash_bond(
+            &mut omega_contract, omega_contract_acc.key, escrow_acc, vault_acc,
+            spl_token_program_acc, omega_signer_acc, supply
+        )?;
+        if let Some(supplies) = resolved_supplies(&omega_contract, winner_acc)? {
+            check_vault_solvency(&omega_contract, vault_acc, &supplies)?;
+        }
+        Ok(())
+    }
+
+    fn release_creator_bond(program_id: &Pubkey, accounts: &[AccountInfo]) -> OmegaResult<()> {
+        let accounts = array_ref![accounts, 0, 6];
+        let [
+            omega_contract_acc,
+            escrow_acc,
+            creator_quote_acc,
+            spl_token_program_acc,
+            omega_signer_acc,
+            clock_acc
+        ] = accounts;
+
+        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+        check_assert!(omega_contract.creator_bond.status == BondStatus::Posted as u64)?;
+        check_assert!(*escrow_acc.key == omega_contract.creator_bond.escrow)?;
+        check_assert!(omega_contract.winner != Pubkey::default())?;
+        let clock = solana_program::clock::Clock::from_account_info(clock_acc)?;
+        let curr_time = unix_time(&clock)?;
+        if curr_time < omega_contract.resolved_time.saturating_add(CREATOR_DISPUTE_WINDOW) {
+            return Err(OmegaErrorCode::BondAtStake.into());
+        }
+
+        let creator_quote = Account::unpack(&creator_quote_acc.try_borrow_data()?)?;
+        check_assert!(creator_quote.owner == omega_contract.creator_bond.creator)?;
+
+        let release_instruction = spl_token::instruction::transfer(
+            spl_token_program_acc.key,
+            escrow_acc.key,
+            creator_quote_acc.key,
+            omega_signer_acc.key,
+            &[],
+            omega_contract.creator_bond.amount
+        )?;
+        let release_accs = [
+            escrow_acc.clone(),
+            creator_quote_acc.clone(),
+            omega_signer_acc.clone(),
+            spl_token_program_acc.clone()
+        ];
+        let signer_seeds = gen_signer_seeds(&omega_contract.signer_nonce, omega_contract_acc.key);
+        invoke_signed(&release_instruction, &release_accs, &[&signer_seeds])?;
+
+        omega_contract.creator_bond.status = BondStatus::Released as u64;
+
+        Ok(())
+    }
+
+    fn forfeit_creator_bond(program_id: &Pubkey, accounts: &[AccountInfo]) -> OmegaResult<()> {
+        let accounts = array_ref![accounts, 0, 8];
+        let [
+            omega_contract_acc,
+            program_config_acc,
+            admin_acc,  // signer unless the contract is resolved invalid
+            escrow_acc,
+            treasury_quote_acc,
+            spl_token_program_acc,
+            omega_signer_acc,
+            clock_acc
+        ] = accounts;
+
+        let program_config = ProgramConfig::load(program_config_acc)?;
+        check_assert!(program_config.account_flags == (AccountFlag::Initialized | AccountFlag::ProgramConfig).bits())?;
+        check_assert!(program_config_acc.owner == program_id)?;
+
+        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+        check_assert!(omega_contract.creator_bond.status == BondStatus::Posted as u64)?;
+        check_assert!(*escrow_acc.key == omega_contract.creator_bond.escrow)?;
+        let clock = solana_program::clock::Clock::from_account_info(clock_acc)?;
+        let curr_time = unix_time(&clock)?;
+
+        if !omega_contract.is_resolved_invalid(curr_time) {
+            // the admin found the contract ambiguous
+            check_assert!(program_config.settings.admin == *admin_acc.key)?;
+            check_assert!(admin_acc.is_signer)?;
+            check_assert!(omega_contract.winner != Pubkey::default())?;
+            check_assert!(curr_time < omega_contract.resolved_time.saturating_add(CREATOR_DISPUTE_WINDOW))?;
+        }
+
+        let treasury_quote = Account::unpack(&treasury_quote_acc.try_borrow_data()?)?;
+        check_assert!(treasury_quote.owner == program_config.settings.treasury)?;
+
+        let forfeit_instruction = spl_token::instruction::transfer(
+            spl_token_program_acc.key,
+            escrow_acc.key,
+            treasury_quote_acc.key,
+            omega_signer_acc.key,
+            &[],
+            omega_contract.creator_bond.amount
+        )?;
+        let forfeit_accs = [
+            escrow_acc.clone(),
+            treasury_quote_acc.clone(),
+            omega_signer_acc.clone(),
+            spl_token_program_acc.clone()
+        ];
+        let signer_seeds = gen_signer_seeds(&omega_contract.signer_nonce, omega_contract_acc.key);
+        invoke_signed(&forfeit_instruction, &forfeit_accs, &[&signer_seeds])?;
+
+        omega_contract.creator_bond.status = BondStatus::Slashed as u64;
+
+        Ok(())
+    }
+
+    fn set_allow_list(program_id: &Pubkey, accounts: &[AccountInfo], root: [u8; 32]) -> OmegaResult<()> {
+        let accounts = array_ref![accounts, 0, 3];
+        let [
+            omega_contract_acc,
+            program_config_acc,
+            admin_acc  // signer
+        ] = accounts;
+
+        let program_config = ProgramConfig::load(program_config_acc)?;
+        check_assert!(program_config.account_flags == (AccountFlag::Initialized | AccountFlag::ProgramConfig).bits())?;
+        check_assert!(program_config_acc.owner == program_id)?;
+        check_assert!(program_config.settings.admin == *admin_acc.key)?;
+        check_assert!(admin_acc.is_signer)?;
+
+        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+        omega_contract.allow_list_root = root;
+
+        Ok(())
+    }
+
+    fn set_issuance_caps(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        max_supply: u64,
+        wallet_cap: u64
+    ) -> OmegaResult<()> {
+        let accounts = array_ref![accounts, 0, 3];
+        let [
+            omega_contract_acc,
+            program_config_acc,
+            admin_acc  // signer
+        ] = accounts;
+
+        let program_config = ProgramConfig::load(program_config_acc)?;
+        check_assert!(program_config.account_flags == (AccountFlag::Initialized | AccountFlag::ProgramConfig).bits())?;
+        check_assert!(program_config_acc.owner == program_id)?;
+        check_assert!(program_config.settings.admin == *admin_acc.key)?;
+        check_assert!(admin_acc.is_signer)?;
+
+        let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
+        check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+        check_assert!(omega_contract_acc.owner == program_id)?;
+        omega_contract.max_supply = max_supply;
+        omega_contract.wallet_cap = wallet_cap;
+
+        Ok(())
+    }
+
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+        let instruction = OmegaInstruction::unpack(data).ok_or(ProgramError::InvalidInstructionData)?;
+        match instruction {
+            OmegaInstruction::InitOmegaContract {
+                exp_time, auto_exp_time, signer_nonce,
+            } => {
+                msg!("InitOmegaContract");
+                let details_buffer = &data[16..];
+                Self::init_omega_contract(program_id, accounts, exp_time, auto_exp_time, signer_nonce, details_buffer)?;
+            },
+            OmegaInstruction::IssueSet {
+                quantity
+            } => {
+                msg!("IssueSet");
+                Self::issue_set(program_id, accounts, quantity, data.get(12..).unwrap_or(&[]))?;
+            },
+            OmegaInstruction::RedeemSet {
+                quantity
+            } => {
+                msg!("RedeemSet");
+                Self::redeem_set(program_id, accounts, quantity)?;
+            },
+            OmegaInstruction::RedeemWinner {
+                quantity
+            } => {
+                msg!("RedeemWinner");
+                Self::redeem_winner(program_id, accounts, quantity)?;
+            },
+            OmegaInstruction::Resolve {
+                evidence_hash
+            } => {
+                msg!("Resolve");
+                let evidence_uri = if data.len() > 36 { &data[36..] } else { &[] };
+                Self::resolve(program_id, accounts, evidence_hash, evidence_uri)?;
+            }
+            OmegaInstruction::InitLmsr {
+                liquidity
+            } => {
+                msg!("InitLmsr");
+                Self::init_lmsr(program_id, accounts, liquidity)?;
+            }
+            OmegaInstruction::LmsrBuy {
+                outcome, quantity, max_cost
+            } => {
+                msg!("LmsrBuy");
+                Self::lmsr_buy(program_id, accounts, outcome, quantity, max_cost, data.get(28..).unwrap_or(&[]))?;
+            }
+            OmegaInstruction::LmsrSell {
+                outcome, quantity, min_proceeds
+            } => {
+                msg!("LmsrSell");
+                Self::lmsr_sell(program_id, accounts, outcome, quantity, min_proceeds)?;
+            }
+            OmegaInstruction::LmsrWithdraw => {
+                msg!("LmsrWithdraw");
+                Self::lmsr_withdraw(program_id, accounts)?;
+            }
+            OmegaInstruction::InitOrderBook => {
+                msg!("InitOrderBook");
+                Self::init_order_book(program_id, accounts)?;
+            }
+            OmegaInstruction::PlaceOrder {
+                side, price, quantity
+            } => {
+                msg!("PlaceOrder");
+                Self::place_order(program_id, accounts, side, price, quantity)?;
+            }
+            OmegaInstruction::CancelOrder {
+                order_id
+            } => {
+                msg!("CancelOrder");
+                Self::settle_order(program_id, accounts, order_id, true)?;
+            }
+            OmegaInstruction::SettleOrder {
+                order_id
+            } => {
+                msg!("SettleOrder");
+                Self::settle_order(program_id, accounts, order_id, false)?;
+            }
+            OmegaInstruction::MatchOrders {
+                limit
+            } => {
+                msg!("MatchOrders");
+                Self::match_orders(program_id, accounts, limit)?;
+            }
+            OmegaInstruction::MatchSets {
+                side, limit
+            } => {
+                msg!("MatchSets");
+                Self::match_sets(program_id, accounts, side, limit)?;
+            }
+            OmegaInstruction::InitParimutuel {
+                fee_bps
+            } => {
+                msg!("InitParimutuel");
+                Self::init_parimutuel(program_id, accounts, fee_bps)?;
+            }
+            OmegaInstruction::Stake {
+                outcome, quantity
+            } => {
+                msg!("Stake");
+                Self::stake(program_id, accounts, outcome, quantity, data.get(20..).unwrap_or(&[]))?;
+            }
+            OmegaInstruction::CollectParimutuelFee => {
+                msg!("CollectParimutuelFee");
+                Self::collect_parimutuel_fee(program_id, accounts)?;
+            }
+            OmegaInstruction::BuyOutcome {
+                outcome, quantity, max_cost
+            } => {
+                msg!("BuyOutcome");
+                let allow_list_proof = data.get(28..).unwrap_or(&[]);
+                Self::swap_outcome(program_id, accounts, Side::Bid, outcome, quantity, max_cost, allow_list_proof)?;
+            }
+            OmegaInstruction::SellOutcome {
+                outcome, quantity, min_proceeds
+            } => {
+                msg!("SellOutcome");
+                Self::swap_outcome(program_id, accounts, Side::Ask, outcome, quantity, min_proceeds, &[])?;
+            }
+            OmegaInstruction::InitConditional {
+                parent_outcome
+            } => {
+                msg!("InitConditional");
+                Self::init_conditional(program_id, accounts, parent_outcome)?;
+            }
+            OmegaInstruction::IssueConditionalSet {
+                quantity
+            } => {
+                msg!("IssueConditionalSet");
+                Self::issue_conditional_set(program_id, accounts, quantity, true, data.get(12..).unwrap_or(&[]))?;
+            }
+            OmegaInstruction::RedeemConditionalSet {
+                quantity
+            } => {
+                msg!("RedeemConditionalSet");
+                Self::issue_conditional_set(program_id, accounts, quantity, false, &[])?;
+            }
+            OmegaInstruction::RedeemConditionalWinner {
+                quantity
+            } => {
+                msg!("RedeemConditionalWinner");
+                Self::redeem_conditional_winner(program_id, accounts, quantity)?;
+            }
+            OmegaInstruction::InitBucketGroup {
+                num_breakpoints, breakpoints
+            } => {
+                msg!("InitBucketGroup");
+                Self::init_bucket_group(program_id, accounts, num_breakpoints, &breakpoints)?;
+            }
+            OmegaInstruction::ResolveValue {
+                value
+            } => {
+                msg!("ResolveValue");
+                Self::resolve_value(program_id, accounts, value)?;
+            }
+            OmegaInstruction::InitMarketSeries {
+                num_outcomes, first_exp_time, period, resolve_window
+            } => {
+                msg!("InitMarketSeries");
+                let details_buffer = &data[36..];
+                Self::init_market_series(
+                    program_id, accounts, num_outcomes, first_exp_time, period, resolve_window, details_buffer
+                )?;
+            }
+            OmegaInstruction::CreateSeriesContract {
+                contract_nonce, signer_nonce
+            } => {
+                msg!("CreateSeriesContract");
+                Self::create_series_contract(program_id, accounts, contract_nonce, signer_nonce)?;
+            }
+            OmegaInstruction::InitRegistry => {
+                msg!("InitRegistry");
+                Self::init_registry(program_id, accounts)?;
+            }
+            OmegaInstruction::UpdateRegistryEntry {
+                category
+            } => {
+                msg!("UpdateRegistryEntry");
+                Self::update_registry_entry(program_id, accounts, category)?;
+            }
+            OmegaInstruction::CloseContract => {
+                msg!("CloseContract");
+                Self::close_contract(program_id, accounts)?;
+            }
+            OmegaInstruction::InitProgramConfig {
+                timelock, fee_bps, restrict_creators, num_quote_mints, creator_bond
+            } => {
+                msg!("InitProgramConfig");
+                Self::init_program_config(
+                    program_id, accounts, timelock, fee_bps, restrict_creators, num_quote_mints, creator_bond
+                )?;
+            }
+            OmegaInstruction::ProposeConfig {
+                timelock, fee_bps, restrict_creators, num_quote_mints, creator_bond
+            } => {
+                msg!("ProposeConfig");
+                Self::propose_config(
+                    program_id, accounts, timelock, fee_bps, restrict_creators, num_quote_mints, creator_bond
+                )?;
+            }
+            OmegaInstruction::ApplyConfig => {
+                msg!("ApplyConfig");
+                Self::apply_config(program_id, accounts)?;
+            }
+            OmegaInstruction::InitPriceResolution {
+                source, comparison, threshold, max_staleness
+            } => {
+                msg!("InitPriceResolution");
+                Self::init_price_resolution(program_id, accounts, source, comparison, threshold, max_staleness)?;
+            }
+            OmegaInstruction::ResolveFromPrice => {
+                msg!("ResolveFromPrice");
+                Self::resolve_from_price(program_id, accounts)?;
+            }
+            OmegaInstruction::ResolveWithAttestation {
+                winner, timestamp
+            } => {
+                msg!("ResolveWithAttestation");
+                Self::resolve_with_attestation(program_id, accounts, winner, timestamp)?;
+            }
+            OmegaInstruction::CommitResolution {
+                commitment, reveal_window
+            } => {
+                msg!("CommitResolution");
+                Self::commit_resolution(program_id, accounts, commitment, reveal_window)?;
+            }
+            OmegaInstruction::RevealResolution {
+                salt
+            } => {
+                msg!("RevealResolution");
+                Self::reveal_resolution(program_id, accounts, salt)?;
+            }
+            OmegaInstruction::InitOracleBond {
+                amount,
+                dispute_window
+            } => {
+                msg!("InitOracleBond");
+                Self::init_oracle_bond(program_id, accounts, amount, dispute_window)?;
+            }
+            OmegaInstruction::ReleaseOracleBond => {
+                msg!("ReleaseOracleBond");
+                Self::release_oracle_bond(program_id, accounts)?;
+            }
+            OmegaInstruction::SlashOracleBond => {
+                msg!("SlashOracleBond");
+                Self::slash_oracle_bond(program_id, accounts)?;
+            }
+            OmegaInstruction::OverturnResolution => {
+                msg!("OverturnResolution");
+                Self::overturn_resolution(program_id, accounts)?;
+            }
+            OmegaInstruction::InitOracleFee {
+                amount,
+                fee_bps,
+                deadline
+            } => {
+                msg!("InitOracleFee");
+                Self::init_oracle_fee(program_id, accounts, amount, fee_bps, deadline)?;
+            }
+            OmegaInstruction::WithdrawOracleFee => {
+                msg!("WithdrawOracleFee");
+                Self::withdraw_oracle_fee(program_id, accounts)?;
+            }
+            OmegaInstruction::ResolveLate => {
+                msg!("ResolveLate");
+                Self::resolve_late(program_id, accounts)?;
+            }
+            OmegaInstruction::InitOracleLiveness {
+                heartbeat_period
+            } => {
+                msg!("InitOracleLiveness");
+                Self::init_oracle_liveness(program_id, accounts, heartbeat_period)?;
+            }
+            OmegaInstruction::OracleHeartbeat => {
+                msg!("OracleHeartbeat");
+                Self::oracle_heartbeat(program_id, accounts)?;
+            }
+            OmegaInstruction::InitOracleReputation => {
+                msg!("InitOracleReputation");
+                Self::init_oracle_reputation(program_id, accounts)?;
+            }
+            OmegaInstruction::RecordOracleReputation => {
+                msg!("RecordOracleReputation");
+                Self::record_oracle_reputation(program_id, accounts)?;
+            }
+            OmegaInstruction::InitVoteResolution {
+                voting_period, quorum, tie_break, tie_break_outcome
+            } => {
+                msg!("InitVoteResolution");
+                Self::init_vote_resolution(program_id, accounts, voting_period, quorum, tie_break, tie_break_outcome)?;
+            }
+            OmegaInstruction::CastVote {
+                outcome, amount
+            } => {
+                msg!("CastVote");
+                Self::cast_vote(program_id, accounts, outcome, amount)?;
+            }
+            OmegaInstruction::FinalizeVote => {
+                msg!("FinalizeVote");
+                Self::finalize_vote(program_id, accounts)?;
+            }
+            OmegaInstruction::WithdrawVote => {
+                msg!("WithdrawVote");
+                Self::withdraw_vote(program_id, accounts)?;
+            }
+            OmegaInstruction::ReleaseCreatorBond => {
+                msg!("ReleaseCreatorBond");
+                Self::release_creator_bond(program_id, accounts)?;
+            }
+            OmegaInstruction::ForfeitCreatorBond => {
+                msg!("ForfeitCreatorBond");
+                Self::forfeit_creator_bond(program_id, accounts)?;
+            }
+            OmegaInstruction::SetAllowList {
+                root
+            } => {
+                msg!("SetAllowList");
+                Self::set_allow_list(program_id, accounts, root)?;
+            }
+            OmegaInstruction::SetIssuanceCaps {
+                max_supply,
+                wallet_cap
+            } => {
+                msg!("SetIssuanceCaps");
+                Self::set_issuance_caps(program_id, accounts, max_supply, wallet_cap)?;
+            }
+        }
+
+
+        Ok(())
+    }
+}
+
+
+/// Initialize a new contract in an account owned by the program. Shared by InitOmegaContract
+/// and CreateSeriesContract
+#[allow(clippy::too_many_arguments)]
+fn init_contract(
+    program_id: &Pubkey,
+    omega_contract_acc: &AccountInfo,
+    oracle: &Pubkey,
+    quote_mint_acc: &AccountInfo,
+    vault_acc: &AccountInfo,
+    signer_acc: &AccountInfo,
+    outcome_accs: &[AccountInfo],
+    exp_time: u64,
+    auto_exp_time: u64,
+    signer_nonce: u64,
+    details: &[u8]
+) -> OmegaResult<()> {
+    check_assert!(details.len() <= DETAILS_BUFFER_LEN)?;
+
+    let mut omega_contract = OmegaContract::load_mut(omega_contract_acc)?;
+
+    check_assert!(omega_contract.account_flags == 0)?;
+    check_assert!(auto_exp_time >= exp_time)?;
+    let signer_key = gen_signer_key(signer_nonce, omega_contract_acc.key, program_id)?;
+    check_assert!(signer_key == *signer_acc.key)?;
+    omega_contract.account_flags = (AccountFlag::Initialized | AccountFlag::OmegaContract).bits();
+    omega_contract.oracle = *oracle;
+    omega_contract.quote_mint = *quote_mint_acc.key;
+    omega_contract.exp_time = exp_time;
+    omega_contract.auto_exp_time = auto_exp_time;
+    omega_contract.vault = *vault_acc.key;
+    omega_contract.signer_key = *signer_acc.key;
+    omega_contract.signer_nonce = signer_nonce;
+    omega_contract.winner = Pubkey::default();
+    omega_contract.num_outcomes = outcome_accs.len();
+
+    let details_buf = &mut omega_contract.details[..details.len()];
+    details_buf.copy_from_slice(details);
+
+    let quote_mint = Mint::unpack(&quote_mint_acc.try_borrow_data()?)?;
+    let vault = Account::unpack(&vault_acc.try_borrow_data()?)?;
+    check_assert!(vault.owner == signer_key)?;
+    check_assert!(&vault.mint == quote_mint_acc.key)?;
+
+    for (i, outcome_acc) in outcome_accs.iter().enumerate() {
+        let outcome = Mint::unpack(&outcome_acc.try_borrow_data()?)?;
+        let authority = outcome.mint_authority.ok_or(OmegaErrorCode::InvalidOutcomeMintAuthority)?;
+        check_assert!(*outcome_acc.key != Pubkey::default())?;
+        check_assert!(outcome.is_initialized)?;
+        check_assert!(authority == signer_key)?;
+        check_assert!(outcome.supply == 0)?;
+        check_assert!(outcome.decimals == quote_mint.decimals)?;
+        omega_contract.outcomes[i] = *outcome_acc.key;
+    }
+
+    Ok(())
+}
+
+/// Create an account owned by this program at the address derived from `seeds`
+fn create_pda_account<'a, T: Pod>(
+    program_id: &Pubkey,
+    payer_acc: &AccountInfo<'a>,
+    new_acc: &AccountInfo<'a>,
+    system_program_acc: &AccountInfo<'a>,
+    rent: &Rent,
+    seeds: &[&[u8]]
+) -> OmegaResult<()> {
+    let create_instruction = solana_program::system_instruction::create_account(
+        payer_acc.key,
+        new_acc.key,
+        rent.minimum_balance(size_of::<T>()),
+        size_of::<T>() as u64,
+        program_id
+    );
+    let create_accs = [payer_acc.clone(), new_acc.clone(), system_program_acc.clone()];
+    invoke_signed(&create_instruction, &create_accs, &[seeds])?;
+    Ok(())
+}
+
+/// Check that `creator` may make a market with `quote_mint`, and return the settings it is made under
+fn check_program_config(
+    program_id: &Pubkey,
+    program_config_acc: &AccountInfo,
+    creator_acc: &AccountInfo,
+    quote_mint: &Pubkey
+) -> OmegaResult<ConfigSettings> {
+    let (program_config_key, _) = find_program_config_address(program_id);
+    check_assert!(*program_config_acc.key == program_config_key)?;
+    check_assert!(program_config_acc.owner == program_id)?;
+
+    let program_config = ProgramConfig::load(program_config_acc)?;
+    check_assert!(program_config.account_flags == (AccountFlag::Initialized | AccountFlag::ProgramConfig).bits())?;
+    let settings = &program_config.settings;
+    check_assert!(creator_acc.is_signer || (settings.restrict_creators == 0 && settings.creator_bond == 0))?;
+
+    if !settings.is_quote_mint_allowed(quote_mint) {
+        return Err(OmegaErrorCode::QuoteMintNotAllowed.into());
+    }
+    if !settings.is_creator_allowed(creator_acc.key) {
+        return Err(OmegaErrorCode::CreatorNotAllowed.into());
+    }
+    Ok(*settings)
+}
+
+/// Settings whose allowed quote mints are the first `num_quote_mints` of `settings_accs`
+/// and allowed creators the rest
+fn read_config_settings(
+    admin: &Pubkey,
+    timelock: u64,
+    fee_bps: u64,
+    restrict_creators: u64,
+    num_quote_mints: u64,
+    creator_bond: u64,
+    settings_accs: &[AccountInfo]
+) -> OmegaResult<ConfigSettings> {
+    check_assert!(fee_bps <= MAX_BPS)?;
+    let (treasury_acc, settings_accs) = settings_accs.split_first().ok_or(assertion_error!())?;
+    check_assert!(creator_bond == 0 || *treasury_acc.key != Pubkey::default())?;
+    check_assert!(num_quote_mints as usize <= min(settings_accs.len(), MAX_QUOTE_MINTS))?;
+    let (quote_mint_accs, creator_accs) = settings_accs.split_at(num_quote_mints as usize);
+    check_assert!(creator_accs.len() <= MAX_CREATORS)?;
+    check_assert!(restrict_creators != 0 || creator_accs.is_empty())?;
+
+    let mut settings = ConfigSettings::zeroed();
+    settings.admin = *admin;
+    settings.timelock = timelock;
+    settings.fee_bps = fee_bps;
+    settings.num_quote_mints = num_quote_mints;
+    for (i, quote_mint_acc) in quote_mint_accs.iter().enumerate() {
+        check_assert!(*quote_mint_acc.owner == spl_token::ID)?;
+        settings.quote_mints[i] = *quote_mint_acc.key;
+    }
+    settings.restrict_creators = restrict_creators;
+    settings.num_creators = creator_accs.len() as u64;
+    for (i, creator_acc) in creator_accs.iter().enumerate() {
+        settings.creators[i] = *creator_acc.key;
+    }
+    settings.creator_bond = creator_bond;
+    settings.treasury = *treasury_acc.key;
+    Ok(settings)
+}
+
+fn check_order_book(
+    order_book: &OrderBook,
+    order_book_acc: &AccountInfo,
+    omega_contract_acc: &AccountInfo,
+    program_id: &Pubkey
+) -> OmegaResult<()> {
+    check_assert!(order_book.account_flags == (AccountFlag::Initialized | AccountFlag::OrderBook).bits())?;
+    check_assert!(order_book_acc.owner == program_id)?;
+    check_assert!(order_book.omega_contract == *omega_contract_acc.key)?;
+    Ok(())
+}
+
+/// A conditional contract must be collateralized by the outcome token of its recorded parent
+fn check_conditional(
+    omega_contract: &OmegaContract,
+    omega_contract_acc: &AccountInfo,
+    parent_contract: &OmegaContract,
+    parent_contract_acc: &AccountInfo,
+    program_id: &Pubkey
+) -> OmegaResult<()> {
+    check_assert!(omega_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+    check_assert!(omega_contract_acc.owner == program_id)?;
+    check_assert!(parent_contract.account_flags == (AccountFlag::Initialized | AccountFlag::OmegaContract).bits())?;
+    check_assert!(parent_contract_acc.owner == program_id)?;
+    check_assert!(omega_contract.parent == *parent_contract_acc.key)?;
+    check_assert!(omega_contract.quote_mint == parent_contract.outcomes[omega_contract.parent_outcome as usize])?;
+    if omega_contract.mode != ContractMode::CompleteSet as u64 || parent_contract.mode != ContractMode::CompleteSet as u64 {
+        return Err(OmegaErrorCode::InvalidContractMode.into());
+    }
+    Ok(())
+}
+
+/// Quote tokens paid for `quantity` tokens of `mint` once a complete set contract is resolved or auto expired
+fn winner_payout(omega_contract: &OmegaContract, mint: &Pubkey, quantity: u64, curr_time: u64) -> OmegaResult<u64> {
+    if omega_contract.winner == Pubkey::default() {
+        // If it has auto expired with no winner, then let user redeem at 1 / num_outcomes rate.
+        // The mint has to be checked since anyone can burn tokens of a mint they hold
+        check_assert!(curr_time >= omega_contract.auto_exp_time)?;
+        if !omega_contract.outcomes[..omega_contract.num_outcomes].contains(mint) {
+            return Err(OmegaErrorCode::InvalidOutcome.into());
+        }
+        math::div(quantity, omega_contract.num_outcomes as u64)
+    } else {
+        check_assert!(*mint == omega_contract.winner)?;
+        Ok(quantity)
+    }
+}
+
+/// Move the bond into the vault, to be split over `supply` outcome tokens when they are redeemed
+fn slash_bond<'a>(
+    omega_contract: &mut OmegaContract,
+    omega_contract_pk: &Pubkey,
+    escrow_acc: &AccountInfo<'a>,
+    vault_acc: &AccountInfo<'a>,
+    spl_token_program_acc: &AccountInfo<'a>,
+    omega_signer_acc: &AccountInfo<'a>,
+    supply: u64
+) -> OmegaResult<()> {
+    check_assert!(omega_contract.bond.status == BondStatus::Posted as u64)?;
+    check_assert!(*escrow_acc.key == omega_contract.bond.escrow)?;
+    check_assert!(*vault_acc.key == omega_contract.vault)?;
+
+    let slash_instruction = spl_token::instruction::transfer(
+        spl_token_program_acc.key,
+        escrow_acc.key,
+        vault_acc.key,
+        omega_signer_acc.key,
+        &[],
+        omega_contract.bond.amount
+    )?;
+    let slash_accs = [
+        escrow_acc.clone(),
+        vault_acc.clone(),
+        omega_signer_acc.clone(),
+        spl_token_program_acc.clone()
+    ];
+    let signer_seeds = gen_signer_seeds(&omega_contract.signer_nonce, omega_contract_pk);
+    invoke_signed(&slash_instruction, &slash_accs, &[&signer_seeds])?;
+
+    omega_contract.bond.status = BondStatus::Slashed as u64;
+    omega_contract.bond.slashed_supply = supply;
+    msg!("slashed {} over {} outcome tokens", omega_contract.bond.amount, supply);
+
+    Ok(())
+}
+
+/// Winnings can't be paid while the bond may still go to the holders: before resolution and during the
+/// dispute window after it
+fn check_bond_settled(omega_contract: &OmegaContract, curr_time: u64) -> OmegaResult<()> {
+    let bond = &omega_contract.bond;
+    if bond.status == BondStatus::Posted as u64 && (
+        omega_contract.winner == Pubkey::default() || bond.is_at_stake(omega_contract.resolved_time, curr_time)
+    ) {
+        return Err(OmegaErrorCode::BondAtStake.into());
+    }
+    Ok(())
+}
+
+/// Split off the clock of instructions that take it optionally, if it was passed
+fn split_optional_clock<'a, 'b>(
+    accs: &'a [AccountInfo<'b>]
+) -> (Option<&'a AccountInfo<'b>>, &'a [AccountInfo<'b>]) {
+    match accs.split_first() {
+        Some((clock_acc, rest)) if *clock_acc.key == solana_program::sysvar::clock::ID => (Some(clock_acc), rest),
+        _ => (None, accs),
+    }
+}
+
+/// Time for the stats of an instruction with an optional clock: without it the contract's
+/// last activity stays as it was
+fn optional_clock_time(omega_contract: &OmegaContract, clock_acc: Option<&AccountInfo>) -> OmegaResult<u64> {
+    match clock_acc {
+        Some(clock_acc) => unix_time(&solana_program::clock::Clock::from_account_info(clock_acc)?),
+        None => Ok(omega_contract.stats.last_activity),
+    }
+}
+
+/// A slashed bond is split over the supply at the time it was slashed, so new tokens can't be issued
+fn check_not_slashed(omega_contract: &OmegaContract) -> OmegaResult<()> {
+    if omega_contract.bond.status == BondStatus::Slashed as u64 {
+        return Err(OmegaErrorCode::TradingClosed.into());
+    }
+    Ok(())
+}
+
+/// Users issuing new tokens of a contract with an allow-list have to prove they are on it
+fn check_allow_list(omega_contract: &OmegaContract, user_pk: &Pubkey, proof: &[u8]) -> OmegaResult<()> {
+    if omega_contract.allow_list_root != [0u8; 32] && !verify_proof(&omega_contract.allow_list_root, user_pk, proof) {
+        return Err(OmegaErrorCode::NotOnAllowList.into());
+    }
+    Ok(())
+}
+
+/// Count the oracle events of the contract not recorded yet that are on the oracle of this reputation
+fn record_reputation(
+    program_id: &Pubkey,
+    omega_contract: &mut OmegaContract,
+    oracle_reputation_acc: &AccountInfo
+) -> OmegaResult<()> {
+    let mut oracle_reputation = OracleReputation::load_mut(oracle_reputation_acc)?;
+    check_assert!(oracle_reputation.account_flags == (AccountFlag::Initialized | AccountFlag::OracleReputation).bits())?;
+    check_assert!(oracle_reputation_acc.owner == program_id)?;
+
+    let pending = BitFlags::<OracleEvent>::from_bits_truncate(
+        omega_contract.oracle_events & !omega_contract.recorded_events
+    );
+    for event in pending.iter() {
+        if omega_contract.oracle_for_event(event) == oracle_reputation.oracle {
+            oracle_reputation.record(event, omega_contract.resolution_delay)?;
+            omega_contract.recorded_events |= event as u64;
+        }
+    }
+    Ok(())
+}
+
+/// Quote tokens paid for `quantity` winning tickets: the pot minus the fee, split pro rata over the winning pool
+fn parimutuel_payout(quantity: u64, pools: &[u64], winner: usize, fee_bps: u64) -> OmegaResult<u64> {
+    // the pot of up to MAX_OUTCOMES pools can exceed a u64, so this is done in 128 bits
+    let pot: u128 = pools.iter().map(|&p| p as u128).sum();
+    let payout = pot
+        .checked_mul(fee_bps as u128)
+        .map(|fee| fee / (MAX_BPS as u128))
+        .and_then(|fee| pot.checked_sub(fee))
+        .and_then(|prize| prize.checked_mul(quantity as u128))
+        .and_then(|amount| amount.checked_div(pools[winner] as u128))
+        .ok_or(OmegaErrorCode::MathOverflow)?;
+    Ok(u64::try_from(payout).map_err(|_| OmegaErrorCode::MathOverflow)?)
+}
+
+/// Quote tokens the vault has to hold for holders of outcome tokens with the given supplies.
+/// Until the contract is resolved any outcome can still win, afterwards only the winner is paid
+fn owed_to_holders(omega_contract: &OmegaContract, supplies: &[u64; MAX_OUTCOMES]) -> OmegaResult<u64> {
+    let num_outcomes = omega_contract.num_outcomes;
+    let supplies = &supplies[..num_outcomes];
+    let bond = &omega_contract.bond;
+    let total = math::sum(supplies)?;
+    let pools = &omega_contract.parimutuel.pools[..num_outcomes];
+    let parimutuel = omega_contract.mode == ContractMode::Parimutuel as u64;
+    let winner = omega_contract.outcomes[..num_outcomes].iter().position(|pk| *pk == omega_contract.winner);
+
+    match winner {
+        // tickets are refunded if nobody staked on the winner
+        Some(w) if parimutuel && pools[w] == 0 => math::add(total, bond.share(total)?),
+        Some(w) if parimutuel => math::add(
+            parimutuel_payout(supplies[w], pools, w, omega_contract.parimutuel.fee_bps)?,
+            bond.share(supplies[w])?
+        ),
+        Some(w) => math::add(supplies[w], bond.share(supplies[w])?),
+        None if parimutuel => math::add(total, bond.share(total)?),
+        // once holders redeemed at the auto expiry rate the contract can no longer be resolved
+        None if omega_contract.auto_exp_redemptions > 0 => {
+            math::add(math::div(total, num_outcomes as u64)?, bond.share(total)?)
+        }
+        None => {
+            let max_supply = supplies.iter().copied().max().unwrap_or(0);
+            math::add(max_supply, bond.share(max_supply)?)
+        }
+    }
+}
+
+/// Supply of each outcome, read back from its mint
+fn read_outcome_supplies<'a, 'b: 'a>(
+    omega_contract: &OmegaContract,
+    outcome_mint_accs: impl Iterator<Item = &'a AccountInfo<'b>>
+) -> OmegaResult<[u64; MAX_OUTCOMES]> {
+    let mut supplies = [0u64; MAX_OUTCOMES];
+    let mut num_mints = 0;
+    for (i, outcome_mint_acc) in outcome_mint_accs.enumerate() {
+        check_assert!(i < omega_contract.num_outcomes)?;
+        check_assert!(*outcome_mint_acc.key == omega_contract.outcomes[i])?;
+        supplies[i] = Mint::unpack(&outcome_mint_acc.try_borrow_data()?)?.supply;
+        num_mints += 1;
+    }
+    check_assert!(num_mints == omega_contract.num_outcomes)?;
+    Ok(supplies)
+}
+
+/// Supplies to check the vault against once the contract is resolved, when the instruction only has the winner mint.
+/// None if holders of every outcome can still redeem
+fn resolved_supplies(
+    omega_contract: &OmegaContract,
+    winner_mint_acc: &AccountInfo
+) -> OmegaResult<Option<[u64; MAX_OUTCOMES]>> {
+    let num_outcomes = omega_contract.num_outcomes;
+    let winner = match omega_contract.outcomes[..num_outcomes].iter().position(|pk| *pk == omega_contract.winner) {
+        None => return Ok(None),
+        Some(w) => w
+    };
+    if omega_contract.mode == ContractMode::Parimutuel as u64 && omega_contract.parimutuel.pools[winner] == 0 {
+        return Ok(None);
+    }
+    check_assert!(*winner_mint_acc.key == omega_contract.winner)?;
+    let mut supplies = [0u64; MAX_OUTCOMES];
+    supplies[winner] = Mint::unpack(&winner_mint_acc.try_borrow_data()?)?.supply;
+    Ok(Some(supplies))
+}
+
+/// Complete sets are only safe if the vault can pay out every outstanding outcome token. Called after the CPIs
+/// of an instruction, with the supplies read back from the mints
+fn check_vault_solvency(
+    omega_contract: &OmegaContract,
+    vault_acc: &AccountInfo,
+    supplies: &[u64; MAX_OUTCOMES]
+) -> OmegaResult<()> {
+    let vault = Account::unpack(&vault_acc.try_borrow_data()?)?;
+    // the unpaid oracle reward sits in the vault as well
+    let owed = math::add(owed_to_holders(omega_contract, supplies)?, omega_contract.oracle_fee.reserve)?;
+
+    #[cfg(feature = "debug-invariants")]
+    {
+        check_assert!(vault.mint == omega_contract.quote_mint)?;
+        check_assert!(vault.owner == omega_contract.signer_key)?;
+    }
+
+    if vault.amount < owed {
+        msg!("vault holds {} but owes {}", vault.amount, owed);
+        return Err(OmegaErrorCode::VaultInsolvent.into());
+    }
+    Ok(())
+}
+
+/// Check the vault after instructions that take it with the mint of each outcome just for that,
+/// `solvency_accs` being the vault followed by the mints in order
+fn check_solvency_accounts(omega_contract: &OmegaContract, solvency_accs: &[AccountInfo]) -> OmegaResult<()> {
+    let (vault_acc, outcome_mint_accs) = solvency_accs.split_first().ok_or(assertion_error!())?;
+    check_assert!(*vault_acc.key == omega_contract.vault)?;
+    let supplies = read_outcome_supplies(omega_contract, outcome_mint_accs.iter())?;
+    check_vault_solvency(omega_contract, vault_acc, &supplies)
+}
+
+fn gen_signer_seeds<'a>(nonce: &'a u64, contract_pk: &'a Pubkey) -> [&'a [u8]; 2] {
+    [contract_pk.as_ref(), bytes_of(nonce)]
+}
+
+fn gen_signer_key(
+    nonce: u64,
+    contract_pk: &Pubkey,
+    program_id: &Pubkey,
+) -> Result<Pubkey, ProgramError> {
+    let seeds = gen_signer_seeds(&nonce, contract_pk);
+    Ok(Pubkey::create_program_address(&seeds, program_id)?)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::collections::HashMap;
+    use std::mem::size_of;
+
+    use bytemuck::Pod;
+    use solana_program::instruction::Instruction;
+    use solana_program::program_error::PrintProgramError;
+    use solana_program::program_stubs::{set_syscall_stubs, SyscallStubs};
+    use solana_program::rent::Rent;
+    use solana_sdk::account::{Account, create_account, create_is_signer_account_infos};
+
+    use solana_program::clock::Clock;
+
+    use crate::error::OmegaError;
+    use crate::instruction::*;
+    use crate::price::{Price, PYTH_MAGIC, PYTH_PRICE_ACCOUNT, PYTH_STATUS_TRADING, PYTH_VERSION, PythPrice, SimplePrice};
+    use crate::state::MAX_REGISTRY_ENTRIES;
+
+    use super::*;
+
+    fn do_process_instruction(
+        instruction: Instruction,
+        accounts: Vec<&mut Account>,
+    ) -> ProgramResult {
+        let account_infos = instruction
+            .accounts
+            .iter()
+            .zip(accounts)
+            .map(|(account_meta, account)| AccountInfo::new(
+                &account_meta.pubkey,
+                account_meta.is_signer,
+                account_meta.is_writable,
+                &mut account.lamports,
+                &mut account.data,
+                &account.owner,
+                account.executable,
+                account.rent_epoch
+            ))
+            .collect::<Vec<_>>();
+        process_account_infos(&instruction, &account_infos)
+    }
+
+    fn process_account_infos(instruction: &Instruction, account_infos: &[AccountInfo]) -> ProgramResult {
+        // like the runtime, refuse changes to accounts the instruction passed read-only
+        let data_before = account_infos.iter().map(|acc| acc.data.borrow().to_vec()).collect::<Vec<_>>();
+        let result = Processor::process(&instruction.program_id, account_infos, &instruction.data);
+        for (acc, data) in account_infos.iter().zip(data_before) {
+            assert!(acc.is_writable || **acc.data.borrow() == data[..], "read-only account {} was modified", acc.key);
+        }
+        result
+    }
+
+    /// Run `instruction` against the accounts in `accs` by key, and keep what it wrote if it succeeds.
+    /// Like the runtime, an account passed more than once is the same account at every position
+    fn process_with(instruction: Instruction, accs: &mut HashMap<Pubkey, Account>) -> ProgramResult {
+        let metas = &instruction.accounts;
+        let mut keys: Vec<Pubkey> = vec![];
+        for meta in metas.iter() {
+            if !keys.contains(&meta.pubkey) {
+                keys.push(meta.pubkey);
+            }
+        }
+        let is_writable = |key: &Pubkey| metas.iter().any(|meta| meta.pubkey == *key && meta.is_writable);
+        let is_signer = |key: &Pubkey| metas.iter().any(|meta| meta.pubkey == *key && meta.is_signer);
+
+        let mut accounts: Vec<Account> = keys.iter().map(|key| accs.get(key).cloned().unwrap_or_default()).collect();
+        let unique_infos = keys.iter().zip(accounts.iter_mut())
+            .map(|(key, account)| AccountInfo::new(
+                key,
+                is_signer(key),
+                is_writable(key),
+                &mut account.lamports,
+                &mut account.data,
+                &account.owner,
+                account.executable,
+                account.rent_epoch
+            ))
+            .collect::<Vec<_>>();
+        let account_infos = metas.iter()
+            .map(|meta| unique_infos.iter().find(|acc| *acc.key == meta.pubkey).unwrap().clone())
+            .collect::<Vec<_>>();
+        let result = process_account_infos(&instruction, &account_infos);
+        drop(account_infos);
+        drop(unique_infos);
+
+        if result.is_ok() {
+            for (key, account) in keys.iter().zip(accounts) {
+                if is_writable(key) && accs.contains_key(key) {
+                    accs.insert(*key, account);
+                }
+            }
+        }
+        result
+    }
+
+    thread_local! {
+        static TOKEN_CPI: Cell<bool> = const { Cell::new(false) };
+    }
+
+    /// Runs the SPL token instructions a test invokes, once it called `execute_token_cpi`.
+    /// Signers are taken from the instruction, so the seeds are not checked
+    struct TokenStubs;
+
+    impl SyscallStubs for TokenStubs {
+        fn sol_invoke_signed(
+            &self,
+            instruction: &Instruction,
+            account_infos: &[AccountInfo],
+            _signers_seeds: &[&[&[u8]]]
+        ) -> ProgramResult {
+            if !TOKEN_CPI.with(|token_cpi| token_cpi.get()) {
+                return Ok(());
+            }
+            let accounts = instruction.accounts.iter().map(|meta| {
+                let mut account_info = account_infos.iter().find(|acc| *acc.key == meta.pubkey).unwrap().clone();
+                account_info.is_signer = meta.is_signer;
+                account_info
+            }).collect::<Vec<_>>();
+            spl_token::processor::Processor::process(&instruction.program_id, &accounts, &instruction.data)
+        }
+    }
+
+    fn execute_token_cpi() {
+        static SET_STUBS: std::sync::Once = std::sync::Once::new();
+        SET_STUBS.call_once(|| {
+            set_syscall_stubs(Box::new(TokenStubs));
+        });
+        TOKEN_CPI.with(|token_cpi| token_cpi.set(true));
+    }
+
+    fn get_rent_exempt<T: Pod>(owner: &Pubkey) -> Account {
+        let rent = Rent::default();
+        Account::new(rent.minimum_balance(size_of::<T>()), size_of::<T>(), owner)
+    }
+
+    fn clock_account(clock: &Clock) -> Account {
+        create_account(clock, 1)
+    }
+
+    fn mint_account(supply: u64) -> Account {
+        let mut mint_acc = Account::new(1, Mint::LEN, &spl_token::id());
+        Mint::pack(Mint { supply, is_initialized: true, ..Mint::default() }, &mut mint_acc.data).unwrap();
+        mint_acc
+    }
+
+    fn token_account(mint: &Pubkey, owner: &Pubkey, amount: u64) -> Account {
+        let token = spl_token::state::Account {
+            mint: *mint,
+            owner: *owner,
+            amount,
+            state: spl_token::state::AccountState::Initialized,
+            ..spl_token::state::Account::default()
+        };
+        let mut token_acc = Account::new(1, spl_token::state::Account::LEN, &spl_token::id());
+        spl_token::state::Account::pack(token, &mut token_acc.data).unwrap();
+        token_acc
+    }
+
+    #[test]
+    fn test_init_omega_contract() {
+        let program_id = Pubkey::new_unique();
+        let omega_contract_pk = Pubkey::new_unique();
+        let mut omega_contract_acc = get_rent_exempt::<OmegaContract>(&program_id);
+
+        let oracle_pk = Pubkey::new_unique();
+        let mut oracle_acc = Account::default();
+
+        let (signer_pk, signer_nonce) = (0..256u64)
+            .find_map(|nonce| gen_signer_key(nonce, &omega_contract_pk, &program_id).ok().map(|pk| (pk, nonce)))
+            .unwrap();
+        let mut signer_acc = Account::default();
+
+        let mint_acc = |mint_authority: &Pubkey| {
+            let mint = Mint {
+                mint_authority: Some(*mint_authority).into(),
+                decimals: 6,
+                is_initialized: true,
+                ..Mint::default()
+            };
+            let mut mint_acc = Account::new(1, Mint::LEN, &spl_token::id());
+            Mint::pack(mint, &mut mint_acc.data).unwrap();
+            mint_acc
+        };
+        let quote_mint_pk = Pubkey::new_unique();
+        let mut quote_mint_acc = mint_acc(&Pubkey::new_unique());
+
+        let vault_pk = Pubkey::new_unique();
+        let vault = spl_token::state::Account {
+            mint: quote_mint_pk,
+            owner: signer_pk,
+            state: spl_token::state::AccountState::Initialized,
+            ..spl_token::state::Account::default()
+        };
+        let mut vault_acc = Account::new(1, spl_token::state::Account::LEN, &spl_token::id());
+        spl_token::state::Account::pack(vault, &mut vault_acc.data).unwrap();
+
+        let mut rent_acc = create_account(&Rent::default(), 1);
+        let mut program_config = ProgramConfig::zeroed();
+        program_config.account_flags = (AccountFlag::Initialized | AccountFlag::ProgramConfig).bits();
+        program_config.settings.fee_bps = 30;
+        let mut program_config_acc = get_rent_exempt::<ProgramConfig>(&program_id);
+        program_config_acc.data.copy_from_slice(bytes_of(&program_config));
+        let creator_pk = Pubkey::new_unique();
+        let mut creator_acc = Account::default();
+        let registry_pk = Pubkey::new_unique();
+        let mut registry = Registry::zeroed();
+        registry.account_flags = (AccountFlag::Initialized | AccountFlag::Registry).bits();
+        registry.creator = creator_pk;
+        let mut registry_acc = get_rent_exempt::<Registry>(&program_id);
+        registry_acc.data.copy_from_slice(bytes_of(&registry));
+        let mut clock_acc = clock_account(&Clock { unix_timestamp: 100, ..Clock::default() });
+
+        let outcome_pks = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let mut outcome_accs = [mint_acc(&signer_pk), mint_acc(&signer_pk)];
+
+        let instruction = init_omega_contract(
+            &program_id, &omega_contract_pk, &oracle_pk, &quote_mint_pk, &vault_pk, &signer_pk, &creator_pk,
+            &registry_pk, &outcome_pks, 0, 0, signer_nonce, "DO NOT USE THIS CONTRACT"
+        ).unwrap();
+
+        // the program config has to be created first
+        let mut uncreated_config_acc = Account::default();
+        let [outcome0_acc, outcome1_acc] = &mut outcome_accs;
+        let accounts = vec![
+            &mut omega_contract_acc, &mut oracle_acc, &mut quote_mint_acc, &mut vault_acc, &mut signer_acc,
+            &mut rent_acc, &mut uncreated_config_acc, &mut creator_acc, &mut registry_acc, &mut clock_acc,
+            outcome0_acc, outcome1_acc
+        ];
+        let result = do_process_instruction(instruction.clone(), accounts);
+        assert!(result.is_err());
+
+        // and contracts can only be listed in the registry of their creator
+        let mut other_registry_acc = registry_acc.clone();
+        other_registry_acc.data.copy_from_slice(bytes_of(&Registry { creator: Pubkey::new_unique(), ..registry }));
+        let mut unlisted_contract_acc = omega_contract_acc.clone();
+        let [outcome0_acc, outcome1_acc] = &mut outcome_accs;
+        let accounts = vec![
+            &mut unlisted_contract_acc, &mut oracle_acc, &mut quote_mint_acc, &mut vault_acc, &mut signer_acc,
+            &mut rent_acc, &mut program_config_acc, &mut creator_acc, &mut other_registry_acc, &mut clock_acc,
+            outcome0_acc, outcome1_acc
+        ];
+        let result = do_process_instruction(instruction.clone(), accounts);
+        assert!(result.is_err());
+
+        let [outcome0_acc, outcome1_acc] = &mut outcome_accs;
+        let accounts = vec![
+            &mut omega_contract_acc, &mut oracle_acc, &mut quote_mint_acc, &mut vault_acc, &mut signer_acc,
+            &mut rent_acc, &mut program_config_acc, &mut creator_acc, &mut registry_acc, &mut clock_acc,
+            outcome0_acc, outcome1_acc
+        ];
+        let result = do_process_instruction(instruction, accounts);
+        assert!(result == Ok(()));
+        let registry = Registry::load_from_bytes(&registry_acc.data).unwrap();
+        assert_eq!(registry.entries()[0].contract, omega_contract_pk);
+        assert_eq!(registry.entries()[0].created_at, 100);
+
+        let omega_contract = OmegaContract::load_from_bytes(&omega_contract_acc.data).unwrap();
+        assert_eq!(omega_contract.oracle, oracle_pk);
+        assert_eq!(&omega_contract.outcomes[..omega_contract.num_outcomes], &outcome_pks);
+        assert_eq!(omega_contract.creator_bond.amount, 0);
+        // and gives the contract its default oracle fee rate
+        assert_eq!(omega_contract.oracle_fee.fee_bps, 30);
+    }
+
+    #[test]
+    fn test_parimutuel_payout() {
+        let pools = [300, 100, 0];
+        assert_eq!(parimutuel_payout(100, &pools, 1, 0).unwrap(), 400);
+        assert_eq!(parimutuel_payout(100, &pools, 1, 250).unwrap(), 390);
+        assert_eq!(parimutuel_payout(33, &pools, 0, 0).unwrap(), 44);
+        assert_eq!(parimutuel_payout(300, &pools, 0, MAX_BPS).unwrap(), 0);
+
+        // individual payouts never add up to more than the prize
+        let pools = [u64::MAX / 2, u64::MAX / 2];
+        let prize = parimutuel_payout(pools[0], &pools, 0, 100).unwrap();
+        let split = parimutuel_payout(pools[0] / 3, &pools, 0, 100).unwrap() * 3
+            + parimutuel_payout(pools[0] % 3, &pools, 0, 100).unwrap();
+        assert!(split <= prize);
+
+        // a pot larger than a u64 is fine, a payout larger than one is not
+        let overflow = Err(OmegaErrorCode::MathOverflow.into());
+        let pools = [u64::MAX, u64::MAX];
+        assert_eq!(parimutuel_payout(u64::MAX / 2, &pools, 0, 0).unwrap(), u64::MAX - 1);
+        assert_eq!(parimutuel_payout(u64::MAX, &pools, 0, 0), overflow);
+        assert_eq!(parimutuel_payout(u64::MAX, &pools, 0, MAX_BPS + 1), overflow);
+        assert_eq!(parimutuel_payout(1, &[0, 1], 0, 0), overflow);
+    }
+
+    #[test]
+    fn test_vault_solvency() {
+        let outcomes = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let mut omega_contract = OmegaContract::zeroed();
+        omega_contract.quote_mint = Pubkey::new_unique();
+        omega_contract.signer_key = Pubkey::new_unique();
+        omega_contract.num_outcomes = 2;
+        omega_contract.outcomes[..2].copy_from_slice(&outcomes);
+        let mut supplies = [0u64; MAX_OUTCOMES];
+        supplies[..2].copy_from_slice(&[100, 40]);
+
+        // any outcome can still win, then only the winner is paid, or everyone at the auto expiry rate
+        assert_eq!(owed_to_holders(&omega_contract, &supplies).unwrap(), 100);
+        omega_contract.auto_exp_redemptions = 1;
+        assert_eq!(owed_to_holders(&omega_contract, &supplies).unwrap(), 70);
+        omega_contract.winner = outcomes[1];
+        assert_eq!(owed_to_holders(&omega_contract, &supplies).unwrap(), 40);
+
+        let mut parimutuel = omega_contract;
+        parimutuel.mode = ContractMode::Parimutuel as u64;
+        parimutuel.parimutuel.pools[..2].copy_from_slice(&[100, 40]);
+        assert_eq!(owed_to_holders(&parimutuel, &supplies).unwrap(), 140);
+        parimutuel.winner = Pubkey::default();
+        assert_eq!(owed_to_holders(&parimutuel, &supplies).unwrap(), 140);
+
+        let vault_pk = Pubkey::new_unique();
+        let (quote_mint, signer_key) = (omega_contract.quote_mint, omega_contract.signer_key);
+        let vault_at = |amount: u64| {
+            let vault = spl_token::state::Account {
+                mint: quote_mint,
+                owner: signer_key,
+                amount,
+                state: spl_token::state::AccountState::Initialized,
+                ..spl_token::state::Account::default()
+            };
+            let mut vault_acc = Account::new(0, spl_token::state::Account::LEN, &spl_token::id());
+            spl_token::state::Account::pack(vault, &mut vault_acc.data).unwrap();
+            vault_acc
+        };
+        omega_contract.winner = Pubkey::default();
+        omega_contract.auto_exp_redemptions = 0;
+        for (amount, solvent) in [(99, false), (100, true)].iter() {
+            let mut vault_acc = vault_at(*amount);
+            let mut meta = [(&vault_pk, false, &mut vault_acc)];
+            let vault_infos = create_is_signer_account_infos(&mut meta);
+            let result = check_vault_solvency(&omega_contract, &vault_infos[0], &supplies);
+            assert_eq!(result.is_ok(), *solvent);
+        }
+
+        // the unpaid oracle reward is owed on top of what holders can redeem
+        omega_contract.oracle_fee.reserve = 5;
+        for (amount, solvent) in [(104, false), (105, true)].iter() {
+            let mut vault_acc = vault_at(*amount);
+            let mut meta = [(&vault_pk, false, &mut vault_acc)];
+            let vault_infos = create_is_signer_account_infos(&mut meta);
+            let result = check_vault_solvency(&omega_contract, &vault_infos[0], &supplies);
+            assert_eq!(result.is_ok(), *solvent);
+        }
+    }
+
+    #[test]
+    fn test_pack_unpack_instructions() {
+        let instructions = vec![
+            OmegaInstruction::IssueSet { quantity: 7 },
+            OmegaInstruction::LmsrBuy { outcome: 1, quantity: 20_000, max_cost: 15_000 },
+            OmegaInstruction::MatchSets { side: Side::Ask.into(), limit: 3 },
+            OmegaInstruction::InitBucketGroup { num_breakpoints: 2, breakpoints: [-5, 10, 0, 0, 0, 0, 0] },
+            OmegaInstruction::ResolveValue { value: -3 },
+            OmegaInstruction::Resolve { evidence_hash: [3u8; 32] },
+            OmegaInstruction::InitOracleBond { amount: 5_000, dispute_window: 86400 },
+            OmegaInstruction::ReleaseOracleBond,
+            OmegaInstruction::SlashOracleBond,
+            OmegaInstruction::OverturnResolution,
+            OmegaInstruction::InitOracleFee { amount: 1_000, fee_bps: 50, deadline: 1_612_224_000 },
+            OmegaInstruction::WithdrawOracleFee,
+            OmegaInstruction::ResolveLate,
+            OmegaInstruction::InitOracleLiveness { heartbeat_period: 3600 },
+            OmegaInstruction::OracleHeartbeat,
+            OmegaInstruction::InitOracleReputation,
+            OmegaInstruction::RecordOracleReputation,
+            OmegaInstruction::InitVoteResolution { voting_period: 86400, quorum: 1_000_000, tie_break: 2, tie_break_outcome: 1 },
+            OmegaInstruction::CastVote { outcome: 1, amount: 500 },
+            OmegaInstruction::FinalizeVote,
+            OmegaInstruction::WithdrawVote,
+            OmegaInstruction::ReleaseCreatorBond,
+            OmegaInstruction::ForfeitCreatorBond,
+            OmegaInstruction::SetAllowList { root: [9u8; 32] },
+            OmegaInstruction::SetIssuanceCaps { max_supply: 1_000_000, wallet_cap: 10_000 },
+            OmegaInstruction::CreateSeriesContract { contract_nonce: 254, signer_nonce: 1 },
+            OmegaInstruction::InitRegistry,
+            OmegaInstruction::UpdateRegistryEntry { category: 4 },
+            OmegaInstruction::CloseContract,
+            OmegaInstruction::InitProgramConfig {
+                timelock: 86400, fee_bps: 30, restrict_creators: 0, num_quote_mints: 1, creator_bond: 0
+            },
+            OmegaInstruction::ProposeConfig {
+                timelock: 3600, fee_bps: 0, restrict_creators: 1, num_quote_mints: 2, creator_bond: 50_000
+            },
+            OmegaInstruction::ApplyConfig,
+        ];
+        for instruction in instructions {
+            assert_eq!(OmegaInstruction::unpack(&instruction.pack()), Some(instruction));
+        }
+    }
+
+    #[test]
+    fn test_bucket() {
+        let mut omega_contract = OmegaContract::zeroed();
+        omega_contract.num_breakpoints = 2;
+        omega_contract.breakpoints[..2].copy_from_slice(&[30_000, 40_000]);
+        assert_eq!(omega_contract.bucket(i64::MIN), 0);
+        assert_eq!(omega_contract.bucket(29_999), 0);
+        assert_eq!(omega_contract.bucket(30_000), 1);
+        assert_eq!(omega_contract.bucket(39_999), 1);
+        assert_eq!(omega_contract.bucket(40_000), 2);
+    }
+
+    #[test]
+    fn test_init_bucket_group() {
+        let program_id = Pubkey::new_unique();
+        let omega_contract_pk = Pubkey::new_unique();
+        let oracle_pk = Pubkey::new_unique();
+        let outcomes = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+
+        let mut omega_contract = OmegaContract::zeroed();
+        omega_contract.account_flags = (AccountFlag::Initialized | AccountFlag::OmegaContract).bits();
+        omega_contract.oracle = oracle_pk;
+        omega_contract.exp_time = 1000;
+        omega_contract.auto_exp_time = 2000;
+        omega_contract.num_outcomes = 3;
+        omega_contract.outcomes[..3].copy_from_slice(&outcomes);
+
+        let mut accs = HashMap::new();
+        let mut omega_contract_acc = get_rent_exempt::<OmegaContract>(&program_id);
+        omega_contract_acc.data.copy_from_slice(bytes_of(&omega_contract));
+        accs.insert(omega_contract_pk, omega_contract_acc);
+        accs.insert(Pubkey::default(), token_account(&Pubkey::default(), &Pubkey::default(), 0));
+        for outcome_pk in outcomes.iter() {
+            accs.insert(*outcome_pk, mint_account(0));
+        }
+        let set_clock = |accs: &mut HashMap<Pubkey, Account>, curr_time: i64| {
+            accs.insert(solana_program::sysvar::clock::ID, clock_account(&Clock { unix_timestamp: curr_time, ..Clock::default() }));
+        };
+        let init = |oracle_pk: &Pubkey, breakpoints: &[i64]| {
+            init_bucket_group(&program_id, &omega_contract_pk, oracle_pk, breakpoints).unwrap()
+        };
+        let invalid_breakpoints = Err(ProgramError::Custom(OmegaErrorCode::InvalidBreakpoints.into()));
+
+        // one breakpoint less than outcomes, strictly increasing, set by the oracle
+        assert_eq!(process_with(init(&oracle_pk, &[30_000]), &mut accs), invalid_breakpoints);
+        assert_eq!(process_with(init(&oracle_pk, &[30_000, 30_000]), &mut accs), invalid_breakpoints);
+        assert!(process_with(init(&Pubkey::new_unique(), &[30_000, 40_000]), &mut accs).is_err());
+
+        // a contract without breakpoints can't be resolved from a value
+        set_clock(&mut accs, 1000);
+        let resolve = |value: i64| resolve_value(
+            &program_id, &omega_contract_pk, &oracle_pk, &Pubkey::default(), &outcomes, value
+        ).unwrap();
+        assert_eq!(process_with(resolve(35_000), &mut accs), invalid_breakpoints);
+
+        assert_eq!(process_with(init(&oracle_pk, &[30_000, 40_000]), &mut accs), Ok(()));
+        let contract = OmegaContract::load_from_bytes(&accs[&omega_contract_pk].data).unwrap();
+        assert_eq!((contract.num_breakpoints, &contract.breakpoints[..2]), (2, &[30_000, 40_000][..]));
+        assert!(process_with(init(&oracle_pk, &[10_000, 20_000]), &mut accs).is_err());
+
+        // the value picks the bucket it falls in, once the contract expired
+        set_clock(&mut accs, 999);
+        assert!(process_with(resolve(35_000), &mut accs).is_err());
+        set_clock(&mut accs, 1000);
+        assert_eq!(process_with(resolve(35_000), &mut accs), Ok(()));
+        let contract = OmegaContract::load_from_bytes(&accs[&omega_contract_pk].data).unwrap();
+        assert_eq!((contract.winner, contract.resolved_value), (outcomes[1], 35_000));
+    }
+
+    #[test]
+    fn test_resolve_from_price() {
+        let program_id = Pubkey::new_unique();
+        let omega_contract_pk = Pubkey::new_unique();
+        let price_pk = Pubkey::new_unique();
+        let price_program_pk = Pubkey::new_unique();
+        let vault_pk = Pubkey::new_unique();
+        let outcomes = [Pubkey::new_unique(), Pubkey::new_unique()];
+
+        let mut omega_contract = OmegaContract::zeroed();
+        omega_contract.account_flags = (AccountFlag::Initialized | AccountFlag::OmegaContract).bits();
+        omega_contract.vault = vault_pk;
+        omega_contract.exp_time = 1000;
+        omega_contract.auto_exp_time = 2000;
+        omega_contract.num_outcomes = 2;
+        omega_contract.outcomes[..2].copy_from_slice(&outcomes);
+        omega_contract.price_resolution = PriceResolution {
+            price_acc: price_pk,
+            price_program: price_program_pk,
+            source: PriceSourceKind::Simple.into(),
+            comparison: Comparison::GreaterThan.into(),
+            threshold: 3000,
+            max_staleness: 60
+        };
+        let mut omega_contract_acc = get_rent_exempt::<OmegaContract>(&program_id);
+        omega_contract_acc.data.copy_from_slice(bytes_of(&omega_contract));
+
+        // mock price account in the simple layout
+        let resolve_from = |price_acc: &mut Account, curr_time: i64| {
+            let mut contract_acc = omega_contract_acc.clone();
+            let clock = Clock { unix_timestamp: curr_time, ..Clock::default() };
+            let mut clock_acc = clock_account(&clock);
+
+            let instruction = resolve_from_price(&program_id, &omega_contract_pk, &price_pk, &vault_pk, &outcomes).unwrap();
+            let result = do_process_instruction(
+                instruction,
+                vec![
+                    &mut contract_acc, price_acc, &mut clock_acc, &mut token_account(&Pubkey::default(), &Pubkey::default(), 0),
+                    &mut mint_account(0), &mut mint_account(0)
+                ]
+            );
+            let contract = *OmegaContract::load_from_bytes(&contract_acc.data).unwrap();
+            (result, contract.winner, contract.resolved_value)
+        };
+        let resolve_at = |price: i64, timestamp: u64, curr_time: i64| {
+            let mut price_acc = Account::new(1, size_of::<SimplePrice>(), &price_program_pk);
+            price_acc.data.copy_from_slice(bytes_of(&SimplePrice { price, timestamp }));
+            resolve_from(&mut price_acc, curr_time)
+        };
+
+        assert_eq!(resolve_at(3100, 1010, 1020), (Ok(()), outcomes[0], 3100));
+        assert_eq!(resolve_at(3000, 995, 1020), (Ok(()), outcomes[1], 3000));
+
+        let stale = Err(ProgramError::Custom(OmegaErrorCode::StalePrice.into()));
+        assert_eq!(resolve_at(3100, 900, 1020).0, stale);
+        assert_eq!(resolve_at(3100, 1500, 1600).0, stale);
+        assert!(resolve_at(3100, 990, 999).0.is_err());
+        assert!(resolve_at(3100, 1990, 2000).0.is_err());
+
+        // only the program the contract was set up with can write the price
+        let mut forged_acc = Account::new(1, size_of::<SimplePrice>(), &Pubkey::new_unique());
+        forged_acc.data.copy_from_slice(bytes_of(&SimplePrice { price: 3100, timestamp: 1010 }));
+        assert!(resolve_from(&mut forged_acc, 1020).0.is_err());
+
+        let mut pyth_price = PythPrice::zeroed();
+        pyth_price.magic = PYTH_MAGIC;
+        pyth_price.ver = PYTH_VERSION;
+        pyth_price.atype = PYTH_PRICE_ACCOUNT;
+        pyth_price.expo = -2;
+        pyth_price.timestamp = 1010;
+        pyth_price.agg_price = 2999;
+        pyth_price.agg_status = PYTH_STATUS_TRADING;
+        assert_eq!(
+            PriceSourceKind::Pyth.read_price(bytes_of(&pyth_price)).unwrap(),
+            Price { price: 2999, timestamp: 1010 }
+        );
+        pyth_price.agg_status = 0;
+        let stale = Err(OmegaErrorCode::StalePrice.into());
+        assert_eq!(PriceSourceKind::Pyth.read_price(bytes_of(&pyth_price)), stale);
+        pyth_price.agg_status = PYTH_STATUS_TRADING;
+        pyth_price.magic = 0;
+        assert!(PriceSourceKind::Pyth.read_price(bytes_of(&pyth_price)).is_err());
+
+        // the oracle can't pick the winner of a contract that resolves from a price
+        let oracle_pk = Pubkey::new_unique();
+        omega_contract.oracle = oracle_pk;
+        let resolve_by_oracle = |omega_contract: &OmegaContract| {
+            let mut contract_acc = get_rent_exempt::<OmegaContract>(&program_id);
+            contract_acc.data.copy_from_slice(bytes_of(omega_contract));
+            let instruction = resolve(
+                &program_id, &omega_contract_pk, &oracle_pk, &outcomes[1], &vault_pk, &outcomes, "", [0u8; 32]
+            ).unwrap();
+            let result = do_process_instruction(
+                instruction,
+                vec![
+                    &mut contract_acc, &mut Account::default(), &mut Account::default(),
+                    &mut clock_account(&Clock { unix_timestamp: 1020, ..Clock::default() }),
+                    &mut token_account(&Pubkey::default(), &Pubkey::default(), 0), &mut mint_account(0), &mut mint_account(0)
+                ]
+            );
+            (result, OmegaContract::load_from_bytes(&contract_acc.data).unwrap().winner)
+        };
+        assert_eq!(resolve_by_oracle(&omega_contract).1, Pubkey::default());
+        assert!(resolve_by_oracle(&omega_contract).0.is_err());
+        omega_contract.price_resolution.price_acc = Pubkey::default();
+        assert_eq!(resolve_by_oracle(&omega_contract), (Ok(()), outcomes[1]));
+    }
+
+    #[test]
+    fn test_resolve_with_evidence() {
+        let program_id = Pubkey::new_unique();
+        let omega_contract_pk = Pubkey::new_unique();
+        let oracle_pk = Pubkey::new_unique();
+        let vault_pk = Pubkey::new_unique();
+        let outcomes = [Pubkey::new_unique(), Pubkey::new_unique()];
+
+        let mut omega_contract = OmegaContract::zeroed();
+        omega_contract.account_flags = (AccountFlag::Initialized | AccountFlag::OmegaContract).bits();
+        omega_contract.oracle = oracle_pk;
+        omega_contract.vault = vault_pk;
+        omega_contract.exp_time = 1000;
+        omega_contract.auto_exp_time = 2000;
+        omega_contract.num_outcomes = 2;
+        omega_contract.outcomes[..2].copy_from_slice(&outcomes);
+        let mut omega_contract_acc = get_rent_exempt::<OmegaContract>(&program_id);
+        omega_contract_acc.data.copy_from_slice(bytes_of(&omega_contract));
+
+        let mut clock_acc = clock_account(&Clock { slot: 42, unix_timestamp: 1500, ..Clock::default() });
+        let mut vault_acc = token_account(&Pubkey::default(), &Pubkey::default(), 0);
+        let resolve_with = |uri: &str| {
+            let mut contract_acc = omega_contract_acc.clone();
+            let instruction = resolve(
+                &program_id, &omega_contract_pk, &oracle_pk, &outcomes[1], &vault_pk, &outcomes, uri, [7u8; 32]
+            ).unwrap();
+            let result = do_process_instruction(
+                instruction,
+                vec![
+                    &mut contract_acc, &mut Account::default(), &mut Account::default(), &mut clock_acc.clone(),
+                    &mut vault_acc.clone(), &mut mint_account(0), &mut mint_account(0)
+                ]
+            );
+            (result, *OmegaContract::load_from_bytes(&contract_acc.data).unwrap())
+        };
+
+        let (result, contract) = resolve_with("https://example.com/results.json");
+        assert_eq!(result, Ok(()));
+        assert_eq!(contract.winner, outcomes[1]);
+        assert_eq!(contract.evidence_uri(), b"https://example.com/results.json");
+        assert_eq!(contract.evidence_hash, [7u8; 32]);
+        assert_eq!((contract.resolved_slot, contract.resolved_time), (42, 1500));
+
+        assert!(resolve_with(&"x".repeat(EVIDENCE_URI_LEN + 1)).0.is_err());
+
+        // old clients send no evidence at all
+        let mut instruction = resolve(
+            &program_id, &omega_contract_pk, &oracle_pk, &outcomes[0], &vault_pk, &outcomes, "", [0u8; 32]
+        ).unwrap();
+        instruction.data.truncate(4);
+        let result = do_process_instruction(
+            instruction,
+            vec![
+                &mut omega_contract_acc, &mut Account::default(), &mut Account::default(), &mut clock_acc,
+                &mut vault_acc, &mut mint_account(0), &mut mint_account(0)
+            ]
+        );
+        assert_eq!(result, Ok(()));
+        let contract = OmegaContract::load_from_bytes(&omega_contract_acc.data).unwrap();
+        assert_eq!(contract.winner, outcomes[0]);
+        assert_eq!(contract.evidence_uri_len, 0);
+    }
+
+    #[test]
+    fn test_oracle_fee() {
+        let mut oracle_fee = OracleFee {
+            funder: Pubkey::new_unique(),
+            amount: 1_000,
+            fee_bps: 0,
+            deadline: 2000,
+            reserve: 1_000
+        };
+        assert_eq!(oracle_fee.reward(1000, 1000, 0).unwrap(), 1_000);
+        assert_eq!(oracle_fee.reward(1000, 1250, 0).unwrap(), 750);
+        assert_eq!(oracle_fee.reward(1000, 1999, 0).unwrap(), 1);
+        assert_eq!(oracle_fee.reward(1000, 2000, 0).unwrap(), 0);
+
+        // basis points of open interest, capped at the funded amount
+        oracle_fee.fee_bps = 100;
+        assert_eq!(oracle_fee.reward(1000, 1500, 50_000).unwrap(), 250);
+        assert_eq!(oracle_fee.reward(1000, 1000, 500_000).unwrap(), 1_000);
+
+        // never more than what is left in the reserve
+        oracle_fee.reserve = 100;
+        assert_eq!(oracle_fee.reward(1000, 1000, 500_000).unwrap(), 100);
+    }
+
+    #[test]
+    fn test_oracle_bond() {
+        let mut bond = OracleBond {
+            escrow: Pubkey::new_unique(),
+            amount: 1_000,
+            dispute_window: 100,
+            status: BondStatus::Posted as u64,
+            slashed_supply: 0
+        };
+        assert!(bond.is_at_stake(1500, 1599));
+        assert!(!bond.is_at_stake(1500, 1600));
+        assert_eq!(bond.share(300).unwrap(), 0);
+
+        bond.status = BondStatus::Slashed as u64;
+        bond.slashed_supply = 3_000;
+        assert!(!bond.is_at_stake(1500, 1550));
+        assert_eq!(bond.share(300).unwrap(), 100);
+        assert_eq!(bond.share(3_000).unwrap(), 1_000);
+
+        // the bond can't be released while the resolution can still be overturned
+        let program_id = Pubkey::new_unique();
+        let omega_contract_pk = Pubkey::new_unique();
+        let mut omega_contract = OmegaContract::zeroed();
+        omega_contract.account_flags = (AccountFlag::Initialized | AccountFlag::OmegaContract).bits();
+        omega_contract.winner = Pubkey::new_unique();
+        omega_contract.resolved_time = 1500;
+        omega_contract.bond = OracleBond { status: BondStatus::Posted as u64, slashed_supply: 0, ..bond };
+        let mut omega_contract_acc = get_rent_exempt::<OmegaContract>(&program_id);
+        omega_contract_acc.data.copy_from_slice(bytes_of(&omega_contract));
+
+        let instruction = release_oracle_bond(
+            &program_id, &omega_contract_pk, &bond.escrow, &Pubkey::new_unique(), &Pubkey::new_unique()
+        ).unwrap();
+        let mut clock_acc = clock_account(&Clock { unix_timestamp: 1550, ..Clock::default() });
+        let result = do_process_instruction(
+            instruction,
+            vec![
+                &mut omega_contract_acc, &mut Account::default(), &mut Account::default(),
+                &mut Account::default(), &mut Account::default(), &mut clock_acc
+            ]
+        );
+        assert_eq!(result, Err(ProgramError::Custom(OmegaErrorCode::BondAtStake.into())));
+
+        // nor can the parimutuel fee be collected, since it depends on what the holders are owed
+        let fee_pk = Pubkey::new_unique();
+        omega_contract.mode = ContractMode::Parimutuel as u64;
+        omega_contract.parimutuel.fee_acc = fee_pk;
+        omega_contract_acc.data.copy_from_slice(bytes_of(&omega_contract));
+        let instruction = collect_parimutuel_fee(
+            &program_id, &omega_contract_pk, &fee_pk, &Pubkey::default(), &Pubkey::new_unique(), &omega_contract.winner
+        ).unwrap();
+        let result = do_process_instruction(
+            instruction,
+            vec![
+                &mut omega_contract_acc, &mut Account::default(), &mut Account::default(),
+                &mut Account::default(), &mut Account::default(), &mut Account::default(), &mut clock_acc
+            ]
+        );
+        assert_eq!(result, Err(ProgramError::Custom(OmegaErrorCode::BondAtStake.into())));
+
+        // once slashed, new tokens would dilute the bond
+        omega_contract.bond.status = BondStatus::Slashed as u64;
+        assert!(check_not_slashed(&omega_contract).is_err());
+        assert!(check_bond_settled(&omega_contract, 1550).is_ok());
+    }
+
+    #[test]
+    fn test_resolve_late() {
+        let program_id = Pubkey::new_unique();
+        let omega_contract_pk = Pubkey::new_unique();
+        let admin_pk = Pubkey::new_unique();
+        let outcomes = [Pubkey::new_unique(), Pubkey::new_unique()];
+
+        let mut program_config = ProgramConfig::zeroed();
+        program_config.account_flags = (AccountFlag::Initialized | AccountFlag::ProgramConfig).bits();
+        program_config.settings.admin = admin_pk;
+        let mut program_config_acc = get_rent_exempt::<ProgramConfig>(&program_id);
+        program_config_acc.data.copy_from_slice(bytes_of(&program_config));
+
+        let mut omega_contract = OmegaContract::zeroed();
+        omega_contract.account_flags = (AccountFlag::Initialized | AccountFlag::OmegaContract).bits();
+        omega_contract.exp_time = 1000;
+        omega_contract.auto_exp_time = 2000;
+        omega_contract.num_outcomes = 2;
+        omega_contract.outcomes[..2].copy_from_slice(&outcomes);
+
+        let mut winner_mint_acc = Account::new(0, Mint::LEN, &spl_token::ID);
+        Mint::pack(Mint { supply: 500, is_initialized: true, ..Mint::default() }, &mut winner_mint_acc.data).unwrap();
+
+        let resolve_late_at = |omega_contract: &OmegaContract, admin_pk: &Pubkey, curr_time: i64| {
+            let mut contract_acc = get_rent_exempt::<OmegaContract>(&program_id);
+            contract_acc.data.copy_from_slice(bytes_of(omega_contract));
+            let instruction = resolve_late(&program_id, &omega_contract_pk, admin_pk, &outcomes[1], None).unwrap();
+            let result = do_process_instruction(
+                instruction,
+                vec![
+                    &mut contract_acc, &mut program_config_acc.clone(), &mut Account::default(),
+                    &mut winner_mint_acc.clone(),
+                    &mut clock_account(&Clock { unix_timestamp: curr_time, ..Clock::default() })
+                ]
+            );
+            (result, *OmegaContract::load_from_bytes(&contract_acc.data).unwrap())
+        };
+
+        let (result, contract) = resolve_late_at(&omega_contract, &admin_pk, 2500);
+        assert_eq!(result, Ok(()));
+        assert_eq!((contract.winner, contract.resolved_time), (outcomes[1], 2500));
+
+        assert!(resolve_late_at(&omega_contract, &admin_pk, 1999).0.is_err());
+        assert!(resolve_late_at(&omega_contract, &admin_pk, 2000 + LATE_RESOLUTION_WINDOW as i64).0.is_err());
+        assert!(resolve_late_at(&omega_contract, &Pubkey::new_unique(), 2500).0.is_err());
+
+        // holders already redeemed at the auto expiry rate
+        omega_contract.auto_exp_redemptions = 1;
+        assert!(resolve_late_at(&omega_contract, &admin_pk, 2500).0.is_err());
+
+        // a bond slashed at auto expiry now only goes to the holders of the winner
+        omega_contract.auto_exp_redemptions = 0;
+        omega_contract.bond.status = BondStatus::Slashed as u64;
+        omega_contract.bond.slashed_supply = 1_000;
+        let (result, contract) = resolve_late_at(&omega_contract, &admin_pk, 2500);
+        assert_eq!(result, Ok(()));
+        assert_eq!(contract.bond.slashed_supply, 500);
+    }
+
+    #[test]
+    fn test_oracle_liveness() {
+        let program_id = Pubkey::new_unique();
+        let omega_contract_pk = Pubkey::new_unique();
+        let oracle_pk = Pubkey::new_unique();
+        let backup_oracle_pk = Pubkey::new_unique();
+        let outcomes = [Pubkey::new_unique(), Pubkey::new_unique()];
+
+        let mut omega_contract = OmegaContract::zeroed();
+        omega_contract.account_flags = (AccountFlag::Initialized | AccountFlag::OmegaContract).bits();
+        omega_contract.oracle = oracle_pk;
+        omega_contract.exp_time = 1500;
+        omega_contract.auto_exp_time = 3000;
+        omega_contract.num_outcomes = 2;
+        omega_contract.outcomes[..2].copy_from_slice(&outcomes);
+        let mut omega_contract_acc = get_rent_exempt::<OmegaContract>(&program_id);
+        omega_contract_acc.data.copy_from_slice(bytes_of(&omega_contract));
+
+        let clock_at = |curr_time: i64| clock_account(&Clock { unix_timestamp: curr_time, ..Clock::default() });
+
+        let instruction = init_oracle_liveness(&program_id, &omega_contract_pk, &oracle_pk, &backup_oracle_pk, 500).unwrap();
+        let result = do_process_instruction(
+            instruction, vec![&mut omega_contract_acc, &mut Account::default(), &mut Account::default(), &mut clock_at(1000)]
+        );
+        assert_eq!(result, Ok(()));
+
+        let instruction = oracle_heartbeat(&program_id, &omega_contract_pk, &oracle_pk).unwrap();
+        let result = do_process_instruction(
+            instruction, vec![&mut omega_contract_acc, &mut Account::default(), &mut clock_at(1400)]
+        );
+        assert_eq!(result, Ok(()));
+        assert_eq!(OmegaContract::load_from_bytes(&omega_contract_acc.data).unwrap().liveness.last_heartbeat, 1400);
+
+        let resolve_at = |oracle_pk: &Pubkey, curr_time: i64| {
+            let mut contract_acc = omega_contract_acc.clone();
+            let instruction = resolve(
+                &program_id, &omega_contract_pk, oracle_pk, &outcomes[0], &Pubkey::default(), &outcomes, "", [0u8; 32]
+            ).unwrap();
+            do_process_instruction(
+                instruction,
+                vec![
+                    &mut contract_acc, &mut Account::default(), &mut Account::default(), &mut clock_at(curr_time),
+                    &mut token_account(&Pubkey::default(), &Pubkey::default(), 0), &mut mint_account(0), &mut mint_account(0)
+                ]
+            )
+        };
+        assert_eq!(resolve_at(&oracle_pk, 1800), Ok(()));
+        assert!(resolve_at(&backup_oracle_pk, 1800).is_err());
+        assert!(resolve_at(&oracle_pk, 2100).is_err());
+        assert_eq!(resolve_at(&backup_oracle_pk, 2100), Ok(()));
+
+        // the oracle can't take the rights back after missing a heartbeat
+        let instruction = oracle_heartbeat(&program_id, &omega_contract_pk, &oracle_pk).unwrap();
+        let result = do_process_instruction(
+            instruction, vec![&mut omega_contract_acc, &mut Account::default(), &mut clock_at(1901)]
+        );
+        assert_eq!(result, Err(ProgramError::Custom(OmegaErrorCode::OracleLapsed.into())));
+    }
+
+    #[test]
+    fn test_oracle_reputation() {
+        let program_id = Pubkey::new_unique();
+        let omega_contract_pk = Pubkey::new_unique();
+        let oracle_pk = Pubkey::new_unique();
+        let outcomes = [Pubkey::new_unique(), Pubkey::new_unique()];
+
+        let mut omega_contract = OmegaContract::zeroed();
+        omega_contract.account_flags = (AccountFlag::Initialized | AccountFlag::OmegaContract).bits();
+        omega_contract.oracle = oracle_pk;
+        omega_contract.exp_time = 1000;
+        omega_contract.auto_exp_time = 2000;
+        omega_contract.num_outcomes = 2;
+        omega_contract.outcomes[..2].copy_from_slice(&outcomes);
+        let mut omega_contract_acc = get_rent_exempt::<OmegaContract>(&program_id);
+        omega_contract_acc.data.copy_from_slice(bytes_of(&omega_contract));
+        let mut expired_contract_acc = omega_contract_acc.clone();
+        let mut third_contract_acc = omega_contract_acc.clone();
+
+        let new_reputation_acc = |oracle_pk: &Pubkey| {
+            let mut oracle_reputation = OracleReputation::zeroed();
+            oracle_reputation.account_flags = (AccountFlag::Initialized | AccountFlag::OracleReputation).bits();
+            oracle_reputation.oracle = *oracle_pk;
+            let mut oracle_reputation_acc = get_rent_exempt::<OracleReputation>(&program_id);
+            oracle_reputation_acc.data.copy_from_slice(bytes_of(&oracle_reputation));
+            oracle_reputation_acc
+        };
+        let mut oracle_reputation_acc = new_reputation_acc(&oracle_pk);
+        let mut other_reputation_acc = new_reputation_acc(&Pubkey::new_unique());
+        let clock_at = |curr_time: i64| clock_account(&Clock { unix_timestamp: curr_time, ..Clock::default() });
+
+        let instruction = resolve(
+            &program_id, &omega_contract_pk, &oracle_pk, &outcomes[0], &Pubkey::default(), &outcomes, "", [0u8; 32]
+        ).unwrap();
+        let result = do_process_instruction(
+            instruction,
+            vec![
+                &mut omega_contract_acc, &mut Account::default(), &mut Account::default(), &mut clock_at(1300),
+                &mut token_account(&Pubkey::default(), &Pubkey::default(), 0), &mut mint_account(0), &mut mint_account(0)
+            ]
+        );
+        assert_eq!(result, Ok(()));
+
+        let record = |contract_acc: &mut Account, reputation_acc: &mut Account, curr_time: i64| {
+            let instruction = record_oracle_reputation(&program_id, &omega_contract_pk, &oracle_pk).unwrap();
+            let result = do_process_instruction(instruction, vec![contract_acc, reputation_acc, &mut clock_at(curr_time)]);
+            assert_eq!(result, Ok(()));
+            *OracleReputation::load_from_bytes(&reputation_acc.data).unwrap()
+        };
+
+        // only the oracle that resolved gets the credit, and only once
+        assert_eq!(record(&mut omega_contract_acc, &mut other_reputation_acc, 1400).num_resolved, 0);
+        assert_eq!(record(&mut omega_contract_acc, &mut oracle_reputation_acc, 1400).num_resolved, 1);
+        let reputation = record(&mut omega_contract_acc, &mut oracle_reputation_acc, 1500);
+        assert_eq!((reputation.num_resolved, reputation.average_delay()), (1, 300));
+
+        // nobody resolved the other contract before auto_exp_time
+        assert_eq!(record(&mut expired_contract_acc, &mut oracle_reputation_acc, 1999).auto_expiries, 0);
+        let reputation = record(&mut expired_contract_acc, &mut oracle_reputation_acc, 2000);
+        assert_eq!((reputation.num_resolved, reputation.auto_expiries, reputation.disputes_lost), (1, 1, 0));
+        assert_eq!(record(&mut expired_contract_acc, &mut oracle_reputation_acc, 2100).auto_expiries, 1);
+
+        // resolving with the reputation attached records it right away
+        let instruction = with_oracle_reputation(
+            resolve(
+                &program_id, &omega_contract_pk, &oracle_pk, &outcomes[1], &Pubkey::default(), &outcomes, "", [0u8; 32]
+            ).unwrap(),
+            &oracle_pk
+        );
+        let result = do_process_instruction(
+            instruction,
+            vec![
+                &mut third_contract_acc, &mut Account::default(), &mut Account::default(), &mut clock_at(1100),
+                &mut token_account(&Pubkey::default(), &Pubkey::default(), 0), &mut mint_account(0), &mut mint_account(0), &mut oracle_reputation_acc
+            ]
+        );
+        assert_eq!(result, Ok(()));
+        let reputation = record(&mut third_contract_acc, &mut oracle_reputation_acc, 1200);
+        assert_eq!((reputation.num_resolved, reputation.average_delay()), (2, 200));
+        assert_eq!(OracleReputation::zeroed().average_delay(), 0);
+    }
+
+    #[test]
+    fn test_vote_resolution() {
+        let program_id = Pubkey::new_unique();
+        let omega_contract_pk = Pubkey::new_unique();
+        let oracle_pk = Pubkey::new_unique();
+        let outcomes = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+
+        let mut omega_contract = OmegaContract::zeroed();
+        omega_contract.account_flags = (AccountFlag::Initialized | AccountFlag::OmegaContract).bits();
+        omega_contract.oracle = oracle_pk;
+        omega_contract.exp_time = 1000;
+        omega_contract.auto_exp_time = 3000;
+        omega_contract.num_outcomes = 3;
+        omega_contract.outcomes[..3].copy_from_slice(&outcomes);
+        omega_contract.vote.gov_mint = Pubkey::new_unique();
+        omega_contract.vote.voting_period = 1000;
+        omega_contract.vote.quorum = 100;
+        omega_contract.vote.tallies[..3].copy_from_slice(&[40, 40, 30]);
+        omega_contract.vote.total_votes = 110;
+
+        // a tie between the leading outcomes is settled by the tie break
+        assert_eq!(omega_contract.vote.winner(3), None);
+        omega_contract.vote.tie_break = TieBreak::LowestIndex.into();
+        assert_eq!(omega_contract.vote.winner(3), Some(0));
+        omega_contract.vote.tie_break = TieBreak::Designated.into();
+        omega_contract.vote.tie_break_outcome = 2;
+        assert_eq!(omega_contract.vote.winner(3), Some(2));
+        omega_contract.vote.tallies[1] = 50;
+        omega_contract.vote.total_votes = 120;
+        assert_eq!(omega_contract.vote.winner(3), Some(1));
+        omega_contract.vote.quorum = 121;
+        assert_eq!(omega_contract.vote.winner(3), None);
+        omega_contract.vote.quorum = 120;
+
+        let mut omega_contract_acc = get_rent_exempt::<OmegaContract>(&program_id);
+        omega_contract_acc.data.copy_from_slice(bytes_of(&omega_contract));
+        let clock_at = |curr_time: i64| clock_account(&Clock { unix_timestamp: curr_time, ..Clock::default() });
+
+        // the oracle gave up its resolution rights
+        let instruction = resolve(
+            &program_id, &omega_contract_pk, &oracle_pk, &outcomes[0], &Pubkey::default(), &outcomes, "", [0u8; 32]
+        ).unwrap();
+        let result = do_process_instruction(
+            instruction,
+            vec![
+                &mut omega_contract_acc, &mut Account::default(), &mut Account::default(), &mut clock_at(1500),
+                &mut token_account(&Pubkey::default(), &Pubkey::default(), 0), &mut mint_account(0), &mut mint_account(0), &mut mint_account(0)
+            ]
+        );
+        assert!(result.is_err());
+
+        let mut finalize_at = |curr_time: i64| {
+            let instruction = finalize_vote(&program_id, &omega_contract_pk, &Pubkey::default(), &outcomes).unwrap();
+            do_process_instruction(
+                instruction,
+                vec![
+                    &mut omega_contract_acc, &mut clock_at(curr_time), &mut token_account(&Pubkey::default(), &Pubkey::default(), 0),
+                    &mut mint_account(0), &mut mint_account(0), &mut mint_account(0)
+                ]
+            )
+        };
+        assert!(finalize_at(1999).is_err());
+        assert_eq!(finalize_at(2000), Ok(()));
+        assert!(finalize_at(2001).is_err());
+        let omega_contract = OmegaContract::load_from_bytes(&omega_contract_acc.data).unwrap();
+        assert_eq!((omega_contract.winner, omega_contract.resolved_time), (outcomes[1], 2000));
+        assert_eq!(omega_contract.resolver, Pubkey::default());
+
+        let mut inconclusive = *omega_contract;
+        inconclusive.winner = Pubkey::default();
+        inconclusive.vote.tallies[1] = 40;
+        inconclusive.vote.total_votes = 110;
+        let mut inconclusive_acc = get_rent_exempt::<OmegaContract>(&program_id);
+        inconclusive_acc.data.copy_from_slice(bytes_of(&inconclusive));
+        let instruction = finalize_vote(&program_id, &omega_contract_pk, &Pubkey::default(), &outcomes).unwrap();
+        let result = do_process_instruction(
+            instruction,
+            vec![
+                &mut inconclusive_acc, &mut clock_at(2000), &mut token_account(&Pubkey::default(), &Pubkey::default(), 0),
+                &mut mint_account(0), &mut mint_account(0), &mut mint_account(0)
+            ]
+        );
+        assert_eq!(result, Err(ProgramError::Custom(OmegaErrorCode::VoteInconclusive.into())));
+    }
+
+    #[test]
+    fn test_creator_bond() {
+        let program_id = Pubkey::new_unique();
+        let omega_contract_pk = Pubkey::new_unique();
+        let admin_pk = Pubkey::new_unique();
+
+        let mut program_config = ProgramConfig::zeroed();
+        program_config.account_flags = (AccountFlag::Initialized | AccountFlag::ProgramConfig).bits();
+        program_config.settings.admin = admin_pk;
+        program_config.settings.creator_bond = 1_000;
+        let mut program_config_acc = get_rent_exempt::<ProgramConfig>(&program_id);
+        program_config_acc.data.copy_from_slice(bytes_of(&program_config));
+
+        let mut omega_contract = OmegaContract::zeroed();
+        omega_contract.account_flags = (AccountFlag::Initialized | AccountFlag::OmegaContract).bits();
+        omega_contract.exp_time = 1000;
+        omega_contract.auto_exp_time = 2000;
+        omega_contract.creator_bond = CreatorBond {
+            creator: Pubkey::new_unique(),
+            escrow: Pubkey::new_unique(),
+            amount: 1_000,
+            status: BondStatus::Posted as u64
+        };
+        assert!(!omega_contract.is_resolved_invalid(2000));
+        assert!(omega_contract.is_resolved_invalid(2000 + LATE_RESOLUTION_WINDOW));
+        omega_contract.auto_exp_redemptions = 1;
+        assert!(!omega_contract.is_resolved_invalid(2000));
+        omega_contract.auto_exp_redemptions = 0;
+
+        omega_contract.winner = Pubkey::new_unique();
+        omega_contract.resolved_time = 1500;
+        assert!(!omega_contract.is_resolved_invalid(2000 + LATE_RESOLUTION_WINDOW));
+        let mut omega_contract_acc = get_rent_exempt::<OmegaContract>(&program_id);
+        omega_contract_acc.data.copy_from_slice(bytes_of(&omega_contract));
+        let clock_at = |curr_time: i64| clock_account(&Clock { unix_timestamp: curr_time, ..Clock::default() });
+
+        // the bond stays in escrow while the admin can still find the contract ambiguous
+        let escrow_pk = omega_contract.creator_bond.escrow;
+        let instruction = release_creator_bond(
+            &program_id, &omega_contract_pk, &escrow_pk, &Pubkey::new_unique(), &Pubkey::new_unique()
+        ).unwrap();
+        let result = do_process_instruction(
+            instruction,
+            vec![
+                &mut omega_contract_acc, &mut Account::default(), &mut Account::default(),
+                &mut Account::default(), &mut Account::default(), &mut clock_at(1600)
+            ]
+        );
+        assert_eq!(result, Err(ProgramError::Custom(OmegaErrorCode::BondAtStake.into())));
+
+        // only the admin can forfeit the bond of a contract that resolved normally
+        let instruction = forfeit_creator_bond(
+            &program_id, &omega_contract_pk, None, &escrow_pk, &Pubkey::new_unique(), &Pubkey::new_unique()
+        ).unwrap();
+        let result = do_process_instruction(
+            instruction,
+            vec![
+                &mut omega_contract_acc, &mut program_config_acc, &mut Account::default(), &mut Account::default(),
+                &mut Account::default(), &mut Account::default(), &mut Account::default(), &mut clock_at(1600)
+            ]
+        );
+        assert!(result.is_err());
+        assert_eq!(
+            OmegaContract::load_from_bytes(&omega_contract_acc.data).unwrap().creator_bond.status,
+            BondStatus::Posted as u64
+        );
+
+        // the admin can within the dispute window
+        let treasury_quote_acc = || {
+            let mut treasury_quote_acc = Account::new(1, spl_token::state::Account::LEN, &spl_token::id());
+            spl_token::state::Account::pack(
+                spl_token::state::Account {
+                    owner: program_config.settings.treasury,
+                    state: spl_token::state::AccountState::Initialized,
+                    ..spl_token::state::Account::default()
+                },
+                &mut treasury_quote_acc.data
+            ).unwrap();
+            treasury_quote_acc
+        };
+        let forfeit = |omega_contract_acc: &mut Account, curr_time: i64| {
+            let instruction = forfeit_creator_bond(
+                &program_id, &omega_contract_pk, Some(&admin_pk), &escrow_pk, &Pubkey::new_unique(),
+                &Pubkey::new_unique()
+            ).unwrap();
+            do_process_instruction(
+                instruction,
+                vec![
+                    omega_contract_acc, &mut program_config_acc.clone(), &mut Account::default(),
+                    &mut Account::default(), &mut treasury_quote_acc(), &mut Account::default(),
+                    &mut Account::default(), &mut clock_at(curr_time)
+                ]
+            )
+        };
+        let mut late_acc = omega_contract_acc.clone();
+        assert!(forfeit(&mut late_acc, 1500 + CREATOR_DISPUTE_WINDOW as i64).is_err());
+        assert_eq!(forfeit(&mut omega_contract_acc, 1600), Ok(()));
+        assert_eq!(
+            OmegaContract::load_from_bytes(&omega_contract_acc.data).unwrap().creator_bond.status,
+            BondStatus::Slashed as u64
+        );
+
+        // but not before the contract resolved
+        omega_contract.winner = Pubkey::default();
+        let mut omega_contract_acc = get_rent_exempt::<OmegaContract>(&program_id);
+        omega_contract_acc.data.copy_from_slice(bytes_of(&omega_contract));
+        assert!(forfeit(&mut omega_contract_acc, 1600).is_err());
+    }
+
+    #[test]
+    fn test_allow_list() {
+        let program_id = Pubkey::new_unique();
+        let omega_contract_pk = Pubkey::new_unique();
+        let admin_pk = Pubkey::new_unique();
+        let members: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        let outsider_pk = Pubkey::new_unique();
+
+        let mut program_config = ProgramConfig::zeroed();
+        program_config.account_flags = (AccountFlag::Initialized | AccountFlag::ProgramConfig).bits();
+        program_config.settings.admin = admin_pk;
+        let mut program_config_acc = get_rent_exempt::<ProgramConfig>(&program_id);
+        program_config_acc.data.copy_from_slice(bytes_of(&program_config));
+
+        let mut omega_contract = OmegaContract::zeroed();
+        omega_contract.account_flags = (AccountFlag::Initialized | AccountFlag::OmegaContract).bits();
+        let mut omega_contract_acc = get_rent_exempt::<OmegaContract>(&program_id);
+        omega_contract_acc.data.copy_from_slice(bytes_of(&omega_contract));
+
+        let root = crate::allow_list::merkle_root(&members);
+        let instruction = set_allow_list(&program_id, &omega_contract_pk, &admin_pk, root).unwrap();
+        let result = do_process_instruction(
+            instruction, vec![&mut omega_contract_acc, &mut program_config_acc, &mut Account::default()]
+        );
+        assert_eq!(result, Ok(()));
+        assert_eq!(OmegaContract::load_from_bytes(&omega_contract_acc.data).unwrap().allow_list_root, root);
+
+        let instruction = set_allow_list(&program_id, &omega_contract_pk, &outsider_pk, [0u8; 32]).unwrap();
+        let result = do_process_instruction(
+            instruction, vec![&mut omega_contract_acc, &mut program_config_acc, &mut Account::default()]
+        );
+        assert!(result.is_err());
+
+        // a proof for someone else does not let an outsider in
+        let proof = crate::allow_list::merkle_proof(&members, 1);
+        let instruction = with_allow_list_proof(
+            issue_set(
+                &program_id, &omega_contract_pk, &outsider_pk, &Pubkey::default(), &Pubkey::default(),
+                &Pubkey::default(), &[], 10
+            ).unwrap(),
+            &proof
+        );
+        let mut accounts = vec![Account::default(); 7];
+        accounts[0] = omega_contract_acc.clone();
+        let result = do_process_instruction(instruction, accounts.iter_mut().collect());
+        assert_eq!(result, Err(ProgramError::Custom(OmegaErrorCode::NotOnAllowList.into())));
+
+        // nor into buying from the market maker
+        let mut omega_contract = *OmegaContract::load_from_bytes(&omega_contract_acc.data).unwrap();
+        omega_contract.exp_time = 1000;
+        omega_contract.lmsr.liquidity = 1;
+        omega_contract_acc.data.copy_from_slice(bytes_of(&omega_contract));
+        let instruction = with_allow_list_proof(
+            lmsr_buy(
+                &program_id, &omega_contract_pk, &outsider_pk, &Pubkey::default(), &Pubkey::default(),
+                &Pubkey::default(), &Pubkey::default(), &Pubkey::default(), &[], 0, 10, 10
+            ).unwrap(),
+            &proof
+        );
+        let mut accounts = vec![Account::default(); 8];
+        accounts[0] = omega_contract_acc.clone();
+        accounts.push(clock_account(&Clock::default()));
+        let result = do_process_instruction(instruction, accounts.iter_mut().collect());
+        assert_eq!(result, Err(ProgramError::Custom(OmegaErrorCode::NotOnAllowList.into())));
+
+        // and bids are left to the asks, since the crank can't prove who placed them
+        let instruction = match_sets(
+            &program_id, &omega_contract_pk, &Pubkey::default(), &Pubkey::default(), &[], Side::Bid, 1
+        ).unwrap();
+        let mut accounts = vec![Account::default(); 5];
+        accounts[0] = omega_contract_acc.clone();
+        let result = do_process_instruction(instruction, accounts.iter_mut().collect());
+        assert_eq!(result, Err(ProgramError::Custom(OmegaErrorCode::NotOnAllowList.into())));
+    }
+
+    #[test]
+    fn test_issuance_caps() {
+        let program_id = Pubkey::new_unique();
+        let omega_contract_pk = Pubkey::new_unique();
+        let admin_pk = Pubkey::new_unique();
+        let user_pk = Pubkey::new_unique();
+
+        let mut program_config = ProgramConfig::zeroed();
+        program_config.account_flags = (AccountFlag::Initialized | AccountFlag::ProgramConfig).bits();
+        program_config.settings.admin = admin_pk;
+        let mut program_config_acc = get_rent_exempt::<ProgramConfig>(&program_id);
+        program_config_acc.data.copy_from_slice(bytes_of(&program_config));
+
+        let mut omega_contract = OmegaContract::zeroed();
+        omega_contract.account_flags = (AccountFlag::Initialized | AccountFlag::OmegaContract).bits();
+        omega_contract.num_outcomes = 2;
+        omega_contract.supplies[..2].copy_from_slice(&[90, 60]);
+        let mut omega_contract_acc = get_rent_exempt::<OmegaContract>(&program_id);
+        omega_contract_acc.data.copy_from_slice(bytes_of(&omega_contract));
+
+        let instruction = set_issuance_caps(&program_id, &omega_contract_pk, &user_pk, 100, 0).unwrap();
+        let result = do_process_instruction(
+            instruction, vec![&mut omega_contract_acc, &mut program_config_acc, &mut Account::default()]
+        );
+        assert!(result.is_err());
+
+        let instruction = set_issuance_caps(&program_id, &omega_contract_pk, &admin_pk, 100, 0).unwrap();
+        let result = do_process_instruction(
+            instruction, vec![&mut omega_contract_acc, &mut program_config_acc, &mut Account::default()]
+        );
+        assert_eq!(result, Ok(()));
+        assert_eq!(OmegaContract::load_from_bytes(&omega_contract_acc.data).unwrap().max_supply, 100);
+
+        let issue = |quantity: u64| issue_set(
+            &program_id, &omega_contract_pk, &user_pk, &Pubkey::default(), &Pubkey::default(),
+            &Pubkey::default(), &[(Pubkey::default(), Pubkey::default()); 2], quantity
+        ).unwrap();
+
+        // the global cap counts the most issued outcome
+        let mut accounts = vec![Account::default(); 11];
+        accounts[0] = omega_contract_acc.clone();
+        let result = do_process_instruction(issue(20), accounts.iter_mut().collect());
+        assert_eq!(result, Err(ProgramError::Custom(OmegaErrorCode::IssuanceCapExceeded.into())));
+
+        // and so do the market maker's mints
+        let mut omega_contract = *OmegaContract::load_from_bytes(&omega_contract_acc.data).unwrap();
+        omega_contract.exp_time = 1000;
+        omega_contract.lmsr.liquidity = 1000;
+        omega_contract_acc.data.copy_from_slice(bytes_of(&omega_contract));
+        let buy = |outcome: u64| lmsr_buy(
+            &program_id, &omega_contract_pk, &user_pk, &Pubkey::default(), &Pubkey::default(),
+            &Pubkey::default(), &Pubkey::default(), &Pubkey::default(), &[Pubkey::default(); 2], outcome, 20, u64::MAX
+        ).unwrap();
+        let vault = spl_token::state::Account {
+            amount: 1000,
+            state: spl_token::state::AccountState::Initialized,
+            ..spl_token::state::Account::default()
+        };
+        let mut accounts = vec![Account::default(); 8];
+        accounts[0] = omega_contract_acc.clone();
+        accounts[3] = Account::new(1, spl_token::state::Account::LEN, &spl_token::id());
+        spl_token::state::Account::pack(vault, &mut accounts[3].data).unwrap();
+        accounts.push(clock_account(&Clock::default()));
+        // the mints as they are after the buy
+        accounts.extend([mint_account(90), mint_account(80)].iter().cloned());
+        let result = do_process_instruction(buy(0), accounts.iter_mut().collect());
+        assert_eq!(result, Err(ProgramError::Custom(OmegaErrorCode::IssuanceCapExceeded.into())));
+        let result = do_process_instruction(buy(1), accounts.iter_mut().collect());
+        assert_eq!(result, Ok(()));
+        let contract = OmegaContract::load_from_bytes(&accounts[0].data).unwrap();
+        assert_eq!((contract.supplies[1], contract.stats.outcomes_issued), (80, 20));
+
+        // the vault has to cover the most issued outcome according to the mints afterwards
+        let vault = spl_token::state::Account { amount: 50, ..vault };
+        spl_token::state::Account::pack(vault, &mut accounts[3].data).unwrap();
+        let result = do_process_instruction(buy(1), accounts.iter_mut().collect());
+        assert_eq!(result, Err(ProgramError::Custom(OmegaErrorCode::VaultInsolvent.into())));
+
+        // the wallet cap counts what the user issued before
+        let mut capped = OmegaContract::zeroed();
+        capped.account_flags = (AccountFlag::Initialized | AccountFlag::OmegaContract).bits();
+        capped.wallet_cap = 50;
+        capped.num_outcomes = 2;
+        let (_, nonce) = find_issuance_record_address(&program_id, &omega_contract_pk, &user_pk);
+        let mut issuance_record = IssuanceRecord::zeroed();
+        issuance_record.account_flags = (AccountFlag::Initialized | AccountFlag::IssuanceRecord).bits();
+        issuance_record.omega_contract = omega_contract_pk;
+        issuance_record.user = user_pk;
+        issuance_record.nonce = nonce;
+        issuance_record.issued = 40;
+
+        let mut accounts = vec![Account::default(); 14];
+        accounts[0] = get_rent_exempt::<OmegaContract>(&program_id);
+        accounts[0].data.copy_from_slice(bytes_of(&capped));
+        accounts[11] = get_rent_exempt::<IssuanceRecord>(&program_id);
+        accounts[11].data.copy_from_slice(bytes_of(&issuance_record));
+        let instruction = with_issuance_record(issue(20), &program_id, &omega_contract_pk, &user_pk);
+        let result = do_process_instruction(instruction, accounts.iter_mut().collect());
+        assert_eq!(result, Err(ProgramError::Custom(OmegaErrorCode::IssuanceCapExceeded.into())));
+
+        // and can't be skipped by leaving out the record
+        let result = do_process_instruction(issue(5), accounts[..11].iter_mut().collect());
+        assert_eq!(result, Err(ProgramError::Custom(OmegaErrorCode::IssuanceCapExceeded.into())));
+
+        // which crank-filled bids can't be counted against
+        let instruction = match_sets(
+            &program_id, &omega_contract_pk, &Pubkey::default(), &Pubkey::default(), &[], Side::Bid, 1
+        ).unwrap();
+        accounts.truncate(5);
+        let result = do_process_instruction(instruction, accounts.iter_mut().collect());
+        assert_eq!(result, Err(ProgramError::Custom(OmegaErrorCode::IssuanceCapExceeded.into())));
+    }
+
+    #[test]
+    fn test_commit_reveal_resolution() {
+        let program_id = Pubkey::new_unique();
+        let omega_contract_pk = Pubkey::new_unique();
+        let oracle_pk = Pubkey::new_unique();
+        let outcomes = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let salt = [9u8; 32];
+
+        let mut omega_contract = OmegaContract::zeroed();
+        omega_contract.account_flags = (AccountFlag::Initialized | AccountFlag::OmegaContract).bits();
+        omega_contract.oracle = oracle_pk;
+        omega_contract.exp_time = 1000;
+        omega_contract.auto_exp_time = 2000;
+        omega_contract.num_outcomes = 2;
+        omega_contract.outcomes[..2].copy_from_slice(&outcomes);
+        let mut omega_contract_acc = get_rent_exempt::<OmegaContract>(&program_id);
+        omega_contract_acc.data.copy_from_slice(bytes_of(&omega_contract));
+
+        let clock_at = |curr_time: i64| clock_account(&Clock { unix_timestamp: curr_time, ..Clock::default() });
+
+        let instruction = commit_resolution(
+            &program_id, &omega_contract_pk, &oracle_pk, commitment_hash(&outcomes[1], &salt), 100
+        ).unwrap();
+        let result = do_process_instruction(
+            instruction, vec![&mut omega_contract_acc, &mut Account::default(), &mut clock_at(1100)]
+        );
+        assert_eq!(result, Ok(()));
+        assert_eq!(OmegaContract::load_from_bytes(&omega_contract_acc.data).unwrap().auto_exp_time, 1200);
+
+        // the oracle can't resolve some other way after committing
+        let instruction = resolve(
+            &program_id, &omega_contract_pk, &oracle_pk, &outcomes[0], &Pubkey::default(), &outcomes, "", [0u8; 32]
+        ).unwrap();
+        let result = do_process_instruction(
+            instruction,
+            vec![
+                &mut omega_contract_acc, &mut Account::default(), &mut Account::default(), &mut clock_at(1150),
+                &mut token_account(&Pubkey::default(), &Pubkey::default(), 0), &mut mint_account(0), &mut mint_account(0)
+            ]
+        );
+        assert!(result.is_err());
+
+        let reveal_at = |winner: &Pubkey, salt: [u8; 32], curr_time: i64| {
+            let mut contract_acc = omega_contract_acc.clone();
+            let instruction = reveal_resolution(
+                &program_id, &omega_contract_pk, winner, &Pubkey::default(), &outcomes, salt
+            ).unwrap();
+            let result = do_process_instruction(
+                instruction,
+                vec![
+                    &mut contract_acc, &mut Account::default(), &mut clock_at(curr_time), &mut token_account(&Pubkey::default(), &Pubkey::default(), 0),
+                    &mut mint_account(0), &mut mint_account(0)
+                ]
+            );
+            (result, OmegaContract::load_from_bytes(&contract_acc.data).unwrap().winner)
+        };
+
+        let invalid_reveal = Err(ProgramError::Custom(OmegaErrorCode::InvalidReveal.into()));
+        assert_eq!(reveal_at(&outcomes[0], salt, 1150).0, invalid_reveal);
+        assert_eq!(reveal_at(&outcomes[1], [0u8; 32], 1150).0, invalid_reveal);
+        assert!(reveal_at(&outcomes[1], salt, 1200).0.is_err());
+        assert_eq!(reveal_at(&outcomes[1], salt, 1199), (Ok(()), outcomes[1]));
+    }
+
+    #[test]
+    fn test_config_settings() {
+        let quote_mint = Pubkey::new_unique();
+        let creator = Pubkey::new_unique();
+        let mut settings = ConfigSettings::zeroed();
+        assert!(settings.is_quote_mint_allowed(&quote_mint));
+        assert!(settings.is_creator_allowed(&creator));
+
+        settings.num_quote_mints = 1;
+        settings.quote_mints[0] = quote_mint;
+        settings.restrict_creators = 1;
+        assert!(settings.is_quote_mint_allowed(&quote_mint));
+        assert!(!settings.is_quote_mint_allowed(&creator));
+        assert!(!settings.is_creator_allowed(&creator));
+
+        settings.num_creators = 1;
+        settings.creators[0] = creator;
+        assert!(settings.is_creator_allowed(&creator));
+    }
+
+    #[test]
+    fn test_market_series_template() {
+        let mut market_series = MarketSeries::zeroed();
+        market_series.first_exp_time = 1_609_459_200;  // 2021-01-01 00:00:00 UTC
+        market_series.period = 86400;
+        market_series.resolve_window = 3600;
+        let template = b"#{index}: ETH above 1000 on {exp_date} ({exp_time})? {unknown}";
+        market_series.details_len = template.len() as u64;
+        market_series.details[..template.len()].copy_from_slice(template);
+
+        assert_eq!(market_series.exp_times(59), Some((1_614_556_800, 1_614_560_400)));
+        assert_eq!(market_series.exp_times(u64::MAX), None);
+        assert_eq!(
+            market_series.details_for(59, 1_614_556_800).unwrap(),
+            b"#59: ETH above 1000 on 2021-03-01 (1614556800)? {unknown}".to_vec()
+        );
+        assert_eq!(market_series.details_for(0, 951_782_400).unwrap(), b"#0: ETH above 1000 on 2000-02-29 (951782400)? {unknown}".to_vec());
+    }
+
+    #[test]
+    fn test_series_contract_address() {
+        let program_id = Pubkey::new_unique();
+        let market_series_pk = Pubkey::new_unique();
+        let (pk0, nonce0) = find_series_contract_address(&program_id, &market_series_pk, 0);
+        let (pk1, _) = find_series_contract_address(&program_id, &market_series_pk, 1);
+        assert_ne!(pk0, pk1);
+        let seeds = [market_series_pk.as_ref(), &0u64.to_le_bytes(), bytes_of(&nonce0)];
+        assert_eq!(Pubkey::create_program_address(&seeds, &program_id).unwrap(), pk0);
+    }
+
+    #[test]
+    fn test_create_series_contract() {
+        let program_id = Pubkey::new_unique();
+        let market_series_pk = Pubkey::new_unique();
+        let quote_mint_pk = Pubkey::new_unique();
+        let vault_pk = Pubkey::new_unique();
+        let outcomes = [Pubkey::new_unique(), Pubkey::new_unique()];
+
+        let mut market_series = MarketSeries::zeroed();
+        market_series.account_flags = (AccountFlag::Initialized | AccountFlag::MarketSeries).bits();
+        market_series.oracle = Pubkey::new_unique();
+        market_series.quote_mint = quote_mint_pk;
+        market_series.num_outcomes = 2;
+        market_series.first_exp_time = 1_609_459_200;  // 2021-01-01 00:00:00 UTC
+        market_series.period = 86400;
+        market_series.resolve_window = 3600;
+        let template = b"#{index}: ETH above 1000 on {exp_date}?";
+        market_series.details_len = template.len() as u64;
+        market_series.details[..template.len()].copy_from_slice(template);
+
+        let (omega_contract_pk, contract_nonce) = find_series_contract_address(&program_id, &market_series_pk, 0);
+        let (signer_pk, signer_nonce) = (0..256u64)
+            .find_map(|nonce| gen_signer_key(nonce, &omega_contract_pk, &program_id).ok().map(|pk| (pk, nonce)))
+            .unwrap();
+
+        let mut accs = HashMap::new();
+        let mut market_series_acc = get_rent_exempt::<MarketSeries>(&program_id);
+        market_series_acc.data.copy_from_slice(bytes_of(&market_series));
+        accs.insert(market_series_pk, market_series_acc);
+        // the system program does not run in these tests, so the contract account is handed in already allocated
+        accs.insert(omega_contract_pk, get_rent_exempt::<OmegaContract>(&program_id));
+        accs.insert(quote_mint_pk, mint_account(0));
+        accs.insert(vault_pk, token_account(&quote_mint_pk, &signer_pk, 0));
+        accs.insert(solana_program::sysvar::rent::ID, create_account(&Rent::default(), 1));
+        for outcome_pk in outcomes.iter() {
+            let mint = Mint { mint_authority: Some(signer_pk).into(), is_initialized: true, ..Mint::default() };
+            let mut mint_acc = Account::new(1, Mint::LEN, &spl_token::id());
+            Mint::pack(mint, &mut mint_acc.data).unwrap();
+            accs.insert(*outcome_pk, mint_acc);
+        }
+        let create = |omega_contract_pk: &Pubkey, contract_nonce: u64| create_series_contract(
+            &program_id, &market_series_pk, omega_contract_pk, &Pubkey::new_unique(), &quote_mint_pk, &vault_pk,
+            &signer_pk, &outcomes, contract_nonce, signer_nonce
+        ).unwrap();
+
+        // the contract has to be at the address of the next index
+        let (next_contract_pk, next_nonce) = find_series_contract_address(&program_id, &market_series_pk, 1);
+        accs.insert(next_contract_pk, get_rent_exempt::<OmegaContract>(&program_id));
+        assert!(process_with(create(&next_contract_pk, next_nonce), &mut accs).is_err());
+
+        // and gets its expiry and details from the series
+        assert_eq!(process_with(create(&omega_contract_pk, contract_nonce), &mut accs), Ok(()));
+        let contract = OmegaContract::load_from_bytes(&accs[&omega_contract_pk].data).unwrap();
+        assert_eq!((contract.oracle, contract.quote_mint, contract.vault), (market_series.oracle, quote_mint_pk, vault_pk));
+        assert_eq!((contract.exp_time, contract.auto_exp_time), (1_609_459_200, 1_609_462_800));
+        let details = b"#0: ETH above 1000 on 2021-01-01?";
+        assert_eq!(&contract.details[..details.len()], details);
+        assert_eq!(&contract.outcomes[..contract.num_outcomes], &outcomes);
+        let market_series = MarketSeries::load_from_bytes(&accs[&market_series_pk].data).unwrap();
+        assert_eq!(market_series.next_index, 1);
+
+        // which then moves on to the next contract
+        assert!(process_with(create(&omega_contract_pk, contract_nonce), &mut accs).is_err());
+    }
+    #[test]
+    fn test_registry() {
+        let program_id = Pubkey::new_unique();
+        let registry_pk = Pubkey::new_unique();
+        let mut registry = Registry::zeroed();
+        registry.account_flags = (AccountFlag::Initialized | AccountFlag::Registry).bits();
+        let contracts: Vec<Pubkey> = (0..MAX_REGISTRY_ENTRIES).map(|_| Pubkey::new_unique()).collect();
+        for (i, contract) in contracts.iter().enumerate() {
+            let status = if i == 3 { ContractStatus::Resolved } else { ContractStatus::Open };
+            assert!(registry.push(RegistryEntry { contract: *contract, status: status.into(), ..RegistryEntry::zeroed() }));
+        }
+
+        // a full registry makes room by dropping its oldest resolved contract, and fails once every contract is open
+        let newest = RegistryEntry { contract: Pubkey::new_unique(), ..RegistryEntry::zeroed() };
+        assert!(registry.push(newest));
+        assert_eq!(registry.find(&contracts[3]), None);
+        assert_eq!(registry.find(&contracts[4]), Some(3));
+        assert_eq!(registry.find(&newest.contract), Some(MAX_REGISTRY_ENTRIES - 1));
+        assert!(!registry.push(RegistryEntry::zeroed()));
+
+        let creator_pk = Pubkey::new_unique();
+        registry.creator = creator_pk;
+        let mut omega_contract = OmegaContract::zeroed();
+        omega_contract.account_flags = (AccountFlag::Initialized | AccountFlag::OmegaContract).bits();
+        omega_contract.auto_exp_time = 2000;
+        omega_contract.num_outcomes = 2;
+
+        // only the creator closes its contracts, once they auto expired and nothing is left in them
+        let close_at = |contract: &OmegaContract, curr_time: i64, vault_amount: u64, creator_pk: &Pubkey| {
+            let mut omega_contract_acc = get_rent_exempt::<OmegaContract>(&program_id);
+            omega_contract_acc.data.copy_from_slice(bytes_of(contract));
+            let mut registry_acc = get_rent_exempt::<Registry>(&program_id);
+            registry_acc.data.copy_from_slice(bytes_of(&registry));
+            let mut creator_acc = Account::new(5, 0, &solana_program::system_program::ID);
+            let mut vault_acc = token_account(&Pubkey::default(), &Pubkey::default(), vault_amount);
+            let mut clock_acc = clock_account(&Clock { unix_timestamp: curr_time, ..Clock::default() });
+            let instruction = close_contract(
+                &program_id, &contracts[0], &registry_pk, creator_pk, &Pubkey::default()
+            ).unwrap();
+            let result = do_process_instruction(instruction, vec![
+                &mut omega_contract_acc, &mut registry_acc, &mut creator_acc, &mut vault_acc, &mut clock_acc
+            ]);
+            result.map(|_| (omega_contract_acc, registry_acc, creator_acc))
+        };
+        assert!(close_at(&omega_contract, 1999, 0, &creator_pk).is_err());
+        assert!(close_at(&omega_contract, 2000, 0, &Pubkey::new_unique()).is_err());
+        assert!(close_at(&omega_contract, 2000, 1, &creator_pk).is_err());
+        let mut redeemable = omega_contract;
+        redeemable.supplies[1] = 1;
+        assert!(close_at(&redeemable, 2000, 0, &creator_pk).is_err());
+        let mut bonded = omega_contract;
+        bonded.bond.status = BondStatus::Posted as u64;
+        assert!(close_at(&bonded, 2000, 0, &creator_pk).is_err());
+
+        let (omega_contract_acc, registry_acc, creator_acc) = close_at(&omega_contract, 2000, 0, &creator_pk).unwrap();
+        assert_eq!(omega_contract_acc.lamports, 0);
+        assert_eq!(OmegaContract::load_from_bytes(&omega_contract_acc.data).unwrap().account_flags, 0);
+        assert_eq!(creator_acc.lamports, 5 + get_rent_exempt::<OmegaContract>(&program_id).lamports);
+        let registry = Registry::load_from_bytes(&registry_acc.data).unwrap();
+        assert_eq!(registry.num_entries as usize, MAX_REGISTRY_ENTRIES - 1);
+        assert_eq!(registry.entries()[0].contract, contracts[1]);
+    }
+
+    #[test]
+    fn test_redeem_builders() {
+        // redeeming updates the contract, so the builders have to pass it writable
+        let program_id = Pubkey::new_unique();
+        let mint_acc = |supply: u64| {
+            let mint = Mint { supply, decimals: 6, is_initialized: true, ..Mint::default() };
+            let mut mint_acc = Account::new(1, Mint::LEN, &spl_token::id());
+            Mint::pack(mint, &mut mint_acc.data).unwrap();
+            mint_acc
+        };
+        let new_contract = |quote_mint: Pubkey| {
+            let mut omega_contract = OmegaContract::zeroed();
+            omega_contract.account_flags = (AccountFlag::Initialized | AccountFlag::OmegaContract).bits();
+            omega_contract.quote_mint = quote_mint;
+            omega_contract.vault = Pubkey::new_unique();
+            omega_contract.signer_key = Pubkey::new_unique();
+            omega_contract.num_outcomes = 2;
+            omega_contract.outcomes[..2].copy_from_slice(&[Pubkey::new_unique(), Pubkey::new_unique()]);
+            omega_contract.supplies[..2].copy_from_slice(&[100, 100]);
+            omega_contract
+        };
+        let contract_acc = |omega_contract: &OmegaContract| {
+            let mut omega_contract_acc = get_rent_exempt::<OmegaContract>(&program_id);
+            omega_contract_acc.data.copy_from_slice(bytes_of(omega_contract));
+            omega_contract_acc
+        };
+        let vault_acc = |omega_contract: &OmegaContract, amount: u64| {
+            let vault = spl_token::state::Account {
+                mint: omega_contract.quote_mint,
+                owner: omega_contract.signer_key,
+                amount,
+                state: spl_token::state::AccountState::Initialized,
+                ..spl_token::state::Account::default()
+            };
+            let mut vault_acc = Account::new(1, spl_token::state::Account::LEN, &spl_token::id());
+            spl_token::state::Account::pack(vault, &mut vault_acc.data).unwrap();
+            vault_acc
+        };
+        let mut clock_acc = clock_account(&Clock::default());
+        let user_pk = Pubkey::new_unique();
+
+        let mut omega_contract = new_contract(Pubkey::new_unique());
+        omega_contract.winner = omega_contract.outcomes[0];
+        let omega_contract_pk = Pubkey::new_unique();
+        let mut omega_contract_acc = contract_acc(&omega_contract);
+        let instruction = redeem_winner(
+            &program_id, &omega_contract_pk, &user_pk, &Pubkey::new_unique(), &omega_contract.vault,
+            &omega_contract.signer_key, &omega_contract.winner, &Pubkey::new_unique(), 10
+        ).unwrap();
+        let result = do_process_instruction(
+            instruction,
+            vec![
+                &mut omega_contract_acc, &mut Account::default(), &mut Account::default(),
+                &mut vault_acc(&omega_contract, 100), &mut Account::default(), &mut Account::default(),
+                &mut mint_acc(100), &mut Account::default(), &mut clock_acc
+            ]
+        );
+        assert_eq!(result, Ok(()));
+        let contract = OmegaContract::load_from_bytes(&omega_contract_acc.data).unwrap();
+        assert_eq!(contract.stats.winner_redeemed, 10);
+        assert_eq!(contract.supplies[0], 90);
+
+        // clients that leave out the clock can still redeem sets, the last activity stays as it was
+        let mut omega_contract = new_contract(Pubkey::new_unique());
+        omega_contract.stats.last_activity = 7;
+        let mut omega_contract_acc = contract_acc(&omega_contract);
+        let outcome_pks = [
+            (omega_contract.outcomes[0], Pubkey::new_unique()),
+            (omega_contract.outcomes[1], Pubkey::new_unique())
+        ];
+        let mut instruction = redeem_set(
+            &program_id, &omega_contract_pk, &user_pk, &Pubkey::new_unique(), &omega_contract.vault,
+            &omega_contract.signer_key, &outcome_pks, 10
+        ).unwrap();
+        instruction.accounts.pop();
+        let result = do_process_instruction(
+            instruction,
+            vec![
+                &mut omega_contract_acc, &mut Account::default(), &mut Account::default(),
+                &mut vault_acc(&omega_contract, 100), &mut Account::default(), &mut Account::default(),
+                &mut mint_acc(90), &mut Account::default(), &mut mint_acc(90), &mut Account::default()
+            ]
+        );
+        assert_eq!(result, Ok(()));
+        let contract = OmegaContract::load_from_bytes(&omega_contract_acc.data).unwrap();
+        assert_eq!((contract.open_interest(), contract.stats.sets_redeemed, contract.stats.last_activity), (90, 10, 7));
+
+        // withdrawing LMSR liquidity after auto expiry rules out a late resolution
+        let creator_pk = Pubkey::new_unique();
+        let mut omega_contract = new_contract(Pubkey::new_unique());
+        omega_contract.lmsr.liquidity = 1;
+        omega_contract.lmsr.creator = creator_pk;
+        let mut omega_contract_acc = contract_acc(&omega_contract);
+        let instruction = lmsr_withdraw(
+            &program_id, &omega_contract_pk, &creator_pk, &Pubkey::new_unique(), &omega_contract.vault,
+            &omega_contract.signer_key, &omega_contract.outcomes[..2]
+        ).unwrap();
+        let result = do_process_instruction(
+            instruction,
+            vec![
+                &mut omega_contract_acc, &mut Account::default(), &mut Account::default(),
+                &mut vault_acc(&omega_contract, 100), &mut Account::default(), &mut Account::default(),
+                &mut clock_acc, &mut mint_acc(100), &mut mint_acc(100)
+            ]
+        );
+        assert_eq!(result, Ok(()));
+        let contract = OmegaContract::load_from_bytes(&omega_contract_acc.data).unwrap();
+        assert_eq!(contract.auto_exp_redemptions, 1);
+
+        // so does redeeming a conditional contract after both auto expired
+        let parent_pk = Pubkey::new_unique();
+        let parent_contract = new_contract(Pubkey::new_unique());
+        let mut omega_contract = new_contract(parent_contract.outcomes[0]);
+        omega_contract.parent = parent_pk;
+        let mut parent_contract_acc = contract_acc(&parent_contract);
+        let mut omega_contract_acc = contract_acc(&omega_contract);
+        let instruction = redeem_conditional_winner(
+            &program_id, &omega_contract_pk, &parent_pk, &user_pk, &Pubkey::new_unique(), &parent_contract.vault,
+            &omega_contract.vault, &parent_contract.signer_key, &omega_contract.signer_key,
+            &omega_contract.outcomes[0], &Pubkey::new_unique(), &omega_contract.quote_mint,
+            &parent_contract.outcomes[..2], 10
+        ).unwrap();
+        let result = do_process_instruction(
+            instruction,
+            vec![
+                &mut omega_contract_acc, &mut parent_contract_acc, &mut Account::default(), &mut Account::default(),
+                &mut vault_acc(&parent_contract, 100), &mut vault_acc(&omega_contract, 100),
+                &mut Account::default(), &mut Account::default(), &mut Account::default(),
+                &mut mint_acc(100), &mut Account::default(), &mut mint_acc(100), &mut clock_acc,
+                &mut mint_acc(95), &mut mint_acc(100)
+            ]
+        );
+        assert_eq!(result, Ok(()));
+        let contract = OmegaContract::load_from_bytes(&omega_contract_acc.data).unwrap();
+        assert_eq!(contract.auto_exp_redemptions, 1);
+        assert_eq!(contract.supplies[0], 90);
+        let parent = OmegaContract::load_from_bytes(&parent_contract_acc.data).unwrap();
+        assert_eq!(parent.auto_exp_redemptions, 1);
+        assert_eq!(parent.supplies[0], 95);
+        assert_eq!((contract.stats.winner_redeemed, parent.stats.winner_redeemed), (10, 5));
+    }
+
+    #[test]
+    fn test_conditional_set() {
+        // issuing through a conditional contract counts against the parent's cap and stats as well
+        let program_id = Pubkey::new_unique();
+        let user_pk = Pubkey::new_unique();
+        let mint_acc = |supply: u64| {
+            let mint = Mint { supply, decimals: 6, is_initialized: true, ..Mint::default() };
+            let mut mint_acc = Account::new(1, Mint::LEN, &spl_token::id());
+            Mint::pack(mint, &mut mint_acc.data).unwrap();
+            mint_acc
+        };
+        let vault_acc = |omega_contract: &OmegaContract, amount: u64| {
+            let vault = spl_token::state::Account {
+                mint: omega_contract.quote_mint,
+                owner: omega_contract.signer_key,
+                amount,
+                state: spl_token::state::AccountState::Initialized,
+                ..spl_token::state::Account::default()
+            };
+            let mut vault_acc = Account::new(1, spl_token::state::Account::LEN, &spl_token::id());
+            spl_token::state::Account::pack(vault, &mut vault_acc.data).unwrap();
+            vault_acc
+        };
+
+        let mut parent_contract = OmegaContract::zeroed();
+        parent_contract.account_flags = (AccountFlag::Initialized | AccountFlag::OmegaContract).bits();
+        parent_contract.quote_mint = Pubkey::new_unique();
+        parent_contract.vault = Pubkey::new_unique();
+        parent_contract.signer_key = Pubkey::new_unique();
+        parent_contract.num_outcomes = 2;
+        parent_contract.outcomes[..2].copy_from_slice(&[Pubkey::new_unique(), Pubkey::new_unique()]);
+        parent_contract.supplies[..2].copy_from_slice(&[95, 95]);
+        parent_contract.max_supply = 100;
+        let parent_pk = Pubkey::new_unique();
+
+        let mut omega_contract = parent_contract;
+        omega_contract.quote_mint = parent_contract.outcomes[0];
+        omega_contract.vault = Pubkey::new_unique();
+        omega_contract.signer_key = Pubkey::new_unique();
+        omega_contract.outcomes[..2].copy_from_slice(&[Pubkey::new_unique(), Pubkey::new_unique()]);
+        omega_contract.supplies = [0; MAX_OUTCOMES];
+        omega_contract.max_supply = 0;
+        omega_contract.parent = parent_pk;
+        let omega_contract_pk = Pubkey::new_unique();
+
+        let instruction = issue_conditional_set(
+            &program_id, &omega_contract_pk, &parent_pk, &user_pk, &Pubkey::new_unique(), &parent_contract.vault,
+            &omega_contract.vault, &parent_contract.signer_key, &omega_contract.signer_key,
+            &[(parent_contract.outcomes[0], omega_contract.vault), (parent_contract.outcomes[1], Pubkey::new_unique())],
+            &[(omega_contract.outcomes[0], Pubkey::new_unique()), (omega_contract.outcomes[1], Pubkey::new_unique())],
+            10
+        ).unwrap();
+        let mut accounts = vec![Account::default(); 14];
+        accounts[0] = get_rent_exempt::<OmegaContract>(&program_id);
+        accounts[0].data.copy_from_slice(bytes_of(&omega_contract));
+        accounts[1] = get_rent_exempt::<OmegaContract>(&program_id);
+        accounts[1].data.copy_from_slice(bytes_of(&parent_contract));
+        accounts[4] = vault_acc(&parent_contract, 105);
+        accounts[5] = vault_acc(&omega_contract, 10);
+        accounts[9] = clock_account(&Clock { unix_timestamp: 100, ..Clock::default() });
+        accounts[10] = mint_acc(105);
+        accounts[12] = mint_acc(105);
+        accounts.extend([mint_acc(10), Account::default(), mint_acc(10), Account::default()]);
+        let result = do_process_instruction(instruction.clone(), accounts.iter_mut().collect());
+        assert_eq!(result, Err(ProgramError::Custom(OmegaErrorCode::IssuanceCapExceeded.into())));
+
+        parent_contract.max_supply = 0;
+        accounts[1].data.copy_from_slice(bytes_of(&parent_contract));
+        let result = do_process_instruction(instruction, accounts.iter_mut().collect());
+        assert_eq!(result, Ok(()));
+        let contract = OmegaContract::load_from_bytes(&accounts[0].data).unwrap();
+        assert_eq!((contract.open_interest(), contract.stats.sets_issued, contract.stats.last_activity), (10, 10, 100));
+        let parent = OmegaContract::load_from_bytes(&accounts[1].data).unwrap();
+        assert_eq!((parent.open_interest(), parent.stats.sets_issued, parent.stats.last_activity), (105, 10, 100));
+    }
+
+    #[test]
+    fn test_redeem_conditional_winner() {
+        execute_token_cpi();
+        let program_id = Pubkey::new_unique();
+        let oracle_pk = Pubkey::new_unique();
+        let (user_pk, user_quote_pk, winner_user_pk) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+
+        let mut parent_contract = OmegaContract::zeroed();
+        parent_contract.account_flags = (AccountFlag::Initialized | AccountFlag::OmegaContract).bits();
+        parent_contract.oracle = oracle_pk;
+        parent_contract.quote_mint = Pubkey::new_unique();
+        parent_contract.vault = Pubkey::new_unique();
+        parent_contract.signer_key = Pubkey::new_unique();
+        parent_contract.exp_time = 100;
+        parent_contract.auto_exp_time = 1000;
+        parent_contract.num_outcomes = 2;
+        parent_contract.outcomes[..2].copy_from_slice(&[Pubkey::new_unique(), Pubkey::new_unique()]);
+        parent_contract.supplies[..2].copy_from_slice(&[100, 100]);
+        let parent_pk = Pubkey::new_unique();
+
+        // the conditional contract already resolved and holds all of the parent's first outcome as collateral
+        let mut omega_contract = parent_contract;
+        omega_contract.quote_mint = parent_contract.outcomes[0];
+        omega_contract.vault = Pubkey::new_unique();
+        omega_contract.signer_key = Pubkey::new_unique();
+        omega_contract.outcomes[..2].copy_from_slice(&[Pubkey::new_unique(), Pubkey::new_unique()]);
+        omega_contract.parent = parent_pk;
+        omega_contract.winner = omega_contract.outcomes[0];
+        let omega_contract_pk = Pubkey::new_unique();
+
+        let mut accs = HashMap::new();
+        for (pk, contract) in [(parent_pk, &parent_contract), (omega_contract_pk, &omega_contract)] {
+            let mut contract_acc = get_rent_exempt::<OmegaContract>(&program_id);
+            contract_acc.data.copy_from_slice(bytes_of(contract));
+            accs.insert(pk, contract_acc);
+            accs.insert(contract.vault, token_account(&contract.quote_mint, &contract.signer_key, 100));
+            for outcome_pk in contract.outcomes[..2].iter() {
+                accs.insert(*outcome_pk, mint_account(100));
+            }
+        }
+        accs.insert(user_quote_pk, token_account(&parent_contract.quote_mint, &user_pk, 0));
+        accs.insert(winner_user_pk, token_account(&omega_contract.outcomes[0], &user_pk, 30));
+        accs.insert(solana_program::sysvar::clock::ID, clock_account(&Clock { unix_timestamp: 200, ..Clock::default() }));
+
+        let balance = |accs: &HashMap<Pubkey, Account>, pk: &Pubkey| {
+            spl_token::state::Account::unpack(&accs[pk].data).unwrap().amount
+        };
+        let supply = |accs: &HashMap<Pubkey, Account>, pk: &Pubkey| Mint::unpack(&accs[pk].data).unwrap().supply;
+        let redeem = |quantity: u64| redeem_conditional_winner(
+            &program_id, &omega_contract_pk, &parent_pk, &user_pk, &user_quote_pk, &parent_contract.vault,
+            &omega_contract.vault, &parent_contract.signer_key, &omega_contract.signer_key, &omega_contract.outcomes[0],
+            &winner_user_pk, &parent_contract.outcomes[0], &parent_contract.outcomes[..2], quantity
+        ).unwrap();
+
+        // the collateral only pays out once the parent resolved to it
+        assert!(process_with(redeem(30), &mut accs).is_err());
+        let instruction = resolve(
+            &program_id, &parent_pk, &oracle_pk, &parent_contract.outcomes[0], &parent_contract.vault,
+            &parent_contract.outcomes[..2], "", [0u8; 32]
+        ).unwrap();
+        assert_eq!(process_with(instruction, &mut accs), Ok(()));
+
+        // redeeming burns the winner and the collateral backing it, and pays out of the parent's vault
+        assert_eq!(process_with(redeem(30), &mut accs), Ok(()));
+        assert_eq!((balance(&accs, &winner_user_pk), balance(&accs, &user_quote_pk)), (0, 30));
+        assert_eq!((supply(&accs, &omega_contract.outcomes[0]), supply(&accs, &parent_contract.outcomes[0])), (70, 70));
+        assert_eq!((balance(&accs, &omega_contract.vault), balance(&accs, &parent_contract.vault)), (70, 70));
+        let contract = OmegaContract::load_from_bytes(&accs[&omega_contract_pk].data).unwrap();
+        assert_eq!(&contract.supplies[..2], &[70, 100]);
+        let parent = OmegaContract::load_from_bytes(&accs[&parent_pk].data).unwrap();
+        assert_eq!(&parent.supplies[..2], &[70, 100]);
+        assert_eq!(parent.stats.last_activity, 200);
+    }
+
+
+    #[test]
+    fn test_order_book() {
+        execute_token_cpi();
+        let program_id = Pubkey::new_unique();
+        let omega_contract_pk = Pubkey::new_unique();
+        let signer_pk = Pubkey::new_unique();
+        let quote_mint_pk = Pubkey::new_unique();
+        let vault_pk = Pubkey::new_unique();
+        let outcomes = [Pubkey::new_unique(), Pubkey::new_unique()];
+
+        let mut omega_contract = OmegaContract::zeroed();
+        omega_contract.account_flags = (AccountFlag::Initialized | AccountFlag::OmegaContract).bits();
+        omega_contract.quote_mint = quote_mint_pk;
+        omega_contract.vault = vault_pk;
+        omega_contract.signer_key = signer_pk;
+        omega_contract.exp_time = 1000;
+        omega_contract.auto_exp_time = 2000;
+        omega_contract.num_outcomes = 2;
+        omega_contract.outcomes[..2].copy_from_slice(&outcomes);
+
+        let mut accs = HashMap::new();
+        let mut omega_contract_acc = get_rent_exempt::<OmegaContract>(&program_id);
+        omega_contract_acc.data.copy_from_slice(bytes_of(&omega_contract));
+        accs.insert(omega_contract_pk, omega_contract_acc);
+        accs.insert(vault_pk, token_account(&quote_mint_pk, &signer_pk, 0));
+        accs.insert(solana_program::sysvar::clock::ID, clock_account(&Clock { unix_timestamp: 500, ..Clock::default() }));
+
+        // a book for YES and one for NO, as (order_book, quote_escrow, outcome_escrow, outcome_mint)
+        let book_pks: Vec<_> = outcomes.iter()
+            .map(|outcome_pk| (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), *outcome_pk))
+            .collect();
+        for (order_book_pk, quote_escrow_pk, outcome_escrow_pk, outcome_pk) in book_pks.iter() {
+            let mut order_book = OrderBook::zeroed();
+            order_book.account_flags = (AccountFlag::Initialized | AccountFlag::OrderBook).bits();
+            order_book.omega_contract = omega_contract_pk;
+            order_book.outcome_mint = *outcome_pk;
+            order_book.quote_escrow = *quote_escrow_pk;
+            order_book.outcome_escrow = *outcome_escrow_pk;
+            let mut order_book_acc = get_rent_exempt::<OrderBook>(&program_id);
+            order_book_acc.data.copy_from_slice(bytes_of(&order_book));
+            accs.insert(*order_book_pk, order_book_acc);
+            accs.insert(*quote_escrow_pk, token_account(&quote_mint_pk, &signer_pk, 0));
+            accs.insert(*outcome_escrow_pk, token_account(outcome_pk, &signer_pk, 0));
+
+            let mint = Mint { mint_authority: Some(signer_pk).into(), is_initialized: true, ..Mint::default() };
+            let mut mint_acc = Account::new(1, Mint::LEN, &spl_token::id());
+            Mint::pack(mint, &mut mint_acc.data).unwrap();
+            accs.insert(*outcome_pk, mint_acc);
+        }
+
+        // one user bids on YES and the other on NO, as (owner, quote wallet, outcome wallet)
+        let wallets: Vec<_> = outcomes.iter().map(|outcome_pk| {
+            let wallet = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+            accs.insert(wallet.1, token_account(&quote_mint_pk, &wallet.0, 10_000));
+            accs.insert(wallet.2, token_account(outcome_pk, &wallet.0, 0));
+            wallet
+        }).collect();
+
+        let balance = |accs: &HashMap<Pubkey, Account>, pk: &Pubkey| {
+            spl_token::state::Account::unpack(&accs[pk].data).unwrap().amount
+        };
+        let supply = |accs: &HashMap<Pubkey, Account>, pk: &Pubkey| Mint::unpack(&accs[pk].data).unwrap().supply;
+        let bid = |i: usize, price: u64| place_order(
+            &program_id, &omega_contract_pk, &book_pks[i].0, &wallets[i].0, &wallets[i].1, &book_pks[i].1,
+            &vault_pk, &outcomes, Side::Bid, price, PRICE_SCALE
+        ).unwrap();
+        let settle = |i: usize, order_id: u64, cancel: bool| {
+            let (order_book_pk, quote_escrow_pk, outcome_escrow_pk, _) = &book_pks[i];
+            let (owner_pk, owner_quote_pk, owner_outcome_pk) = &wallets[i];
+            let settle_or_cancel = if cancel { cancel_order } else { settle_order };
+            settle_or_cancel(
+                &program_id, &omega_contract_pk, order_book_pk, owner_pk, owner_quote_pk, owner_outcome_pk,
+                quote_escrow_pk, outcome_escrow_pk, &signer_pk, &vault_pk, &outcomes, order_id
+            ).unwrap()
+        };
+
+        // YES at p and NO at 1 - p escrow what they bid
+        assert_eq!(process_with(bid(0, 6_000), &mut accs), Ok(()));
+        assert_eq!(process_with(bid(1, 4_000), &mut accs), Ok(()));
+        assert_eq!((balance(&accs, &book_pks[0].1), balance(&accs, &book_pks[1].1)), (6_000, 4_000));
+        assert_eq!((balance(&accs, &wallets[0].1), balance(&accs, &wallets[1].1)), (4_000, 6_000));
+
+        // and add up to a complete set, which the crank issues into the outcome escrows
+        let instruction = match_sets(
+            &program_id, &omega_contract_pk, &vault_pk, &signer_pk, &book_pks, Side::Bid, 1
+        ).unwrap();
+        assert_eq!(process_with(instruction, &mut accs), Ok(()));
+        assert_eq!(balance(&accs, &vault_pk), PRICE_SCALE);
+        for (_, quote_escrow_pk, outcome_escrow_pk, outcome_pk) in book_pks.iter() {
+            assert_eq!(balance(&accs, quote_escrow_pk), 0);
+            assert_eq!(balance(&accs, outcome_escrow_pk), PRICE_SCALE);
+            assert_eq!(supply(&accs, outcome_pk), PRICE_SCALE);
+        }
+        let contract = OmegaContract::load_from_bytes(&accs[&omega_contract_pk].data).unwrap();
+        assert_eq!(&contract.supplies[..2], &[PRICE_SCALE, PRICE_SCALE]);
+
+        // each bidder withdraws the outcome it bought
+        for i in 0..2 {
+            assert_eq!(process_with(settle(i, 0, false), &mut accs), Ok(()));
+            assert_eq!(balance(&accs, &book_pks[i].2), 0);
+            assert_eq!(balance(&accs, &wallets[i].2), PRICE_SCALE);
+            assert_eq!(OrderBook::load_from_bytes(&accs[&book_pks[i].0].data).unwrap().find(0), None);
+        }
+
+        // cancelling refunds the escrow
+        assert_eq!(process_with(bid(0, 3_000), &mut accs), Ok(()));
+        assert_eq!((balance(&accs, &book_pks[0].1), balance(&accs, &wallets[0].1)), (3_000, 1_000));
+        assert_eq!(process_with(settle(0, 1, true), &mut accs), Ok(()));
+        assert_eq!((balance(&accs, &book_pks[0].1), balance(&accs, &wallets[0].1)), (0, 4_000));
+        assert_eq!(OrderBook::load_from_bytes(&accs[&book_pks[0].0].data).unwrap().find(1), None);
+
+        // no new orders once the contract expired, in another mode or resolved
+        accs.insert(solana_program::sysvar::clock::ID, clock_account(&Clock { unix_timestamp: 1000, ..Clock::default() }));
+        assert!(process_with(bid(0, 1_000), &mut accs).is_err());
+        accs.insert(solana_program::sysvar::clock::ID, clock_account(&Clock { unix_timestamp: 999, ..Clock::default() }));
+        assert_eq!(process_with(bid(0, 1_000), &mut accs), Ok(()));
+
+        let contract = *OmegaContract::load_from_bytes(&accs[&omega_contract_pk].data).unwrap();
+        let bid_on = |contract: &OmegaContract| {
+            let mut accs = accs.clone();
+            accs.get_mut(&omega_contract_pk).unwrap().data.copy_from_slice(bytes_of(contract));
+            process_with(bid(0, 1_000), &mut accs)
+        };
+        let parimutuel = OmegaContract { mode: ContractMode::Parimutuel as u64, ..contract };
+        assert_eq!(bid_on(&parimutuel), Err(ProgramError::Custom(OmegaErrorCode::InvalidContractMode.into())));
+        let resolved = OmegaContract { winner: outcomes[0], ..contract };
+        assert!(bid_on(&resolved).is_err());
+    }
+
+    #[test]
+    fn test_lmsr_vault_covers_quantities() {
+        execute_token_cpi();
+        let program_id = Pubkey::new_unique();
+        let omega_contract_pk = Pubkey::new_unique();
+        let signer_pk = Pubkey::new_unique();
+        let quote_mint_pk = Pubkey::new_unique();
+        let vault_pk = Pubkey::new_unique();
+        let outcomes = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+        let (creator_pk, creator_quote_pk) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (user_pk, user_quote_pk) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let outcome_user_pks = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+        let liquidity = 1_000;
+
+        let mut omega_contract = OmegaContract::zeroed();
+        omega_contract.account_flags = (AccountFlag::Initialized | AccountFlag::OmegaContract).bits();
+        omega_contract.quote_mint = quote_mint_pk;
+        omega_contract.vault = vault_pk;
+        omega_contract.signer_key = signer_pk;
+        omega_contract.exp_time = 1000;
+        omega_contract.num_outcomes = 3;
+        omega_contract.outcomes[..3].copy_from_slice(&outcomes);
+
+        let mut accs = HashMap::new();
+        let mut omega_contract_acc = get_rent_exempt::<OmegaContract>(&program_id);
+        omega_contract_acc.data.copy_from_slice(bytes_of(&omega_contract));
+        accs.insert(omega_contract_pk, omega_contract_acc);
+        accs.insert(vault_pk, token_account(&quote_mint_pk, &signer_pk, 0));
+        accs.insert(creator_quote_pk, token_account(&quote_mint_pk, &creator_pk, 10_000));
+        accs.insert(user_quote_pk, token_account(&quote_mint_pk, &user_pk, 1_000_000));
+        accs.insert(solana_program::sysvar::clock::ID, clock_account(&Clock { unix_timestamp: 500, ..Clock::default() }));
+        for (outcome_pk, outcome_user_pk) in outcomes.iter().zip(outcome_user_pks.iter()) {
+            let mint = Mint { mint_authority: Some(signer_pk).into(), is_initialized: true, ..Mint::default() };
+            let mut mint_acc = Account::new(1, Mint::LEN, &spl_token::id());
+            Mint::pack(mint, &mut mint_acc.data).unwrap();
+            accs.insert(*outcome_pk, mint_acc);
+            accs.insert(*outcome_user_pk, token_account(outcome_pk, &user_pk, 0));
+        }
+
+        let balance = |accs: &HashMap<Pubkey, Account>, pk: &Pubkey| {
+            spl_token::state::Account::unpack(&accs[pk].data).unwrap().amount
+        };
+        let check_vault = |accs: &HashMap<Pubkey, Account>| {
+            let contract = OmegaContract::load_from_bytes(&accs[&omega_contract_pk].data).unwrap();
+            let max_quantity = contract.lmsr.quantities[..3].iter().copied().max().unwrap();
+            assert!(balance(accs, &vault_pk) >= max_quantity);
+        };
+
+        // the creator deposits the most the market maker can lose
+        let instruction = init_lmsr(
+            &program_id, &omega_contract_pk, &creator_pk, &creator_quote_pk, &vault_pk, liquidity
+        ).unwrap();
+        assert_eq!(process_with(instruction, &mut accs), Ok(()));
+        let max_loss = lmsr::max_loss(liquidity, 3);
+        assert_eq!((balance(&accs, &vault_pk), balance(&accs, &creator_quote_pk)), (max_loss, 10_000 - max_loss));
+        check_vault(&accs);
+
+        // then whatever users buy and sell back, the vault covers the outcome that would cost it the most
+        let buy = |i: usize, quantity: u64| lmsr_buy(
+            &program_id, &omega_contract_pk, &user_pk, &user_quote_pk, &vault_pk, &signer_pk, &outcomes[i],
+            &outcome_user_pks[i], &outcomes, i as u64, quantity, u64::MAX
+        ).unwrap();
+        let sell = |i: usize, quantity: u64| lmsr_sell(
+            &program_id, &omega_contract_pk, &user_pk, &user_quote_pk, &vault_pk, &signer_pk, &outcomes[i],
+            &outcome_user_pks[i], &outcomes, i as u64, quantity, 0
+        ).unwrap();
+        let trades = [
+            (0, 5_000, true), (1, 700, true), (0, 1, true), (0, 1, false), (2, 3, true), (0, 2_500, false),
+            (1, 1, true), (1, 1, true), (1, 702, false), (2, 20_000, true), (2, 20_003, false), (0, 2_500, false),
+        ];
+        for &(i, quantity, is_buy) in trades.iter() {
+            let instruction = if is_buy { buy(i, quantity) } else { sell(i, quantity) };
+            assert_eq!(process_with(instruction, &mut accs), Ok(()));
+            check_vault(&accs);
+        }
+
+        // with every outcome sold back, the vault keeps at least the deposit
+        let contract = OmegaContract::load_from_bytes(&accs[&omega_contract_pk].data).unwrap();
+        assert_eq!(&contract.lmsr.quantities[..3], &[0, 0, 0]);
+        assert!(balance(&accs, &vault_pk) >= max_loss);
+    }
+
+    #[test]
+    fn test_swap_outcome_lmsr() {
+        execute_token_cpi();
+        let program_id = Pubkey::new_unique();
+        let omega_contract_pk = Pubkey::new_unique();
+        let signer_pk = Pubkey::new_unique();
+        let quote_mint_pk = Pubkey::new_unique();
+        let vault_pk = Pubkey::new_unique();
+        let outcomes = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let (user_pk, user_quote_pk, outcome_user_pk) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let liquidity = 10_000;
+
+        let mut omega_contract = OmegaContract::zeroed();
+        omega_contract.account_flags = (AccountFlag::Initialized | AccountFlag::OmegaContract).bits();
+        omega_contract.quote_mint = quote_mint_pk;
+        omega_contract.vault = vault_pk;
+        omega_contract.signer_key = signer_pk;
+        omega_contract.exp_time = 1000;
+        omega_contract.num_outcomes = 2;
+        omega_contract.outcomes[..2].copy_from_slice(&outcomes);
+        omega_contract.lmsr.liquidity = liquidity;
+        omega_contract.lmsr.max_loss = lmsr::max_loss(liquidity, 2);
+
+        let mut accs = HashMap::new();
+        let mut omega_contract_acc = get_rent_exempt::<OmegaContract>(&program_id);
+        omega_contract_acc.data.copy_from_slice(bytes_of(&omega_contract));
+        accs.insert(omega_contract_pk, omega_contract_acc);
+        accs.insert(vault_pk, token_account(&quote_mint_pk, &signer_pk, omega_contract.lmsr.max_loss));
+        accs.insert(user_quote_pk, token_account(&quote_mint_pk, &user_pk, 100_000));
+        accs.insert(outcome_user_pk, token_account(&outcomes[0], &user_pk, 0));
+        accs.insert(solana_program::sysvar::clock::ID, clock_account(&Clock { unix_timestamp: 500, ..Clock::default() }));
+        for outcome_pk in outcomes.iter() {
+            let mint = Mint { mint_authority: Some(signer_pk).into(), is_initialized: true, ..Mint::default() };
+            let mut mint_acc = Account::new(1, Mint::LEN, &spl_token::id());
+            Mint::pack(mint, &mut mint_acc.data).unwrap();
+            accs.insert(*outcome_pk, mint_acc);
+        }
+
+        let balance = |accs: &HashMap<Pubkey, Account>, pk: &Pubkey| {
+            spl_token::state::Account::unpack(&accs[pk].data).unwrap().amount
+        };
+        let quantities = |accs: &HashMap<Pubkey, Account>| {
+            OmegaContract::load_from_bytes(&accs[&omega_contract_pk].data).unwrap().lmsr.quantities
+        };
+        // BuyOutcome and SellOutcome with the accounts of LmsrBuy and LmsrSell
+        let buy = |quantity: u64, max_cost: u64| Instruction {
+            data: OmegaInstruction::BuyOutcome { outcome: 0, quantity, max_cost }.pack(),
+            ..lmsr_buy(
+                &program_id, &omega_contract_pk, &user_pk, &user_quote_pk, &vault_pk, &signer_pk, &outcomes[0],
+                &outcome_user_pk, &outcomes, 0, quantity, max_cost
+            ).unwrap()
+        };
+        let sell = |quantity: u64, min_proceeds: u64| Instruction {
+            data: OmegaInstruction::SellOutcome { outcome: 0, quantity, min_proceeds }.pack(),
+            ..lmsr_sell(
+                &program_id, &omega_contract_pk, &user_pk, &user_quote_pk, &vault_pk, &signer_pk, &outcomes[0],
+                &outcome_user_pk, &outcomes, 0, quantity, min_proceeds
+            ).unwrap()
+        };
+
+        // buying pays the market maker's price, up to max_cost
+        let cost = lmsr::buy_cost(liquidity, &[0, 0], 0, 5_000).unwrap();
+        assert_eq!(process_with(buy(5_000, cost - 1), &mut accs), Err(ProgramError::Custom(OmegaErrorCode::SlippageExceeded.into())));
+        assert_eq!(process_with(buy(5_000, cost), &mut accs), Ok(()));
+        assert_eq!((balance(&accs, &user_quote_pk), balance(&accs, &outcome_user_pk)), (100_000 - cost, 5_000));
+        assert_eq!(balance(&accs, &vault_pk), omega_contract.lmsr.max_loss + cost);
+        assert_eq!(&quantities(&accs)[..2], &[5_000, 0]);
+
+        // selling receives the market maker's price, down to min_proceeds
+        let proceeds = lmsr::sell_proceeds(liquidity, &[5_000, 0], 0, 2_000).unwrap();
+        assert_eq!(process_with(sell(2_000, proceeds + 1), &mut accs), Err(ProgramError::Custom(OmegaErrorCode::SlippageExceeded.into())));
+        assert_eq!(process_with(sell(2_000, proceeds), &mut accs), Ok(()));
+        assert_eq!((balance(&accs, &user_quote_pk), balance(&accs, &outcome_user_pk)), (100_000 - cost + proceeds, 3_000));
+        assert_eq!(balance(&accs, &vault_pk), omega_contract.lmsr.max_loss + cost - proceeds);
+        assert_eq!(&quantities(&accs)[..2], &[3_000, 0]);
+    }
+
+}
@@ -1,4 +1,6 @@
 use std::cell::{Ref, RefMut};
+use std::cmp::{max, min};
+use std::convert::TryFrom;
 
 use solana_program::account_info::AccountInfo;
 use solana_program::program_error::ProgramError;
@@ -6,9 +8,47 @@ use solana_program::program_error::ProgramError;
 use bytemuck::{from_bytes, from_bytes_mut, Pod, Zeroable};
 use solana_program::pubkey::Pubkey;
 use enumflags2::BitFlags;
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+
+use crate::error::{OmegaErrorCode, OmegaResult};
+use crate::math;
 
 pub const DETAILS_BUFFER_LEN: usize = 2048;
+pub const EVIDENCE_URI_LEN: usize = 200;
 pub const MAX_OUTCOMES: usize = 8;
+pub const MAX_ORDERS: usize = 64;
+pub const MAX_BPS: u64 = 10_000;
+pub const MAX_REGISTRY_ENTRIES: usize = 256;
+pub const MAX_QUOTE_MINTS: usize = 8;
+pub const MAX_CREATORS: usize = 32;
+
+/// Seed of the ProgramConfig address
+pub const PROGRAM_CONFIG_SEED: &[u8] = b"config";
+
+/// Seed of OracleReputation addresses, followed by the oracle pubkey
+pub const ORACLE_REPUTATION_SEED: &[u8] = b"reputation";
+
+/// Seed of VoteRecord addresses, followed by the contract and voter pubkeys
+pub const VOTE_RECORD_SEED: &[u8] = b"vote";
+
+/// Seed of IssuanceRecord addresses, followed by the contract and user pubkeys
+pub const ISSUANCE_RECORD_SEED: &[u8] = b"issuance";
+
+/// Order prices are in basis points of one quote token, so a complete set is worth PRICE_SCALE.
+/// Order quantities must be a multiple of PRICE_SCALE so that every fill is an exact amount of quote.
+pub const PRICE_SCALE: u64 = 10_000;
+
+/// Seconds after auto_exp_time during which the program config admin can still resolve a contract
+pub const LATE_RESOLUTION_WINDOW: u64 = 7 * 24 * 60 * 60;
+
+/// Seconds after resolution during which the program config admin can find a contract ambiguous
+/// and forfeit its creator bond
+pub const CREATOR_DISPUTE_WINDOW: u64 = 3 * 24 * 60 * 60;
+
+/// Quote tokens paid for `quantity` outcome tokens at `price` basis points
+pub fn quote_amount(price: u64, quantity: u64) -> OmegaResult<u64> {
+    math::mul_div(price, quantity, PRICE_SCALE)
+}
 
 pub trait Loadable: Pod {
     fn load_mut<'a>(account: &'a AccountInfo) -> Result<RefMut<'a, Self>, ProgramError> {
@@ -29,6 +69,22 @@ pub trait Loadable: Pod {
 pub enum AccountFlag {
     Initialized = 1u64 << 0,
     OmegaContract = 1u64 << 1,
+    OrderBook = 1u64 << 2,
+    MarketSeries = 1u64 << 3,
+    Registry = 1u64 << 4,
+    ProgramConfig = 1u64 << 5,
+    OracleReputation = 1u64 << 6,
+    VoteRecord = 1u64 << 7,
+    IssuanceRecord = 1u64 << 8,
+}
+
+/// Things an oracle did on a contract that count towards its OracleReputation
+#[derive(Copy, Clone, BitFlags, Debug, Eq, PartialEq)]
+#[repr(u64)]
+pub enum OracleEvent {
+    Resolved = 1u64 << 0,
+    DisputeLost = 1u64 << 1,
+    MissedDeadline = 1u64 << 2,
 }
 
 
@@ -48,10 +104,738 @@ pub struct OmegaContract {
     pub winner: Pubkey,  // mint address of winning token. Will be 0 if not yet resolved
     pub outcomes: [Pubkey; MAX_OUTCOMES],
     pub num_outcomes: usize,
-    pub details: [u8; DETAILS_BUFFER_LEN]  // utf-8 encoded string (compressed?) of details about how to resolve contract
+    pub details: [u8; DETAILS_BUFFER_LEN],  // utf-8 encoded string (compressed?) of details about how to resolve contract
+    pub lmsr: LmsrState,  // optional market maker selling outcome tokens against the vault
+    pub mode: u64,  // ContractMode
+    pub parimutuel: ParimutuelState,
+    pub parent: Pubkey,  // contract whose outcome token is the quote currency of this one. Will be 0 if not conditional
+    pub parent_outcome: u64,  // index of that outcome in the parent contract
+    pub num_breakpoints: u64,  // outcome i of a bucket group wins if breakpoints[i - 1] <= value < breakpoints[i]
+    pub breakpoints: [i64; MAX_OUTCOMES - 1],
+    pub resolved_value: i64,  // value observed by the oracle when a bucket group is resolved
+    pub price_resolution: PriceResolution,
+    pub commitment: [u8; 32],  // hash of the winner and a salt committed by the oracle. Will be 0 if none
+    pub resolved_slot: u64,
+    pub resolved_time: u64,
+    pub evidence_hash: [u8; 32],  // hash of the content at evidence_uri the oracle based its resolution on
+    pub evidence_uri_len: u64,
+    pub evidence_uri: [u8; EVIDENCE_URI_LEN],  // utf-8 encoded
+    pub bond: OracleBond,
+    pub oracle_fee: OracleFee,
+    pub auto_exp_redemptions: u64,  // payouts made at the auto expiry rate. A late resolution needs this to be 0
+    pub liveness: OracleLiveness,
+    pub resolver: Pubkey,  // oracle key that resolved or committed. Will be 0 if none
+    pub resolution_delay: u64,  // seconds between exp_time and the resolution by `resolver`
+    pub oracle_events: u64,  // OracleEvent bits
+    pub recorded_events: u64,  // OracleEvent bits already counted in an OracleReputation
+    pub vote: VoteResolution,
+    pub creator_bond: CreatorBond,
+    pub allow_list_root: [u8; 32],  // merkle root of the pubkeys allowed to issue sets. Will be 0 if anyone can
+    pub max_supply: u64,  // max supply of each outcome at once. Will be 0 if uncapped
+    pub wallet_cap: u64,  // max sets one user can ever issue. Will be 0 if uncapped
+    pub supplies: [u64; MAX_OUTCOMES],  // outcome tokens the program minted minus those it burned
+    pub stats: ContractStats,
+}
+
+impl OmegaContract {
+    /// Sets outstanding: the largest outcome supply, since any outcome can still win
+    pub fn open_interest(&self) -> u64 {
+        self.supplies[..self.num_outcomes].iter().copied().max().unwrap_or(0)
+    }
+
+    /// Count `quantity` new tokens of outcome `i`, which may not take its supply over max_supply
+    pub fn add_supply(&mut self, i: usize, quantity: u64) -> OmegaResult<()> {
+        let supply = math::add(self.supplies[i], quantity)?;
+        if self.max_supply != 0 && supply > self.max_supply {
+            return Err(OmegaErrorCode::IssuanceCapExceeded.into());
+        }
+        self.supplies[i] = supply;
+        Ok(())
+    }
+
+    pub fn sub_supply(&mut self, i: usize, quantity: u64) -> OmegaResult<()> {
+        self.supplies[i] = math::sub(self.supplies[i], quantity)?;
+        Ok(())
+    }
+
+    /// Count `quantity` new tokens of every outcome
+    pub fn add_sets(&mut self, quantity: u64) -> OmegaResult<()> {
+        (0..self.num_outcomes).try_for_each(|i| self.add_supply(i, quantity))
+    }
+
+    pub fn sub_sets(&mut self, quantity: u64) -> OmegaResult<()> {
+        (0..self.num_outcomes).try_for_each(|i| self.sub_supply(i, quantity))
+    }
+
+    /// Index of the outcome with mint `mint`
+    pub fn outcome_index(&self, mint: &Pubkey) -> OmegaResult<usize> {
+        Ok(self.outcomes[..self.num_outcomes].iter().position(|pk| pk == mint).ok_or(OmegaErrorCode::InvalidOutcome)?)
+    }
+
+    /// Set the winner and record when the contract was resolved
+    pub fn set_winner(&mut self, winner: Pubkey, slot: u64, timestamp: u64) {
+        self.winner = winner;
+        self.resolved_slot = slot;
+        self.resolved_time = timestamp;
+    }
+
+    /// Set the winner picked by `resolver`, one of the oracles of this contract
+    pub fn set_oracle_winner(&mut self, winner: Pubkey, resolver: Pubkey, slot: u64, timestamp: u64) {
+        self.set_winner(winner, slot, timestamp);
+        self.resolver = resolver;
+        self.resolution_delay = timestamp.saturating_sub(self.exp_time);
+        self.oracle_events |= OracleEvent::Resolved as u64;
+    }
+
+    /// Oracle key an OracleEvent counts against
+    pub fn oracle_for_event(&self, event: OracleEvent) -> Pubkey {
+        match event {
+            OracleEvent::MissedDeadline if self.resolver == Pubkey::default() => {
+                if self.vote.is_enabled() { Pubkey::default() } else { self.resolving_oracle(self.auto_exp_time) }
+            }
+            _ => self.resolver
+        }
+    }
+
+    /// Key with resolution rights at `curr_time`: the backup oracle once the oracle missed a heartbeat
+    pub fn resolving_oracle(&self, curr_time: u64) -> Pubkey {
+        if self.liveness.backup_oracle != Pubkey::default() && self.liveness.is_lapsed(curr_time) {
+            self.liveness.backup_oracle
+        } else {
+            self.oracle
+        }
+    }
+
+    /// Auto expired without a winner and too late to be resolved by the program config admin
+    pub fn is_resolved_invalid(&self, curr_time: u64) -> bool {
+        self.winner == Pubkey::default() && curr_time >= self.auto_exp_time.saturating_add(LATE_RESOLUTION_WINDOW)
+    }
+
+    pub fn evidence_uri(&self) -> &[u8] {
+        &self.evidence_uri[..self.evidence_uri_len as usize]
+    }
+
+    /// Index of the bucket containing `value`
+    pub fn bucket(&self, value: i64) -> usize {
+        self.breakpoints[..self.num_breakpoints as usize].iter().filter(|&&b| value >= b).count()
+    }
+}
+
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[repr(u64)]
+pub enum ContractMode {
+    CompleteSet = 0,  // one quote token issues one of each outcome token
+    Parimutuel = 1,  // one quote token staked on an outcome issues one ticket of that outcome
 }
 
 
+#[derive(Copy, Clone, Debug, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[repr(u64)]
+pub enum Comparison {
+    GreaterThan = 0,
+    GreaterOrEqual = 1,
+    LessThan = 2,
+    LessOrEqual = 3,
+}
+
+impl Comparison {
+    pub fn holds(self, price: i64, threshold: i64) -> bool {
+        match self {
+            Comparison::GreaterThan => price > threshold,
+            Comparison::GreaterOrEqual => price >= threshold,
+            Comparison::LessThan => price < threshold,
+            Comparison::LessOrEqual => price <= threshold,
+        }
+    }
+}
+
+
+/// Binary contract resolved from a price account: outcome 0 wins if `price comparison threshold` holds
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct PriceResolution {
+    pub price_acc: Pubkey,  // Will be 0 if the contract is not resolved from a price
+    pub price_program: Pubkey,  // owner of price_acc
+    pub source: u64,  // price::PriceSourceKind describing the layout of price_acc
+    pub comparison: u64,  // Comparison
+    pub threshold: i64,  // in the units of the price account
+    pub max_staleness: u64,  // the price must be published within this many seconds of exp_time
+}
+
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[repr(u64)]
+pub enum BondStatus {
+    None = 0,
+    Posted = 1,
+    Released = 2,
+    Slashed = 3,
+}
+
+
+/// Quote tokens the oracle puts at stake on resolving the contract correctly and on time
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct OracleBond {
+    pub escrow: Pubkey,  // token account owned by the contract signer
+    pub amount: u64,
+    pub dispute_window: u64,  // seconds after resolution during which the winner can be overturned
+    pub status: u64,  // BondStatus
+    pub slashed_supply: u64,  // outcome tokens the slashed bond is split over
+}
+
+impl OracleBond {
+    /// The bond is at stake until the dispute window after resolution has passed
+    pub fn is_at_stake(&self, resolved_time: u64, curr_time: u64) -> bool {
+        self.status == BondStatus::Posted as u64 && curr_time < resolved_time.saturating_add(self.dispute_window)
+    }
+
+    /// Part of a slashed bond paid out when redeeming `quantity` outcome tokens, never more than the bond
+    pub fn share(&self, quantity: u64) -> OmegaResult<u64> {
+        if self.status != BondStatus::Slashed as u64 || self.slashed_supply == 0 {
+            return Ok(0);
+        }
+        Ok(min(math::mul_div(quantity, self.amount, self.slashed_supply)?, self.amount))
+    }
+}
+
+
+/// Quote tokens the creator puts at stake on the contract being resolvable, set by the program config
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct CreatorBond {
+    pub creator: Pubkey,
+    pub escrow: Pubkey,  // token account owned by the contract signer
+    pub amount: u64,
+    pub status: u64,  // BondStatus
+}
+
+/// Activity on the contract, so that bots can see what is at stake in one account fetch
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct ContractStats {
+    pub sets_issued: u64,
+    pub sets_redeemed: u64,
+    pub winner_redeemed: u64,  // winning tokens, or any tokens at the auto expiry rate, turned into quote tokens
+    pub unique_issuers: u64,  // users with an IssuanceRecord
+    pub last_activity: u64,  // timestamp of the last issuance or redemption
+    pub outcomes_issued: u64,  // single outcome tokens sold by the LMSR market maker or staked in the parimutuel pool
+    pub outcomes_redeemed: u64,  // single outcome tokens bought back by the LMSR market maker
+}
+
+/// What an instruction did to the supply of a contract, counted in its ContractStats
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Activity {
+    SetsIssued,
+    SetsRedeemed,
+    OutcomesIssued,
+    OutcomesRedeemed,
+    WinnerRedeemed,
+}
+
+impl ContractStats {
+    pub fn record(&mut self, activity: Activity, quantity: u64, curr_time: u64) -> OmegaResult<()> {
+        let counter = match activity {
+            Activity::SetsIssued => &mut self.sets_issued,
+            Activity::SetsRedeemed => &mut self.sets_redeemed,
+            Activity::OutcomesIssued => &mut self.outcomes_issued,
+            Activity::OutcomesRedeemed => &mut self.outcomes_redeemed,
+            Activity::WinnerRedeemed => &mut self.winner_redeemed,
+        };
+        *counter = math::add(*counter, quantity)?;
+        self.last_activity = curr_time;
+        Ok(())
+    }
+}
+
+
+/// Oracle check-ins that hand resolution rights to a backup oracle when they stop
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct OracleLiveness {
+    pub backup_oracle: Pubkey,  // Will be 0 if there is no backup
+    pub heartbeat_period: u64,  // max seconds between two heartbeats of the oracle
+    pub last_heartbeat: u64,
+}
+
+impl OracleLiveness {
+    pub fn is_lapsed(&self, curr_time: u64) -> bool {
+        curr_time > self.last_heartbeat.saturating_add(self.heartbeat_period)
+    }
+}
+
+
+/// Reward for resolving on time, funded into the vault on top of what holders are owed
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct OracleFee {
+    pub funder: Pubkey,  // gets back whatever the oracle did not earn
+    pub amount: u64,  // fixed reward, or the cap on the reward if fee_bps is set
+    pub fee_bps: u64,  // reward as basis points of open interest. Will be 0 for a fixed reward
+    pub deadline: u64,  // the reward shrinks linearly from exp_time to 0 at the deadline
+    pub reserve: u64,  // quote tokens in the vault not yet paid out or returned
+}
+
+impl OracleFee {
+    /// Reward for resolving at `curr_time` with `open_interest` quote tokens of collateral in the vault
+    pub fn reward(&self, exp_time: u64, curr_time: u64, open_interest: u64) -> OmegaResult<u64> {
+        if curr_time >= self.deadline || self.deadline <= exp_time {
+            return Ok(0);
+        }
+        let full = if self.fee_bps == 0 {
+            self.amount
+        } else {
+            min(self.amount, math::mul_div(open_interest, self.fee_bps, MAX_BPS)?)
+        };
+        let remaining = math::sub(self.deadline, max(curr_time, exp_time))?;
+        let reward = math::mul_div(full, remaining, math::sub(self.deadline, exp_time)?)?;
+        Ok(min(reward, self.reserve))
+    }
+}
+
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[repr(u64)]
+pub enum TieBreak {
+    NoWinner = 0,  // a tie leaves the contract to auto expire
+    LowestIndex = 1,  // the tied outcome with the lowest index wins
+    Designated = 2,  // tie_break_outcome wins any tie
+}
+
+
+/// Resolution by holders of a governance mint instead of an oracle. Votes are weighted by the
+/// governance tokens escrowed with them during the voting period after exp_time
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct VoteResolution {
+    pub gov_mint: Pubkey,  // Will be 0 if the contract is resolved by an oracle
+    pub escrow: Pubkey,  // gov_mint token account owned by the contract signer
+    pub voting_period: u64,
+    pub quorum: u64,  // min governance tokens voted in total for the vote to pick a winner
+    pub tie_break: u64,  // TieBreak
+    pub tie_break_outcome: u64,
+    pub total_votes: u64,
+    pub tallies: [u64; MAX_OUTCOMES],  // governance tokens voted for each outcome
+}
+
+impl VoteResolution {
+    pub fn is_enabled(&self) -> bool {
+        self.gov_mint != Pubkey::default()
+    }
+
+    pub fn voting_end(&self, exp_time: u64) -> u64 {
+        exp_time.saturating_add(self.voting_period)
+    }
+
+    /// Index of the winning outcome, or None if the quorum is not met or a tie leaves no winner
+    pub fn winner(&self, num_outcomes: usize) -> Option<usize> {
+        if self.total_votes == 0 || self.total_votes < self.quorum {
+            return None;
+        }
+        let tallies = &self.tallies[..num_outcomes];
+        let most = *tallies.iter().max()?;
+        let mut tied = (0..num_outcomes).filter(|&i| tallies[i] == most);
+        let first = tied.next()?;
+        if tied.next().is_none() {
+            return Some(first);
+        }
+        match TieBreak::try_from(self.tie_break).ok()? {
+            TieBreak::NoWinner => None,
+            TieBreak::LowestIndex => Some(first),
+            TieBreak::Designated => Some(self.tie_break_outcome as usize),
+        }
+    }
+}
+
+
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct LmsrState {
+    pub creator: Pubkey,  // funded the market maker and receives the vault surplus after resolution
+    pub liquidity: u64,  // the LMSR `b` parameter. Will be 0 if LMSR mode is not enabled
+    pub max_loss: u64,  // b * ln(num_outcomes) quote tokens deposited by the creator
+    pub quantities: [u64; MAX_OUTCOMES],  // outcome tokens currently sold by the market maker
+}
+
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct ParimutuelState {
+    pub fee_acc: Pubkey,  // quote currency wallet receiving the fee
+    pub fee_bps: u64,  // fee on the whole pot in basis points
+    pub pools: [u64; MAX_OUTCOMES],  // quote tokens staked on each outcome
+}
+
 unsafe impl Zeroable for OmegaContract {}
 unsafe impl Pod for OmegaContract {}
 impl Loadable for OmegaContract {}
+
+
+/// Template for a recurring contract, e.g. "ETH above X at 00:00 UTC" every day
+/// Contract `index` of the series expires at first_exp_time + index * period
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct MarketSeries {
+    pub account_flags: u64,
+    pub oracle: Pubkey,
+    pub quote_mint: Pubkey,
+    pub num_outcomes: u64,
+    pub first_exp_time: u64,  // expiration timestamp of the first contract in the series
+    pub period: u64,  // seconds between the expirations of consecutive contracts
+    pub resolve_window: u64,  // seconds from exp_time to auto_exp_time of each contract
+    pub next_index: u64,  // index of the next contract to be created
+    pub details_len: u64,
+    pub details: [u8; DETAILS_BUFFER_LEN],  // details template, see MarketSeries::details_for
+}
+
+impl MarketSeries {
+    /// Expiration and auto expiration timestamps of contract `index`
+    pub fn exp_times(&self, index: u64) -> Option<(u64, u64)> {
+        let exp_time = index.checked_mul(self.period)?.checked_add(self.first_exp_time)?;
+        Some((exp_time, exp_time.checked_add(self.resolve_window)?))
+    }
+
+    /// Details of contract `index` with the placeholders {index}, {exp_time} (unix timestamp)
+    /// and {exp_date} (YYYY-MM-DD in UTC) filled in
+    pub fn details_for(&self, index: u64, exp_time: u64) -> OmegaResult<Vec<u8>> {
+        let template = &self.details[..self.details_len as usize];
+        let placeholders: [(&[u8], String); 3] = [
+            (b"{index}", index.to_string()),
+            (b"{exp_time}", exp_time.to_string()),
+            (b"{exp_date}", format_date(exp_time)?),
+        ];
+
+        let mut details = Vec::with_capacity(template.len());
+        let mut i = 0;
+        'outer: while i < template.len() {
+            for (placeholder, value) in placeholders.iter() {
+                if template[i..].starts_with(placeholder) {
+                    details.extend_from_slice(value.as_bytes());
+                    i += placeholder.len();
+                    continue 'outer;
+                }
+            }
+            details.push(template[i]);
+            i += 1;
+        }
+        Ok(details)
+    }
+}
+
+/// YYYY-MM-DD of a unix timestamp in UTC
+fn format_date(timestamp: u64) -> OmegaResult<String> {
+    use math::{add, div, mul, sub};
+
+    // days since 1970-01-01 to civil date, counting years from March so leap days come last
+    let days = add(div(timestamp, 86400)?, 719_468)?;
+    let era = div(days, 146_097)?;
+    let doe = sub(days, mul(era, 146_097)?)?;
+    let yoe = div(sub(add(sub(doe, div(doe, 1460)?)?, div(doe, 36524)?)?, div(doe, 146_096)?)?, 365)?;
+    let doy = sub(doe, sub(add(mul(365, yoe)?, div(yoe, 4)?)?, div(yoe, 100)?)?)?;
+    let mp = div(add(mul(5, doy)?, 2)?, 153)?;
+    let day = add(sub(doy, div(add(mul(153, mp)?, 2)?, 5)?)?, 1)?;
+    let month = if mp < 10 { add(mp, 3)? } else { sub(mp, 9)? };
+    let year = add(add(mul(era, 400)?, yoe)?, if month <= 2 { 1 } else { 0 })?;
+    Ok(format!("{:04}-{:02}-{:02}", year, month, day))
+}
+
+unsafe impl Zeroable for MarketSeries {}
+unsafe impl Pod for MarketSeries {}
+impl Loadable for MarketSeries {}
+
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[repr(u64)]
+pub enum ContractStatus {
+    Open = 0,
+    Resolved = 1,
+}
+
+
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct RegistryEntry {
+    pub contract: Pubkey,
+    pub oracle: Pubkey,
+    pub category: u64,  // free-form tag picked by the oracle, 0 if uncategorized
+    pub created_at: u64,  // timestamp in seconds when the contract was registered
+    pub status: u64,  // ContractStatus as of the last UpdateRegistryEntry
+}
+
+/// List of the contracts of one creator, used by frontends and bots to discover markets
+/// Contracts are appended by InitOmegaContract and removed by CloseContract
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct Registry {
+    pub account_flags: u64,
+    pub creator: Pubkey,  // only contracts made by this creator are listed, and it gets their rent back on close
+    pub num_entries: u64,
+    pub entries: [RegistryEntry; MAX_REGISTRY_ENTRIES],
+}
+
+impl Registry {
+    pub fn entries(&self) -> &[RegistryEntry] {
+        &self.entries[..self.num_entries as usize]
+    }
+
+    pub fn find(&self, contract: &Pubkey) -> Option<usize> {
+        self.entries().iter().position(|entry| entry.contract == *contract)
+    }
+
+    /// Append an entry. A full registry drops its oldest resolved entry to make room
+    /// Returns false if the registry is full of open contracts
+    pub fn push(&mut self, entry: RegistryEntry) -> bool {
+        if self.num_entries as usize == MAX_REGISTRY_ENTRIES {
+            match self.entries().iter().position(|e| e.status == ContractStatus::Resolved as u64) {
+                Some(i) => self.remove(i),
+                None => return false,
+            }
+        }
+        let n = self.num_entries as usize;
+        self.entries[n] = entry;
+        self.num_entries = (n + 1) as u64;
+        true
+    }
+
+    /// Remove an entry, keeping the others in the order they were added
+    pub fn remove(&mut self, i: usize) {
+        let n = self.num_entries as usize;
+        self.entries.copy_within(i + 1..n, i);
+        self.num_entries = (n - 1) as u64;
+    }
+}
+
+unsafe impl Zeroable for RegistryEntry {}
+unsafe impl Pod for RegistryEntry {}
+
+unsafe impl Zeroable for Registry {}
+unsafe impl Pod for Registry {}
+impl Loadable for Registry {}
+
+
+/// Settings of the whole program, changed by the admin through ProposeConfig and ApplyConfig
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct ConfigSettings {
+    pub admin: Pubkey,
+    pub timelock: u64,  // seconds between ProposeConfig and ApplyConfig
+    pub fee_bps: u64,  // default oracle fee rate in basis points, for contracts funded without one
+    pub num_quote_mints: u64,  // any quote mint is allowed if 0
+    pub quote_mints: [Pubkey; MAX_QUOTE_MINTS],
+    pub restrict_creators: u64,  // only creators in `creators` may make markets if nonzero
+    pub num_creators: u64,
+    pub creators: [Pubkey; MAX_CREATORS],
+    pub creator_bond: u64,  // quote tokens posted by the creator of each contract. Will be 0 if none
+    pub treasury: Pubkey,  // owner of the quote token accounts forfeited creator bonds are paid to
+}
+
+impl ConfigSettings {
+    pub fn is_quote_mint_allowed(&self, quote_mint: &Pubkey) -> bool {
+        self.num_quote_mints == 0 || self.quote_mints[..self.num_quote_mints as usize].contains(quote_mint)
+    }
+
+    pub fn is_creator_allowed(&self, creator: &Pubkey) -> bool {
+        self.restrict_creators == 0 || self.creators[..self.num_creators as usize].contains(creator)
+    }
+}
+
+unsafe impl Zeroable for ConfigSettings {}
+unsafe impl Pod for ConfigSettings {}
+
+/// Track record of one oracle, at the address derived from ORACLE_REPUTATION_SEED and the oracle
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct OracleReputation {
+    pub account_flags: u64,
+    pub oracle: Pubkey,
+    pub nonce: u64,
+    pub num_resolved: u64,
+    pub total_delay: u64,  // sum of seconds between exp_time and resolution over resolved contracts
+    pub disputes_lost: u64,
+    pub auto_expiries: u64,  // contracts the oracle did not resolve before auto_exp_time
+}
+
+impl OracleReputation {
+    /// Average seconds between exp_time and resolution
+    pub fn average_delay(&self) -> u64 {
+        self.total_delay.checked_div(self.num_resolved).unwrap_or(0)
+    }
+
+    pub fn record(&mut self, event: OracleEvent, delay: u64) -> OmegaResult<()> {
+        match event {
+            OracleEvent::Resolved => {
+                self.num_resolved = math::add(self.num_resolved, 1)?;
+                self.total_delay = math::add(self.total_delay, delay)?;
+            }
+            OracleEvent::DisputeLost => self.disputes_lost = math::add(self.disputes_lost, 1)?,
+            OracleEvent::MissedDeadline => self.auto_expiries = math::add(self.auto_expiries, 1)?,
+        }
+        Ok(())
+    }
+}
+
+unsafe impl Zeroable for OracleReputation {}
+unsafe impl Pod for OracleReputation {}
+impl Loadable for OracleReputation {}
+
+/// Governance tokens one voter escrowed on a contract, at the address derived from VOTE_RECORD_SEED,
+/// the contract and the voter
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct VoteRecord {
+    pub account_flags: u64,
+    pub omega_contract: Pubkey,
+    pub voter: Pubkey,
+    pub nonce: u64,
+    pub outcome: u64,
+    pub amount: u64,
+}
+
+unsafe impl Zeroable for VoteRecord {}
+unsafe impl Pod for VoteRecord {}
+impl Loadable for VoteRecord {}
+
+/// Sets one user issued on a contract, at the address derived from ISSUANCE_RECORD_SEED,
+/// the contract and the user
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct IssuanceRecord {
+    pub account_flags: u64,
+    pub omega_contract: Pubkey,
+    pub user: Pubkey,
+    pub nonce: u64,
+    pub issued: u64,
+}
+
+unsafe impl Zeroable for IssuanceRecord {}
+unsafe impl Pod for IssuanceRecord {}
+impl Loadable for IssuanceRecord {}
+
+/// Singleton at the address derived from PROGRAM_CONFIG_SEED
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct ProgramConfig {
+    pub account_flags: u64,
+    pub nonce: u64,
+    pub settings: ConfigSettings,
+    pub pending: ConfigSettings,  // proposed settings, only valid if apply_time is nonzero
+    pub apply_time: u64,  // earliest timestamp at which the pending settings can be applied
+}
+
+unsafe impl Zeroable for ProgramConfig {}
+unsafe impl Pod for ProgramConfig {}
+impl Loadable for ProgramConfig {}
+
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[repr(u64)]
+pub enum Side {
+    Bid = 0,
+    Ask = 1,
+}
+
+
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct Order {
+    pub owner: Pubkey,  // Will be 0 if this slot is free
+    pub order_id: u64,
+    pub side: u64,
+    pub price: u64,  // in basis points of one quote token
+    pub quantity: u64,  // outcome tokens still open on the book
+    pub quote_free: u64,  // quote tokens in the quote escrow owed to the owner
+    pub outcome_free: u64,  // outcome tokens in the outcome escrow owed to the owner
+}
+
+impl Order {
+    pub fn is_free(&self) -> bool {
+        self.owner == Pubkey::default()
+    }
+
+    pub fn is_open(&self, side: Side) -> bool {
+        !self.is_free() && self.quantity != 0 && self.side == side as u64
+    }
+}
+
+
+/// Limit orders for one outcome of an OmegaContract
+/// Bids escrow quote tokens in `quote_escrow` and asks escrow outcome tokens in `outcome_escrow`.
+/// Both escrows are owned by the contract's signer key. Fills only move balances inside the book,
+/// owners withdraw them with SettleOrder or CancelOrder.
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct OrderBook {
+    pub account_flags: u64,
+    pub omega_contract: Pubkey,
+    pub outcome_mint: Pubkey,
+    pub quote_escrow: Pubkey,
+    pub outcome_escrow: Pubkey,
+    pub next_order_id: u64,
+    pub orders: [Order; MAX_ORDERS],
+}
+
+impl OrderBook {
+    /// Index of the open order with the best price on `side`, oldest first for equal prices
+    pub fn best(&self, side: Side) -> Option<usize> {
+        let mut best: Option<usize> = None;
+        for (i, order) in self.orders.iter().enumerate() {
+            if !order.is_open(side) {
+                continue;
+            }
+            best = match best {
+                None => Some(i),
+                Some(j) => {
+                    let other = &self.orders[j];
+                    let better = match side {
+                        Side::Bid => order.price > other.price,
+                        Side::Ask => order.price < other.price,
+                    };
+                    if better || (order.price == other.price && order.order_id < other.order_id) {
+                        Some(i)
+                    } else {
+                        Some(j)
+                    }
+                }
+            };
+        }
+        best
+    }
+
+    /// Fill `quantity` against the resting orders on `side`, best price first, and credit their owners
+    /// Returns the quote tokens exchanged, or None if the book does not have enough liquidity
+    pub fn take(&mut self, side: Side, quantity: u64) -> OmegaResult<Option<u64>> {
+        let mut remaining = quantity;
+        let mut total = 0;
+        while remaining > 0 {
+            let order = match self.best(side) {
+                Some(i) => &mut self.orders[i],
+                None => return Ok(None),
+            };
+            let fill = order.quantity.min(remaining);
+            let amount = quote_amount(order.price, fill)?;
+            order.quantity = math::sub(order.quantity, fill)?;
+            match side {
+                Side::Bid => order.outcome_free = math::add(order.outcome_free, fill)?,
+                Side::Ask => order.quote_free = math::add(order.quote_free, amount)?,
+            }
+            remaining = math::sub(remaining, fill)?;
+            total = math::add(total, amount)?;
+        }
+        Ok(Some(total))
+    }
+
+    pub fn find(&self, order_id: u64) -> Option<usize> {
+        self.orders.iter().position(|o| !o.is_free() && o.order_id == order_id)
+    }
+
+    pub fn free_slot(&self) -> Option<usize> {
+        self.orders.iter().position(|o| o.is_free())
+    }
+}
+
+unsafe impl Zeroable for Order {}
+unsafe impl Pod for Order {}
+
+unsafe impl Zeroable for OrderBook {}
+unsafe impl Pod for OrderBook {}
+impl Loadable for OrderBook {}
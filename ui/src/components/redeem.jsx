@@ -94,7 +94,7 @@ function encodeInstructionData(layout, args) {
 
 function RedeemSetInstruction(omegaContract, user, userQuote, vault, omegaSigner, outcomePks, quantity) {
   let keys = [
-    { pubkey: omegaContract, isSigner: false, isWritable: false },
+    { pubkey: omegaContract, isSigner: false, isWritable: true },
     { pubkey: user, isSigner: true, isWritable: false },
     { pubkey: userQuote, isSigner: false, isWritable: true },
     { pubkey: vault, isSigner: false, isWritable: true },
@@ -105,6 +105,7 @@ function RedeemSetInstruction(omegaContract, user, userQuote, vault, omegaSigner
   for (var i = 0; i < outcomePks.length; i++) {
     keys.push({pubkey: outcomePks[i], isSigner: false, isWritable: true});
   }
+  keys.push({pubkey: SYSVAR_CLOCK_PUBKEY, isSigner: false, isWritable: false});
 
   const data = encodeInstructionData(instructionLayout, {
     instruction: IC_REDEEM_SET,
@@ -116,7 +117,7 @@ function RedeemSetInstruction(omegaContract, user, userQuote, vault, omegaSigner
 
 function RedeemWinnerInstruction(omegaContract, user, userQuote, vault, omegaSigner, winnerMint, winnerWallet, quantity) {
   let keys = [
-    { pubkey: omegaContract, isSigner: false, isWritable: false },
+    { pubkey: omegaContract, isSigner: false, isWritable: true },
     { pubkey: user, isSigner: true, isWritable: false },
     { pubkey: userQuote, isSigner: false, isWritable: true },
     { pubkey: vault, isSigner: false, isWritable: true },
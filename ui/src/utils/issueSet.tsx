@@ -7,6 +7,7 @@ import {
   TransactionInstruction,
   PublicKey,
   Connection,
+  SYSVAR_CLOCK_PUBKEY,
   Transaction
 } from '@solana/web3.js';
 // Our program id
@@ -34,7 +35,7 @@ const QUOTE_CURRENCY_MINT = new PublicKey(contract_keys.quote_mint_pk);
 
 function IssueSetInstruction(omegaContract: any, user: any, userQuote: any, vault: any, omegaSigner: any, outcomePks: any, quantity: any) {
   let keys = [
-    { pubkey: omegaContract, isSigner: false, isWritable: false },
+    { pubkey: omegaContract, isSigner: false, isWritable: true },
     { pubkey: user, isSigner: true, isWritable: false },
     { pubkey: userQuote, isSigner: false, isWritable: true },
     { pubkey: vault, isSigner: false, isWritable: true },
@@ -45,6 +46,7 @@ function IssueSetInstruction(omegaContract: any, user: any, userQuote: any, vaul
   for (var i = 0; i < outcomePks.length; i++) {
     keys.push({pubkey: outcomePks[i], isSigner: false, isWritable: true});
   }
+  keys.push({pubkey: SYSVAR_CLOCK_PUBKEY, isSigner: false, isWritable: false});
 
   const data = encodeInstructionData(instructionLayout, {
     instruction: IC_ISSUE_SET,